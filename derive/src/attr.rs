@@ -1,12 +1,17 @@
+use crate::case::RenameRule;
 use syn::{
     Attribute,
+    Data,
     DeriveInput,
+    Error,
     Field,
     Lit,
+    LitStr,
     Meta,
-    MetaList,
-    MetaNameValue,
     NestedMeta,
+    Path,
+    Result,
+    Variant,
 };
 
 pub(crate) enum DeriveProvider {
@@ -14,53 +19,291 @@ pub(crate) enum DeriveProvider {
     Serde,
 }
 
-pub(crate) fn derive_provider(input: &DeriveInput) -> DeriveProvider {
-    for list in input.attrs.iter().filter_map(sval_attr) {
-        for meta in list.nested {
-            match meta {
-                NestedMeta::Meta(Meta::NameValue(MetaNameValue {
-                    ref ident,
-                    lit: Lit::Str(ref s),
-                    ..
-                })) if ident == "derive_from" && s.value() == "serde" => {
-                    return DeriveProvider::Serde;
+/**
+The way enum variants are represented.
+*/
+pub(crate) enum Tagging {
+    /**
+    Use the variant events of the stream.
+    */
+    External,
+    /**
+    A map with the variant name as an entry alongside its fields.
+    */
+    Internal { tag: String },
+    /**
+    A map with the variant name and its content as separate entries.
+    */
+    Adjacent { tag: String, content: String },
+    /**
+    Just the variant content.
+    */
+    Untagged,
+}
+
+/**
+Attributes that can be applied to structs and enums.
+*/
+pub(crate) struct ContainerAttrs {
+    pub(crate) provider: DeriveProvider,
+    pub(crate) rename_all: Option<RenameRule>,
+    pub(crate) tagging: Tagging,
+}
+
+pub(crate) fn container_attrs(input: &DeriveInput) -> Result<ContainerAttrs> {
+    let mut provider = None;
+    let mut rename_all = None;
+    let mut tag = None;
+    let mut content = None;
+    let mut untagged = None;
+
+    for meta in sval_metas(&input.attrs)? {
+        match meta {
+            NestedMeta::Meta(Meta::NameValue(ref value)) if value.ident == "derive_from" => {
+                let s = lit_str(&value.lit)?;
+
+                if s.value() != "serde" {
+                    return Err(Error::new_spanned(s, "expected `serde`"));
                 }
-                _ => panic!("unsupported attribute"),
+
+                set(&mut provider, &meta, meta.clone())?;
+            }
+            NestedMeta::Meta(Meta::NameValue(ref value)) if value.ident == "rename_all" => {
+                let s = lit_str(&value.lit)?;
+                let rule = RenameRule::from_str(&s.value()).ok_or_else(|| {
+                    Error::new_spanned(
+                        s,
+                        "unsupported rule, expected one of `lowercase`, `UPPERCASE`, \
+                         `PascalCase`, `camelCase`, `snake_case`, `SCREAMING_SNAKE_CASE`, \
+                         `kebab-case` or `SCREAMING-KEBAB-CASE`",
+                    )
+                })?;
+
+                set(&mut rename_all, &meta, rule)?;
             }
+            NestedMeta::Meta(Meta::NameValue(ref value)) if value.ident == "tag" => {
+                let tag_value = lit_str(&value.lit)?.value();
+
+                set(&mut tag, &meta, (tag_value, meta.clone()))?;
+            }
+            NestedMeta::Meta(Meta::NameValue(ref value)) if value.ident == "content" => {
+                let content_value = lit_str(&value.lit)?.value();
+
+                set(&mut content, &meta, (content_value, meta.clone()))?;
+            }
+            NestedMeta::Meta(Meta::Word(ref ident)) if ident == "untagged" => {
+                set(&mut untagged, &meta, meta.clone())?;
+            }
+            _ => return Err(unsupported(&meta)),
+        }
+    }
+
+    let tagging_meta = tag
+        .as_ref()
+        .map(|(_, meta)| meta.clone())
+        .or_else(|| untagged.clone());
+
+    let tagging = match (tag, content, untagged) {
+        (None, None, None) => Tagging::External,
+        (Some((tag, _)), None, None) => Tagging::Internal { tag },
+        (Some((tag, _)), Some((content, _)), None) => Tagging::Adjacent { tag, content },
+        (None, None, Some(_)) => Tagging::Untagged,
+        (None, Some((_, meta)), None) => {
+            return Err(Error::new_spanned(meta, "`content` requires a `tag`"));
+        }
+        (_, _, Some(meta)) => {
+            return Err(Error::new_spanned(
+                meta,
+                "`untagged` can't be combined with `tag` or `content`",
+            ));
+        }
+    };
+
+    if let Some(ref meta) = provider {
+        let is_external = match tagging {
+            Tagging::External => true,
+            _ => false,
+        };
+
+        if rename_all.is_some() || !is_external {
+            return Err(Error::new_spanned(
+                meta,
+                "`derive_from` can't be combined with other attributes",
+            ));
         }
     }
 
-    DeriveProvider::Sval
+    if let Some(meta) = tagging_meta {
+        let is_enum = match input.data {
+            Data::Enum(_) => true,
+            _ => false,
+        };
+
+        if !is_enum {
+            return Err(Error::new_spanned(
+                meta,
+                "`tag`, `content` and `untagged` are only supported on enums",
+            ));
+        }
+    }
+
+    Ok(ContainerAttrs {
+        provider: match provider {
+            Some(_) => DeriveProvider::Serde,
+            None => DeriveProvider::Sval,
+        },
+        rename_all,
+        tagging,
+    })
+}
+
+/**
+Attributes that can be applied to fields.
+*/
+pub(crate) struct FieldAttrs {
+    pub(crate) rename: Option<String>,
+    pub(crate) skip: bool,
+    pub(crate) skip_if: Option<Path>,
+    pub(crate) flatten: bool,
+    pub(crate) with: Option<Path>,
 }
 
-pub(crate) fn name_of_field(field: &Field) -> String {
+impl FieldAttrs {
+    /**
+    Whether any attributes that only make sense on named fields are set.
+    */
+    pub(crate) fn has_named_only(&self) -> bool {
+        self.rename.is_some() || self.skip || self.skip_if.is_some() || self.flatten
+    }
+}
+
+pub(crate) fn field_attrs(field: &Field) -> Result<FieldAttrs> {
     let mut rename = None;
+    let mut skip = None;
+    let mut skip_if = None;
+    let mut flatten = None;
+    let mut with = None;
 
-    for list in field.attrs.iter().filter_map(sval_attr) {
-        for meta in list.nested {
-            if let NestedMeta::Meta(Meta::NameValue(value)) = meta {
-                if value.ident == "rename" && rename.is_none() {
-                    if let Lit::Str(s) = value.lit {
-                        rename = Some(s.value());
-                        continue;
-                    }
-                }
+    for meta in sval_metas(&field.attrs)? {
+        match meta {
+            NestedMeta::Meta(Meta::NameValue(ref value)) if value.ident == "rename" => {
+                set(&mut rename, &meta, lit_str(&value.lit)?.value())?;
+            }
+            NestedMeta::Meta(Meta::Word(ref ident)) if ident == "skip" => {
+                set(&mut skip, &meta, ())?;
+            }
+            NestedMeta::Meta(Meta::NameValue(ref value)) if value.ident == "skip_if" => {
+                set(&mut skip_if, &meta, lit_path(&value.lit)?)?;
+            }
+            NestedMeta::Meta(Meta::Word(ref ident)) if ident == "flatten" => {
+                set(&mut flatten, &meta, ())?;
+            }
+            NestedMeta::Meta(Meta::NameValue(ref value)) if value.ident == "with" => {
+                set(&mut with, &meta, lit_path(&value.lit)?)?;
             }
-            panic!("unsupported attribute");
+            _ => return Err(unsupported(&meta)),
         }
     }
 
-    rename.unwrap_or_else(|| field.ident.as_ref().unwrap().to_string())
+    let attrs = FieldAttrs {
+        rename,
+        skip: skip.is_some(),
+        skip_if,
+        flatten: flatten.is_some(),
+        with,
+    };
+
+    if attrs.flatten && attrs.rename.is_some() {
+        return Err(Error::new_spanned(field, "`flatten` fields can't be renamed"));
+    }
+
+    Ok(attrs)
 }
 
-fn sval_attr(attr: &Attribute) -> Option<MetaList> {
-    let segments = &attr.path.segments;
-    if !(segments.len() == 1 && segments[0].ident == "sval") {
-        return None;
+pub(crate) fn name_of_field(
+    field: &Field,
+    attrs: &FieldAttrs,
+    rename_all: Option<RenameRule>,
+) -> String {
+    if let Some(ref rename) = attrs.rename {
+        return rename.clone();
     }
 
-    match attr.interpret_meta() {
-        Some(Meta::List(list)) => Some(list),
-        _ => panic!("unsupported attribute"),
+    let name = field.ident.as_ref().unwrap().to_string();
+
+    match rename_all {
+        Some(rule) => rule.apply_to_field(&name),
+        None => name,
+    }
+}
+
+pub(crate) fn name_of_variant(variant: &Variant, rename_all: Option<RenameRule>) -> Result<String> {
+    let mut rename = None;
+
+    for meta in sval_metas(&variant.attrs)? {
+        match meta {
+            NestedMeta::Meta(Meta::NameValue(ref value)) if value.ident == "rename" => {
+                set(&mut rename, &meta, lit_str(&value.lit)?.value())?;
+            }
+            _ => return Err(unsupported(&meta)),
+        }
     }
+
+    Ok(rename.unwrap_or_else(|| {
+        let name = variant.ident.to_string();
+
+        match rename_all {
+            Some(rule) => rule.apply_to_variant(&name),
+            None => name,
+        }
+    }))
+}
+
+/**
+Get the items in all `#[sval(...)]` attributes.
+*/
+fn sval_metas(attrs: &[Attribute]) -> Result<Vec<NestedMeta>> {
+    let mut metas = Vec::new();
+
+    for attr in attrs {
+        let segments = &attr.path.segments;
+        if !(segments.len() == 1 && segments[0].ident == "sval") {
+            continue;
+        }
+
+        match attr.parse_meta()? {
+            Meta::List(list) => metas.extend(list.nested),
+            meta => return Err(Error::new_spanned(meta, "expected `#[sval(...)]`")),
+        }
+    }
+
+    Ok(metas)
+}
+
+fn set<T>(slot: &mut Option<T>, meta: &NestedMeta, value: T) -> Result<()> {
+    if slot.is_some() {
+        return Err(Error::new_spanned(meta, "duplicate attribute"));
+    }
+
+    *slot = Some(value);
+
+    Ok(())
+}
+
+fn lit_str(lit: &Lit) -> Result<&LitStr> {
+    match *lit {
+        Lit::Str(ref s) => Ok(s),
+        _ => Err(Error::new_spanned(lit, "expected a string")),
+    }
+}
+
+fn lit_path(lit: &Lit) -> Result<Path> {
+    let s = lit_str(lit)?;
+
+    s.parse()
+        .map_err(|_| Error::new_spanned(s, "expected a path to a function"))
+}
+
+fn unsupported(meta: &NestedMeta) -> Error {
+    Error::new_spanned(meta, "unsupported attribute")
 }
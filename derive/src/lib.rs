@@ -29,6 +29,7 @@ extern crate proc_macro2;
 
 mod attr;
 mod bound;
+mod case;
 mod value;
 
 use proc_macro::TokenStream;
@@ -1,21 +1,38 @@
 use crate::{
     attr,
     bound,
+    case::RenameRule,
 };
 use proc_macro::TokenStream;
-use proc_macro2::Span;
+use proc_macro2::{
+    Span,
+    TokenStream as TokenStream2,
+};
 use syn::{
     Data,
+    DataEnum,
     DataStruct,
     DeriveInput,
+    Error,
+    Field,
     Fields,
+    FieldsNamed,
+    FieldsUnnamed,
     Ident,
+    Index,
+    Result,
+    Variant,
 };
 
 pub(crate) fn derive(input: DeriveInput) -> TokenStream {
-    match attr::derive_provider(&input) {
-        attr::DeriveProvider::Sval => derive_from_sval(input),
-        attr::DeriveProvider::Serde => derive_from_serde(input),
+    let derived = attr::container_attrs(&input).and_then(|attrs| match attrs.provider {
+        attr::DeriveProvider::Sval => derive_from_sval(input, &attrs),
+        attr::DeriveProvider::Serde => Ok(derive_from_serde(input)),
+    });
+
+    match derived {
+        Ok(derived) => derived,
+        Err(err) => TokenStream::from(err.to_compile_error()),
     }
 }
 
@@ -56,46 +73,798 @@ pub(crate) fn derive_from_serde(input: DeriveInput) -> TokenStream {
 /**
 Construct an implementation of `sval::Value` based on the structure of the input.
 */
-pub(crate) fn derive_from_sval(input: DeriveInput) -> TokenStream {
-    let fields = match input.data {
-        Data::Struct(DataStruct {
-            fields: Fields::Named(fields),
-            ..
-        }) => fields,
-        _ => panic!("currently only structs with named fields are supported"),
+pub(crate) fn derive_from_sval(input: DeriveInput, attrs: &attr::ContainerAttrs) -> Result<TokenStream> {
+    let ident = input.ident;
+
+    let body = derive_body(&ident, &input.data, attrs, false)?;
+
+    // Values without any fields to lend can use the default `stream_borrowed`
+    let stream_borrowed = if lends_fields(&input.data, attrs)? {
+        let body_borrowed = derive_body(&ident, &input.data, attrs, true)?;
+
+        quote! {
+            fn stream_borrowed<'sval_s, 'sval_v>(
+                &'sval_v self,
+                stream: &mut sval::value::Stream<'sval_s, 'sval_v>,
+            ) -> Result<(), sval::value::Error> {
+                #body_borrowed
+            }
+        }
+    } else {
+        quote!()
     };
 
-    let ident = input.ident;
     let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
     let dummy = Ident::new(
         &format!("_IMPL_SVAL_VALUE_FOR_{}", ident),
         Span::call_site(),
     );
 
-    let fieldname = &fields.named.iter().map(|f| &f.ident).collect::<Vec<_>>();
-    let fieldstr = fields.named.iter().map(attr::name_of_field);
-    let num_fields = fieldname.len();
-
     let bound = parse_quote!(sval::Value);
     let bounded_where_clause = bound::where_clause_with_bound(&input.generics, bound);
 
-    TokenStream::from(quote! {
+    Ok(TokenStream::from(quote! {
         #[allow(non_upper_case_globals)]
         const #dummy: () = {
             extern crate sval;
 
             impl #impl_generics sval::Value for #ident #ty_generics #bounded_where_clause {
                 fn stream(&self, stream: &mut sval::value::Stream) -> Result<(), sval::value::Error> {
-                    stream.map_begin(Some(#num_fields))?;
+                    #body
+                }
+
+                #stream_borrowed
+            }
+        };
+    }))
+}
+
+/**
+Whether streaming the input with `stream_borrowed` lends any of its fields.
+
+This needs to match the fields that `derive_body` streams using
+`_borrowed` methods when `borrowed` is `true`.
+*/
+fn lends_fields(data: &Data, attrs: &attr::ContainerAttrs) -> Result<bool> {
+    match *data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(ref fields),
+            ..
+        }) => {
+            let mut has_flatten = false;
+            for field in &fields.named {
+                has_flatten |= attr::field_attrs(field)?.flatten;
+            }
 
-                    #(
-                        stream.map_key(#fieldstr)?;
-                        stream.map_value(&self.#fieldname)?;
-                    )*
+            if has_flatten {
+                named_fields_lend(fields, Entries::Map)
+            } else {
+                named_fields_lend(fields, Entries::Struct)
+            }
+        }
+        Data::Struct(DataStruct {
+            fields: Fields::Unnamed(ref fields),
+            ..
+        }) => unnamed_fields_lend(fields),
+        Data::Struct(DataStruct {
+            fields: Fields::Unit,
+            ..
+        }) => Ok(false),
+        Data::Enum(ref data) => {
+            for variant in &data.variants {
+                let lends = match variant.fields {
+                    Fields::Unit => false,
+                    Fields::Unnamed(ref fields) => match attrs.tagging {
+                        // Externally tagged newtype and tuple variants are streamed by value,
+                        // and internally tagged newtype variants are flattened
+                        attr::Tagging::External | attr::Tagging::Internal { .. } => false,
+                        _ => unnamed_fields_lend(fields)?,
+                    },
+                    Fields::Named(ref fields) => match attrs.tagging {
+                        attr::Tagging::External => {
+                            named_fields_lend(fields, Entries::StructVariant)?
+                        }
+                        _ => named_fields_lend(fields, Entries::Map)?,
+                    },
+                };
 
-                    stream.map_end()
+                if lends {
+                    return Ok(true);
                 }
             }
-        };
+
+            Ok(false)
+        }
+        Data::Union(_) => Ok(false),
+    }
+}
+
+/**
+Whether any named fields are lent when streamed into the given kind of container.
+
+Fields streamed using a `with` function aren't lent,
+but their names are still lent as keys when streamed into a map.
+*/
+fn named_fields_lend(fields: &FieldsNamed, entries: Entries) -> Result<bool> {
+    for field in &fields.named {
+        let attrs = attr::field_attrs(field)?;
+
+        if attrs.skip || attrs.flatten {
+            continue;
+        }
+
+        match entries {
+            Entries::Map => return Ok(true),
+            Entries::Struct | Entries::StructVariant => {
+                if attrs.with.is_none() {
+                    return Ok(true);
+                }
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+/**
+Whether any unnamed fields are lent when streamed.
+*/
+fn unnamed_fields_lend(fields: &FieldsUnnamed) -> Result<bool> {
+    for field in &fields.unnamed {
+        if unnamed_field_attrs(field)?.with.is_none() {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/**
+Construct the body of a method that streams the input.
+
+When `borrowed` is `true`, the body is for `stream_borrowed`
+and lends fields to the stream.
+*/
+fn derive_body(
+    ident: &Ident,
+    data: &Data,
+    attrs: &attr::ContainerAttrs,
+    borrowed: bool,
+) -> Result<TokenStream2> {
+    Ok(match *data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(ref fields),
+            ..
+        }) => derive_struct(ident, fields, attrs, borrowed)?,
+        Data::Struct(DataStruct {
+            fields: Fields::Unnamed(ref fields),
+            ..
+        }) if fields.unnamed.len() == 1 => derive_newtype(fields, borrowed)?,
+        Data::Struct(DataStruct {
+            fields: Fields::Unnamed(ref fields),
+            ..
+        }) => derive_tuple(ident, fields, borrowed)?,
+        Data::Struct(DataStruct {
+            fields: Fields::Unit,
+            ..
+        }) => derive_unit(),
+        Data::Enum(ref data) => derive_enum(ident, data, attrs, borrowed)?,
+        Data::Union(ref data) => {
+            return Err(Error::new_spanned(data.union_token, "unions are not supported"));
+        }
     })
 }
+
+fn derive_struct(
+    ident: &Ident,
+    fields: &FieldsNamed,
+    attrs: &attr::ContainerAttrs,
+    borrowed: bool,
+) -> Result<TokenStream2> {
+    let pattern = named_fields_pattern(fields)?;
+
+    // Flattened fields can't be known upfront, so structs
+    // that contain them are streamed as maps instead
+    let mut has_flatten = false;
+    for field in &fields.named {
+        has_flatten |= attr::field_attrs(field)?.flatten;
+    }
+
+    let entries = if has_flatten {
+        derive_named_fields_map(fields, attrs.rename_all, borrowed)?
+    } else {
+        let tystr = ident.to_string();
+        let (len, entries) =
+            derive_named_fields(fields, Entries::Struct, attrs.rename_all, borrowed)?;
+        let len = len.expect("structs without flattened fields always have a length");
+
+        quote! {
+            stream.struct_begin(#tystr, #len)?;
+
+            #entries
+
+            stream.struct_end()
+        }
+    };
+
+    Ok(quote! {
+        let #ident #pattern = *self;
+
+        #entries
+    })
+}
+
+fn derive_newtype(fields: &FieldsUnnamed, borrowed: bool) -> Result<TokenStream2> {
+    let attrs = unnamed_field_attrs(&fields.unnamed[0])?;
+    let stream_value = stream_field(&attrs, "any", &[], quote!(&self.0), borrowed);
+
+    Ok(quote! {
+        #stream_value
+    })
+}
+
+fn derive_tuple(ident: &Ident, fields: &FieldsUnnamed, borrowed: bool) -> Result<TokenStream2> {
+    let tystr = ident.to_string();
+    let stream_elem = fields
+        .unnamed
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let index = Index::from(i);
+            let elem_index = i as u32;
+            let attrs = unnamed_field_attrs(field)?;
+
+            Ok(stream_field(
+                &attrs,
+                "tuple_elem",
+                &[quote!(#elem_index)],
+                quote!(&self.#index),
+                borrowed,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let num_fields = fields.unnamed.len();
+
+    Ok(quote! {
+        stream.tuple_begin(Some(#tystr), #num_fields)?;
+
+        #(
+            #stream_elem?;
+        )*
+
+        stream.tuple_end()
+    })
+}
+
+fn derive_unit() -> TokenStream2 {
+    quote! {
+        stream.unit()
+    }
+}
+
+fn derive_enum(
+    ident: &Ident,
+    data: &DataEnum,
+    attrs: &attr::ContainerAttrs,
+    borrowed: bool,
+) -> Result<TokenStream2> {
+    let tystr = ident.to_string();
+
+    let arms = data
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(index, variant)| {
+            let index = index as u32;
+            let variant_ident = &variant.ident;
+            let variantstr = attr::name_of_variant(variant, attrs.rename_all)?;
+
+            let pattern = match variant.fields {
+                Fields::Unit => quote!(),
+                Fields::Unnamed(ref fields) => {
+                    let binding = (0..fields.unnamed.len()).map(binding);
+                    quote!((#(ref #binding),*))
+                }
+                Fields::Named(ref fields) => named_fields_pattern(fields)?,
+            };
+
+            let body = match attrs.tagging {
+                attr::Tagging::External => {
+                    derive_external_variant(&tystr, index, &variantstr, &variant.fields, borrowed)?
+                }
+                attr::Tagging::Internal { ref tag } => {
+                    derive_internal_variant(tag, &variantstr, variant, borrowed)?
+                }
+                attr::Tagging::Adjacent {
+                    ref tag,
+                    ref content,
+                } => derive_adjacent_variant(tag, content, &variantstr, &variant.fields, borrowed)?,
+                attr::Tagging::Untagged => derive_variant_content(&variant.fields, borrowed)?,
+            };
+
+            Ok(quote! {
+                #ident::#variant_ident #pattern => {
+                    #body
+                }
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(quote! {
+        match *self {
+            #(#arms)*
+        }
+    })
+}
+
+/**
+Stream a variant using the variant events of the stream.
+*/
+fn derive_external_variant(
+    tystr: &str,
+    index: u32,
+    variantstr: &str,
+    fields: &Fields,
+    borrowed: bool,
+) -> Result<TokenStream2> {
+    Ok(match *fields {
+        Fields::Unit => quote! {
+            stream.unit_variant(#tystr, #index, #variantstr)
+        },
+        Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => {
+            let value = unnamed_field_value(&fields.unnamed[0], quote!(field0))?;
+
+            quote! {
+                stream.newtype_variant(#tystr, #index, #variantstr, #value)
+            }
+        }
+        Fields::Unnamed(ref fields) => {
+            let value = unnamed_fields_values(fields)?;
+            let elem_index = (0..fields.unnamed.len() as u32).collect::<Vec<_>>();
+            let num_fields = fields.unnamed.len();
+
+            quote! {
+                stream.tuple_variant_begin(#tystr, #index, #variantstr, #num_fields)?;
+
+                #(
+                    stream.tuple_variant_elem(#elem_index, #value)?;
+                )*
+
+                stream.tuple_variant_end()
+            }
+        }
+        Fields::Named(ref fields) => {
+            let (len, entries) =
+                derive_named_fields(fields, Entries::StructVariant, None, borrowed)?;
+            let len = len.expect("struct variants always have a length");
+
+            quote! {
+                stream.struct_variant_begin(#tystr, #index, #variantstr, #len)?;
+
+                #entries
+
+                stream.struct_variant_end()
+            }
+        }
+    })
+}
+
+/**
+Stream a variant as a map with the tag alongside its fields.
+*/
+fn derive_internal_variant(
+    tag: &str,
+    variantstr: &str,
+    variant: &Variant,
+    borrowed: bool,
+) -> Result<TokenStream2> {
+    Ok(match variant.fields {
+        Fields::Unit => quote! {
+            stream.map_begin(Some(1))?;
+
+            stream.map_key(#tag)?;
+            stream.map_value(#variantstr)?;
+
+            stream.map_end()
+        },
+        Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => {
+            let value = unnamed_field_value(&fields.unnamed[0], quote!(field0))?;
+
+            quote! {
+                stream.map_begin(None)?;
+
+                stream.map_key(#tag)?;
+                stream.map_value(#variantstr)?;
+
+                sval::derive::flatten(stream, #value)?;
+
+                stream.map_end()
+            }
+        }
+        Fields::Unnamed(_) => {
+            return Err(Error::new_spanned(
+                variant,
+                "internally tagged enums don't support tuple variants",
+            ));
+        }
+        Fields::Named(ref fields) => {
+            let (len, entries) = derive_named_fields(fields, Entries::Map, None, borrowed)?;
+            let len = len_hint(len.map(|len| quote!(1 + #len)));
+
+            quote! {
+                stream.map_begin(#len)?;
+
+                stream.map_key(#tag)?;
+                stream.map_value(#variantstr)?;
+
+                #entries
+
+                stream.map_end()
+            }
+        }
+    })
+}
+
+/**
+Stream a variant as a map with separate entries for the tag and its content.
+*/
+fn derive_adjacent_variant(
+    tag: &str,
+    content: &str,
+    variantstr: &str,
+    fields: &Fields,
+    borrowed: bool,
+) -> Result<TokenStream2> {
+    Ok(match *fields {
+        Fields::Unit => quote! {
+            stream.map_begin(Some(1))?;
+
+            stream.map_key(#tag)?;
+            stream.map_value(#variantstr)?;
+
+            stream.map_end()
+        },
+        _ => {
+            let value = derive_variant_content(fields, borrowed)?;
+
+            quote! {
+                stream.map_begin(Some(2))?;
+
+                stream.map_key(#tag)?;
+                stream.map_value(#variantstr)?;
+
+                stream.map_key(#content)?;
+                stream.map_value_begin()?;
+                { #value }?;
+
+                stream.map_end()
+            }
+        }
+    })
+}
+
+/**
+Stream the content of a variant without its tag.
+*/
+fn derive_variant_content(fields: &Fields, borrowed: bool) -> Result<TokenStream2> {
+    Ok(match *fields {
+        Fields::Unit => quote! {
+            stream.unit()
+        },
+        Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => {
+            let attrs = unnamed_field_attrs(&fields.unnamed[0])?;
+
+            stream_field(&attrs, "any", &[], quote!(field0), borrowed)
+        }
+        Fields::Unnamed(ref fields) => {
+            let stream_elem = fields
+                .unnamed
+                .iter()
+                .enumerate()
+                .map(|(i, field)| {
+                    let binding = binding(i);
+                    let attrs = unnamed_field_attrs(field)?;
+
+                    Ok(stream_field(
+                        &attrs,
+                        "seq_elem",
+                        &[],
+                        quote!(#binding),
+                        borrowed,
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let num_fields = fields.unnamed.len();
+
+            quote! {
+                stream.seq_begin(Some(#num_fields))?;
+
+                #(
+                    #stream_elem?;
+                )*
+
+                stream.seq_end()
+            }
+        }
+        Fields::Named(ref fields) => derive_named_fields_map(fields, None, borrowed)?,
+    })
+}
+
+/**
+The kind of container that named fields are streamed into.
+*/
+#[derive(Clone, Copy)]
+enum Entries {
+    Map,
+    Struct,
+    StructVariant,
+}
+
+/**
+Stream a set of named fields as a map.
+
+The fields are expected to be bound by a `named_fields_pattern`.
+*/
+fn derive_named_fields_map(
+    fields: &FieldsNamed,
+    rename_all: Option<RenameRule>,
+    borrowed: bool,
+) -> Result<TokenStream2> {
+    let (len, entries) = derive_named_fields(fields, Entries::Map, rename_all, borrowed)?;
+    let len = len_hint(len);
+
+    Ok(quote! {
+        stream.map_begin(#len)?;
+
+        #entries
+
+        stream.map_end()
+    })
+}
+
+/**
+Stream a set of named fields.
+
+The fields are expected to be bound by a `named_fields_pattern`.
+
+This returns an expression for the number of entries that will be streamed,
+or `None` if it can't be known upfront, along with the statements that stream them.
+*/
+fn derive_named_fields(
+    fields: &FieldsNamed,
+    entries: Entries,
+    rename_all: Option<RenameRule>,
+    borrowed: bool,
+) -> Result<(Option<TokenStream2>, TokenStream2)> {
+    let mut num_fields = 0usize;
+    let mut num_skippable = Vec::new();
+    let mut has_flatten = false;
+    let mut stmts = Vec::new();
+
+    for (i, field) in fields.named.iter().enumerate() {
+        let attrs = attr::field_attrs(field)?;
+
+        if attrs.skip {
+            continue;
+        }
+
+        let binding = binding(i);
+        let value = field_value(&attrs, quote!(#binding));
+
+        let stmt = if attrs.flatten {
+            match entries {
+                Entries::Map => (),
+                Entries::Struct => {
+                    unreachable!("structs with flattened fields are streamed as maps")
+                }
+                Entries::StructVariant => {
+                    return Err(Error::new_spanned(
+                        field,
+                        "`flatten` is not supported on enum variant fields",
+                    ));
+                }
+            }
+
+            has_flatten = true;
+
+            quote! {
+                sval::derive::flatten(stream, #value)?;
+            }
+        } else {
+            let name = attr::name_of_field(field, &attrs, rename_all);
+
+            match entries {
+                Entries::Map => {
+                    let stream_key = stream_method("map_key", borrowed);
+                    let stream_value =
+                        stream_field(&attrs, "map_value", &[], quote!(#binding), borrowed);
+
+                    quote! {
+                        stream.#stream_key(#name)?;
+                        #stream_value?;
+                    }
+                }
+                Entries::Struct => {
+                    let index = i as u32;
+                    let stream_value = stream_field(
+                        &attrs,
+                        "struct_field",
+                        &[quote!(#index), quote!(#name)],
+                        quote!(#binding),
+                        borrowed,
+                    );
+
+                    quote! {
+                        #stream_value?;
+                    }
+                }
+                Entries::StructVariant => {
+                    let index = i as u32;
+                    let stream_value = stream_field(
+                        &attrs,
+                        "struct_variant_field",
+                        &[quote!(#index), quote!(#name)],
+                        quote!(#binding),
+                        borrowed,
+                    );
+
+                    quote! {
+                        #stream_value?;
+                    }
+                }
+            }
+        };
+
+        match attrs.skip_if {
+            Some(ref skip_if) => {
+                num_skippable.push(quote!(if #skip_if(#binding) { 0 } else { 1 }));
+
+                stmts.push(quote! {
+                    if !#skip_if(#binding) {
+                        #stmt
+                    }
+                });
+            }
+            None => {
+                num_fields += 1;
+
+                stmts.push(stmt);
+            }
+        }
+    }
+
+    let len = if has_flatten {
+        None
+    } else if num_skippable.is_empty() {
+        Some(quote!(#num_fields))
+    } else {
+        Some(quote!(#num_fields #(+ #num_skippable)*))
+    };
+
+    Ok((len, quote!(#(#stmts)*)))
+}
+
+/**
+A pattern that binds the named fields that will be streamed.
+
+Each field is bound by reference to an identifier from `binding`.
+*/
+fn named_fields_pattern(fields: &FieldsNamed) -> Result<TokenStream2> {
+    let mut fieldname = Vec::new();
+    let mut binding = Vec::new();
+
+    for (i, field) in fields.named.iter().enumerate() {
+        if !attr::field_attrs(field)?.skip {
+            fieldname.push(&field.ident);
+            binding.push(self::binding(i));
+        }
+    }
+
+    Ok(quote!({ #(#fieldname: ref #binding,)* .. }))
+}
+
+fn len_hint(len: Option<TokenStream2>) -> TokenStream2 {
+    match len {
+        Some(len) => quote!(Some(#len)),
+        None => quote!(None),
+    }
+}
+
+/**
+Get the values to stream for a set of unnamed fields.
+
+The fields are expected to be bound to identifiers from `binding`.
+*/
+fn unnamed_fields_values(fields: &FieldsUnnamed) -> Result<Vec<TokenStream2>> {
+    fields
+        .unnamed
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let binding = binding(i);
+            unnamed_field_value(field, quote!(#binding))
+        })
+        .collect()
+}
+
+/**
+Get the value to stream for an unnamed field.
+
+Unnamed fields only support the `with` attribute.
+*/
+fn unnamed_field_value(field: &Field, value: TokenStream2) -> Result<TokenStream2> {
+    let attrs = unnamed_field_attrs(field)?;
+
+    Ok(field_value(&attrs, value))
+}
+
+/**
+Get the attributes for an unnamed field.
+
+Unnamed fields only support the `with` attribute.
+*/
+fn unnamed_field_attrs(field: &Field) -> Result<attr::FieldAttrs> {
+    let attrs = attr::field_attrs(field)?;
+
+    if attrs.has_named_only() {
+        return Err(Error::new_spanned(
+            field,
+            "only `with` is supported on unnamed fields",
+        ));
+    }
+
+    Ok(attrs)
+}
+
+/**
+Call a stream method with a reference to a field's value.
+
+The `args` are passed to the method before the value.
+When `borrowed` is `true`, fields are lent to the stream using the `_borrowed`
+variant of the method, unless they're streamed using a `with` function.
+*/
+fn stream_field(
+    attrs: &attr::FieldAttrs,
+    method: &str,
+    args: &[TokenStream2],
+    value: TokenStream2,
+    borrowed: bool,
+) -> TokenStream2 {
+    match attrs.with {
+        Some(ref with) => {
+            let method = stream_method(method, false);
+
+            quote!(stream.#method(#(#args,)* sval::derive::With(#value, #with)))
+        }
+        None => {
+            let method = stream_method(method, borrowed);
+
+            quote!(stream.#method(#(#args,)* #value))
+        }
+    }
+}
+
+/**
+Get the name of a stream method, or its `_borrowed` variant.
+*/
+fn stream_method(method: &str, borrowed: bool) -> Ident {
+    if borrowed {
+        Ident::new(&format!("{}_borrowed", method), Span::call_site())
+    } else {
+        Ident::new(method, Span::call_site())
+    }
+}
+
+/**
+Wrap a reference to a field's value so it's streamed using a `with` function, if there is one.
+*/
+fn field_value(attrs: &attr::FieldAttrs, value: TokenStream2) -> TokenStream2 {
+    match attrs.with {
+        Some(ref with) => quote!(sval::derive::With(#value, #with)),
+        None => value,
+    }
+}
+
+fn binding(i: usize) -> Ident {
+    Ident::new(&format!("field{}", i), Span::call_site())
+}
@@ -0,0 +1,481 @@
+use sval::stream::{
+    self,
+    Stack,
+    Stream,
+};
+
+use crate::{
+    config::EscapePolicy,
+    fmt::escape_str,
+    parse::JsonStr,
+    raw::RAW_JSON_TAG,
+    std::{
+        fmt::Write,
+        io,
+        string::{
+            String,
+            ToString,
+        },
+        vec::Vec,
+    },
+};
+
+/**
+Write a [`sval::Value`] to a string as canonical json.
+
+Canonical json follows [RFC 8785](https://www.rfc-editor.org/rfc/rfc8785) (JCS),
+so the same value always produces the same bytes:
+
+- map keys are sorted by their UTF-16 code units.
+- numbers use the ECMAScript serialization of 64bit floats.
+- strings only escape quotes, backslashes and control characters.
+- there's no whitespace between tokens.
+
+Every number is written as a 64bit float, so integers are only supported
+if a 64bit float can represent them exactly. That includes every integer
+within `±(2^53 - 1)`, and larger ones like `2^53` or `2^64`, which are
+written the same way as the equivalent float. Values that can't be
+represented exactly, like `2^53 + 1` or non-finite floats, fail with an error.
+Maps with duplicate keys also fail with an error.
+*/
+pub fn to_string_canonical(v: impl sval::Value) -> Result<String, sval::Error> {
+    let mut canonical = Canonical {
+        stack: Stack::new(),
+        frames: Vec::new(),
+        raw: Raw::None,
+        out: String::new(),
+    };
+
+    sval::stream(v, &mut canonical)?;
+
+    Ok(canonical.out)
+}
+
+/**
+Write a [`sval::Value`] to a writer as canonical json.
+
+See [`to_string_canonical`] for details on canonical json.
+Once the json has been written the writer is flushed.
+*/
+pub fn to_writer_canonical(
+    mut writer: impl io::Write,
+    v: impl sval::Value,
+) -> Result<(), sval::Error> {
+    let json = to_string_canonical(v)?;
+
+    writer
+        .write_all(json.as_bytes())
+        .and_then(|_| writer.flush())
+        .map_err(sval::Error::from_error)
+}
+
+struct Canonical {
+    stack: Stack,
+    frames: Vec<Frame>,
+    raw: Raw,
+    out: String,
+}
+
+/**
+The state of a raw json fragment being written.
+*/
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Raw {
+    None,
+    Begin,
+    End,
+}
+
+/**
+A map or sequence that's being buffered.
+*/
+enum Frame {
+    Seq {
+        out: String,
+    },
+    Map {
+        entries: Vec<(String, String)>,
+        key: Option<String>,
+    },
+}
+
+impl Canonical {
+    /**
+    Write a complete json value into its parent.
+    */
+    fn value(&mut self, is_key: bool, json: &str) -> Result<(), stream::Error> {
+        if is_key {
+            return Err(stream::Error::msg(
+                "only strings are supported as json keys",
+            ));
+        }
+
+        match self.frames.last_mut() {
+            None => self.out.push_str(json),
+            Some(Frame::Seq { out }) => {
+                if out.len() > 1 {
+                    out.push(',');
+                }
+
+                out.push_str(json);
+            }
+            Some(Frame::Map { entries, key }) => {
+                let key = key
+                    .take()
+                    .ok_or_else(|| stream::Error::msg("missing json key"))?;
+
+                entries.push((key, json.to_string()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+    Write a string into its parent, either as a key or a value.
+    */
+    fn string(&mut self, is_key: bool, v: &str) -> Result<(), stream::Error> {
+        if is_key {
+            match self.frames.last_mut() {
+                Some(Frame::Map { key, .. }) => {
+                    *key = Some(v.to_string());
+                    return Ok(());
+                }
+                _ => return Err(stream::Error::msg("missing json map")),
+            }
+        }
+
+        let mut json = String::with_capacity(v.len() + 2);
+        escape_str(v, &mut json, EscapePolicy::Minimal)?;
+
+        self.value(false, &json)
+    }
+
+    fn int(&mut self, is_key: bool, v: i128) -> Result<(), stream::Error> {
+        // The magnitude of `i128::MIN` only fits in a `u128`
+        if v < 0 {
+            self.uint(is_key, true, (v as u128).wrapping_neg())
+        } else {
+            self.uint(is_key, false, v as u128)
+        }
+    }
+
+    fn uint(&mut self, is_key: bool, negative: bool, v: u128) -> Result<(), stream::Error> {
+        // A 64bit float has 53 significant bits, so any integer whose
+        // significant bits fit in that can be represented exactly
+        if v != 0 && 128 - v.leading_zeros() - v.trailing_zeros() > 53 {
+            return Err(stream::Error::msg(
+                "the integer can't be represented exactly by canonical json",
+            ));
+        }
+
+        let v = if negative { -(v as f64) } else { v as f64 };
+
+        let mut json = String::new();
+        write_number(v, &mut json)?;
+
+        self.value(is_key, &json)
+    }
+}
+
+impl Stream for Canonical {
+    fn begin(&mut self) -> Result<(), stream::Error> {
+        self.stack.begin()?;
+
+        Ok(())
+    }
+
+    fn fmt(&mut self, v: stream::Arguments) -> Result<(), stream::Error> {
+        let pos = self.stack.primitive()?;
+
+        self.string(pos.is_key(), &v.to_string())
+    }
+
+    fn i64(&mut self, v: i64) -> Result<(), stream::Error> {
+        let pos = self.stack.primitive()?;
+
+        self.int(pos.is_key(), i128::from(v))
+    }
+
+    fn u64(&mut self, v: u64) -> Result<(), stream::Error> {
+        let pos = self.stack.primitive()?;
+
+        self.int(pos.is_key(), i128::from(v))
+    }
+
+    fn i128(&mut self, v: i128) -> Result<(), stream::Error> {
+        let pos = self.stack.primitive()?;
+
+        self.int(pos.is_key(), v)
+    }
+
+    fn u128(&mut self, v: u128) -> Result<(), stream::Error> {
+        let pos = self.stack.primitive()?;
+
+        self.uint(pos.is_key(), false, v)
+    }
+
+    fn number(&mut self, v: &str) -> Result<(), stream::Error> {
+        let pos = self.stack.primitive()?;
+
+        // Canonical json numbers are doubles, so the number is rounded to the nearest one
+        let v: f64 = v
+            .parse()
+            .map_err(|_| stream::Error::msg("the number is not a valid decimal literal"))?;
+
+        if !v.is_finite() {
+            return Err(stream::Error::msg(
+                "the number is outside of the range supported by canonical json",
+            ));
+        }
+
+        let mut json = String::new();
+        write_number(v, &mut json)?;
+
+        self.value(pos.is_key(), &json)
+    }
+
+    fn f64(&mut self, v: f64) -> Result<(), stream::Error> {
+        let pos = self.stack.primitive()?;
+
+        if !v.is_finite() {
+            return Err(stream::Error::msg(
+                "non-finite floats are not supported in json",
+            ));
+        }
+
+        let mut json = String::new();
+        write_number(v, &mut json)?;
+
+        self.value(pos.is_key(), &json)
+    }
+
+    fn bool(&mut self, v: bool) -> Result<(), stream::Error> {
+        let pos = self.stack.primitive()?;
+
+        self.value(pos.is_key(), if v { "true" } else { "false" })
+    }
+
+    fn char(&mut self, v: char) -> Result<(), stream::Error> {
+        let pos = self.stack.primitive()?;
+
+        self.string(pos.is_key(), v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn str(&mut self, v: &str) -> Result<(), stream::Error> {
+        let pos = self.stack.primitive()?;
+
+        if self.raw == Raw::Begin {
+            // Raw json is parsed so it's canonicalized too
+            let json = to_string_canonical(JsonStr::new(v))?;
+            self.raw = Raw::End;
+
+            return self.value(pos.is_key(), &json);
+        }
+
+        self.string(pos.is_key(), v)
+    }
+
+    fn none(&mut self) -> Result<(), stream::Error> {
+        let pos = self.stack.primitive()?;
+
+        self.value(pos.is_key(), "null")
+    }
+
+    fn map_begin(&mut self, _: Option<usize>) -> Result<(), stream::Error> {
+        let pos = self.stack.map_begin()?;
+
+        if pos.is_key() {
+            return Err(stream::Error::msg(
+                "only strings are supported as json keys",
+            ));
+        }
+
+        self.frames.push(Frame::Map {
+            entries: Vec::new(),
+            key: None,
+        });
+
+        Ok(())
+    }
+
+    fn map_key(&mut self) -> Result<(), stream::Error> {
+        self.stack.map_key()?;
+
+        Ok(())
+    }
+
+    fn map_value(&mut self) -> Result<(), stream::Error> {
+        self.stack.map_value()?;
+
+        Ok(())
+    }
+
+    fn map_end(&mut self) -> Result<(), stream::Error> {
+        self.stack.map_end()?;
+
+        let mut entries = match self.frames.pop() {
+            Some(Frame::Map { entries, .. }) => entries,
+            _ => return Err(stream::Error::msg("missing json map")),
+        };
+
+        entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+        let mut json = String::from("{");
+        for (i, (key, value)) in entries.iter().enumerate() {
+            if i > 0 {
+                if entries[i - 1].0 == *key {
+                    return Err(stream::Error::msg(
+                        "duplicate keys are not supported in canonical json",
+                    ));
+                }
+
+                json.push(',');
+            }
+
+            escape_str(key, &mut json, EscapePolicy::Minimal)?;
+            json.push(':');
+            json.push_str(value);
+        }
+        json.push('}');
+
+        self.value(false, &json)
+    }
+
+    fn seq_begin(&mut self, _: Option<usize>) -> Result<(), stream::Error> {
+        let pos = self.stack.seq_begin()?;
+
+        if pos.is_key() {
+            return Err(stream::Error::msg(
+                "only strings are supported as json keys",
+            ));
+        }
+
+        self.frames.push(Frame::Seq {
+            out: String::from("["),
+        });
+
+        Ok(())
+    }
+
+    fn seq_elem(&mut self) -> Result<(), stream::Error> {
+        self.stack.seq_elem()?;
+
+        Ok(())
+    }
+
+    fn seq_end(&mut self) -> Result<(), stream::Error> {
+        self.stack.seq_end()?;
+
+        let mut json = match self.frames.pop() {
+            Some(Frame::Seq { out }) => out,
+            _ => return Err(stream::Error::msg("missing json sequence")),
+        };
+        json.push(']');
+
+        self.value(false, &json)
+    }
+
+    fn tagged_begin(&mut self, tag: stream::Tag) -> Result<(), stream::Error> {
+        if tag == RAW_JSON_TAG {
+            self.raw = Raw::Begin;
+        }
+
+        Ok(())
+    }
+
+    fn tagged_end(&mut self) -> Result<(), stream::Error> {
+        match self.raw {
+            Raw::None => Ok(()),
+            Raw::Begin => Err(stream::Error::msg("raw json must be a string")),
+            Raw::End => {
+                self.raw = Raw::None;
+
+                Ok(())
+            }
+        }
+    }
+
+    fn end(&mut self) -> Result<(), stream::Error> {
+        self.stack.end()?;
+
+        Ok(())
+    }
+}
+
+/**
+Write a finite float using the ECMAScript `Number.prototype.toString` algorithm.
+*/
+fn write_number(v: f64, mut out: impl Write) -> Result<(), stream::Error> {
+    if v == 0.0 {
+        out.write_char('0')?;
+        return Ok(());
+    }
+
+    if v.is_sign_negative() {
+        out.write_char('-')?;
+    }
+
+    // Get the shortest digits that round-trip the value from `ryu`
+    // and normalize them into `0.digits * 10^n`
+    let mut buf = ryu::Buffer::new();
+    let formatted = buf.format(v.abs());
+
+    let (mantissa, exp) = match formatted.find(|c| c == 'e' || c == 'E') {
+        Some(i) => (
+            &formatted[..i],
+            formatted[i + 1..]
+                .parse::<i32>()
+                .map_err(|_| stream::Error::msg("invalid float"))?,
+        ),
+        None => (formatted, 0),
+    };
+
+    let (int, frac) = match mantissa.find('.') {
+        Some(i) => (&mantissa[..i], &mantissa[i + 1..]),
+        None => (mantissa, ""),
+    };
+
+    let all = int.bytes().chain(frac.bytes());
+    let leading = all.clone().take_while(|b| *b == b'0').count();
+
+    let mut digits = String::new();
+    digits.extend(all.skip(leading).map(char::from));
+    while digits.ends_with('0') {
+        digits.pop();
+    }
+
+    let k = digits.len() as i32;
+    let n = int.len() as i32 + exp - leading as i32;
+
+    if k <= n && n <= 21 {
+        out.write_str(&digits)?;
+        for _ in 0..n - k {
+            out.write_char('0')?;
+        }
+    } else if 0 < n && n <= 21 {
+        out.write_str(&digits[..n as usize])?;
+        out.write_char('.')?;
+        out.write_str(&digits[n as usize..])?;
+    } else if -6 < n && n <= 0 {
+        out.write_str("0.")?;
+        for _ in 0..-n {
+            out.write_char('0')?;
+        }
+        out.write_str(&digits)?;
+    } else {
+        let e = n - 1;
+
+        out.write_str(&digits[..1])?;
+        if k > 1 {
+            out.write_char('.')?;
+            out.write_str(&digits[1..])?;
+        }
+
+        out.write_char('e')?;
+        out.write_char(if e < 0 { '-' } else { '+' })?;
+        write!(out, "{}", e.abs())?;
+    }
+
+    Ok(())
+}
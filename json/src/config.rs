@@ -0,0 +1,235 @@
+use crate::{
+    pretty::Pretty,
+    std::fmt::Write,
+};
+
+/**
+Options for writing json.
+
+The default options write compact json without a trailing newline.
+The same options can be used with any of the functions that write json:
+
+```
+let config = sval_json::Config::new()
+    .pretty(sval_json::Pretty::new().indent("\t"))
+    .floats(sval_json::FloatPolicy::Error)
+    .trailing_newline(true);
+
+let mut json = String::new();
+config.to_fmt(&mut json, &[1, 2][..]).unwrap();
+
+assert_eq!("[\n\t1,\n\t2\n]\n", json);
+```
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Config {
+    pub(crate) pretty: Option<Pretty>,
+    pub(crate) trailing_newline: bool,
+    pub(crate) single_line: bool,
+    pub(crate) big_ints: BigIntPolicy,
+    pub(crate) escape: EscapePolicy,
+    pub(crate) floats: FloatPolicy,
+    pub(crate) keys: KeyPolicy,
+}
+
+/**
+How to write 128bit integers.
+
+Many json parsers, including JavaScript's, read numbers as 64bit floats,
+so integers outside of `Number.MAX_SAFE_INTEGER` (`±(2^53 - 1)`) may lose precision.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BigIntPolicy {
+    /**
+    Write all the digits of the integer as a json number.
+
+    This is the default policy.
+    */
+    Exact,
+    /**
+    Write integers outside of the safe range as quoted strings.
+
+    Integers within the safe range are still written as json numbers.
+    */
+    String,
+    /**
+    Fail with an error when an integer is outside of the safe range.
+    */
+    Error,
+}
+
+impl Default for BigIntPolicy {
+    fn default() -> Self {
+        BigIntPolicy::Exact
+    }
+}
+
+/**
+How to escape characters in strings.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscapePolicy {
+    /**
+    Only escape quotes, backslashes and control characters.
+
+    This is the default policy.
+    */
+    Minimal,
+    /**
+    Also escape every non-ASCII character as `\uXXXX`.
+
+    Characters outside the basic multilingual plane are written as surrogate pairs.
+    */
+    Ascii,
+    /**
+    Also escape `<`, `>`, `&`, U+2028 and U+2029 as `\uXXXX`.
+
+    The output can be safely embedded in an HTML `<script>` block.
+    */
+    Html,
+}
+
+impl Default for EscapePolicy {
+    fn default() -> Self {
+        EscapePolicy::Minimal
+    }
+}
+
+/**
+How to write floating point values that aren't finite.
+
+Json has no representation for `NaN` or infinity.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloatPolicy {
+    /**
+    Write non-finite floats as `null`.
+
+    This is the default policy.
+    */
+    Null,
+    /**
+    Write non-finite floats as the strings `"NaN"`, `"Infinity"` or `"-Infinity"`.
+    */
+    String,
+    /**
+    Fail with an error when a non-finite float is written.
+    */
+    Error,
+}
+
+impl Default for FloatPolicy {
+    fn default() -> Self {
+        FloatPolicy::Null
+    }
+}
+
+/**
+How to write map keys that aren't strings.
+
+Json only supports strings as map keys.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyPolicy {
+    /**
+    Fail with an error when a map key isn't a string.
+
+    This is the default policy.
+    */
+    Strict,
+    /**
+    Write integer, boolean and character map keys as quoted strings.
+
+    Other keys, like floats, maps and sequences, still fail with an error.
+    */
+    Stringify,
+}
+
+impl Default for KeyPolicy {
+    fn default() -> Self {
+        KeyPolicy::Strict
+    }
+}
+
+impl From<Pretty> for Config {
+    fn from(pretty: Pretty) -> Self {
+        Config::new().pretty(pretty)
+    }
+}
+
+impl Config {
+    /**
+    Create the default options for json.
+    */
+    pub fn new() -> Self {
+        Config::default()
+    }
+
+    /**
+    Write json using indentation and newlines.
+    */
+    pub fn pretty(mut self, pretty: Pretty) -> Self {
+        self.pretty = Some(pretty);
+        self
+    }
+
+    /**
+    Write json without any indentation or newlines.
+
+    This is the default.
+    */
+    pub fn compact(mut self) -> Self {
+        self.pretty = None;
+        self
+    }
+
+    /**
+    Set whether to write a newline after the json.
+
+    Pretty json uses its configured newline.
+    Compact json uses `\n`.
+    */
+    pub fn trailing_newline(mut self, trailing_newline: bool) -> Self {
+        self.trailing_newline = trailing_newline;
+        self
+    }
+
+    /**
+    Set how to write 128bit integers.
+    */
+    pub fn big_ints(mut self, big_ints: BigIntPolicy) -> Self {
+        self.big_ints = big_ints;
+        self
+    }
+
+    /**
+    Set how to escape characters in strings.
+    */
+    pub fn escape(mut self, escape: EscapePolicy) -> Self {
+        self.escape = escape;
+        self
+    }
+
+    /**
+    Set how to write floating point values that aren't finite.
+    */
+    pub fn floats(mut self, floats: FloatPolicy) -> Self {
+        self.floats = floats;
+        self
+    }
+
+    /**
+    Set how to write map keys that aren't strings.
+    */
+    pub fn keys(mut self, keys: KeyPolicy) -> Self {
+        self.keys = keys;
+        self
+    }
+
+    /**
+    Write a [`sval::Value`] to a formatter.
+    */
+    pub fn to_fmt(self, fmt: impl Write, v: impl sval::Value) -> Result<(), sval::Error> {
+        crate::fmt::to_fmt_with(fmt, v, self)
+    }
+}
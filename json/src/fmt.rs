@@ -5,39 +5,116 @@ use sval::stream::{
     Stream,
 };
 
-use crate::std::{
-    fmt::{
-        self,
-        Write,
+use crate::{
+    config::{
+        BigIntPolicy,
+        Config,
+        EscapePolicy,
+        FloatPolicy,
+        KeyPolicy,
+    },
+    pretty::Pretty,
+    raw::RAW_JSON_TAG,
+    std::{
+        fmt::{
+            self,
+            Write,
+        },
+        mem,
     },
-    mem,
 };
 
 /**
 Write a [`sval::Value`] to a formatter.
 */
 pub fn to_fmt(fmt: impl Write, v: impl sval::Value) -> Result<(), sval::Error> {
-    let mut fmt = Fmt {
-        stack: Stack::new(),
-        delim: None,
-        out: fmt,
-    };
+    to_fmt_with(fmt, v, Config::default())
+}
+
+/**
+Write a [`sval::Value`] to a formatter, using indentation and newlines.
+*/
+pub fn to_fmt_pretty(fmt: impl Write, v: impl sval::Value) -> Result<(), sval::Error> {
+    Pretty::default().to_fmt(fmt, v)
+}
+
+pub(crate) fn to_fmt_with(
+    fmt: impl Write,
+    v: impl sval::Value,
+    config: Config,
+) -> Result<(), sval::Error> {
+    let mut fmt = Fmt::new(fmt, config);
 
-    sval::stream(v, &mut fmt)
+    fmt.stream(v)?;
+
+    if config.trailing_newline {
+        let newline = config.pretty.map(|pretty| pretty.newline).unwrap_or("\n");
+
+        fmt.out.write_str(newline)?;
+    }
+
+    Ok(())
 }
 
-struct Fmt<W> {
+/**
+The largest integer that can be represented exactly by a 64bit float.
+*/
+const MAX_SAFE_INTEGER: i128 = (1 << 53) - 1;
+
+pub(crate) struct Fmt<W> {
     stack: Stack,
     delim: Option<char>,
-    out: W,
+    config: Config,
+    depth: usize,
+    empty: bool,
+    raw: Raw,
+    pub(crate) out: W,
+}
+
+/**
+The state of a raw json fragment being written.
+*/
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Raw {
+    None,
+    Begin,
+    End,
 }
 
 impl<W> Fmt<W>
 where
     W: Write,
 {
+    pub(crate) fn new(out: W, config: Config) -> Self {
+        Fmt {
+            stack: Stack::new(),
+            delim: None,
+            config,
+            depth: 0,
+            empty: false,
+            raw: Raw::None,
+            out,
+        }
+    }
+
+    /**
+    Write a [`sval::Value`].
+
+    The state of the writer is reset first, so it can be reused
+    for many values, even if writing a previous one failed.
+    */
+    pub(crate) fn stream(&mut self, v: impl sval::Value) -> Result<(), sval::Error> {
+        self.stack.clear();
+        self.delim = None;
+        self.depth = 0;
+        self.empty = false;
+        self.raw = Raw::None;
+
+        sval::stream(v, &mut *self)
+    }
+
     #[inline]
-    fn next_delim(pos: stack::Pos) -> Option<char> {
+    fn next_delim(pos: &stack::Pos) -> Option<char> {
         if pos.is_value() || pos.is_elem() {
             return Some(',');
         }
@@ -46,7 +123,163 @@ where
             return Some(':');
         }
 
-        return None;
+        None
+    }
+
+    /**
+    Write the delimiter that precedes an item at the given position.
+    */
+    #[inline]
+    fn write_delim(&mut self, pos: &stack::Pos, next: Option<char>) -> Result<(), fmt::Error> {
+        if let Some(delim) = mem::replace(&mut self.delim, next) {
+            self.out.write_char(delim)?;
+        }
+
+        if let Some(pretty) = self.config.pretty {
+            if pos.is_value() {
+                self.out.write_char(' ')?;
+            } else if pos.is_key() || pos.is_elem() {
+                self.empty = false;
+                self.write_newline(pretty)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+    Begin a map or sequence.
+    */
+    #[inline]
+    fn write_begin(&mut self, begin: char) -> Result<(), fmt::Error> {
+        self.out.write_char(begin)?;
+
+        self.depth += 1;
+        self.empty = true;
+
+        Ok(())
+    }
+
+    /**
+    End a map or sequence.
+    */
+    #[inline]
+    fn write_end(&mut self, end: char) -> Result<(), fmt::Error> {
+        self.depth -= 1;
+
+        if let Some(pretty) = self.config.pretty {
+            if !mem::replace(&mut self.empty, false) {
+                self.write_newline(pretty)?;
+            }
+        }
+
+        self.out.write_char(end)?;
+
+        Ok(())
+    }
+
+    /**
+    Check whether a non-string primitive can be written as a map key.
+    */
+    #[inline]
+    fn check_key(&self) -> Result<(), stream::Error> {
+        match self.config.keys {
+            KeyPolicy::Strict => Err(stream::Error::msg(
+                "only strings are supported as json keys",
+            )),
+            KeyPolicy::Stringify => Ok(()),
+        }
+    }
+
+    /**
+    Write a non-string primitive, quoting it if it's a map key.
+    */
+    #[inline]
+    fn write_primitive(&mut self, pos: &stack::Pos, v: &str) -> Result<(), stream::Error> {
+        if pos.is_key() {
+            self.check_key()?;
+
+            self.write_delim(pos, Self::next_delim(pos))?;
+
+            self.out.write_char('"')?;
+            self.out.write_str(v)?;
+            self.out.write_char('"')?;
+        } else {
+            self.write_delim(pos, Self::next_delim(pos))?;
+
+            self.out.write_str(v)?;
+        }
+
+        Ok(())
+    }
+
+    /**
+    Write a 128bit integer, checking whether it's in the safe range for json numbers.
+    */
+    #[inline]
+    fn write_big_int(
+        &mut self,
+        pos: &stack::Pos,
+        v: &str,
+        safe: bool,
+    ) -> Result<(), stream::Error> {
+        match self.config.big_ints {
+            BigIntPolicy::String if !safe && !pos.is_key() => {
+                self.write_delim(pos, Self::next_delim(pos))?;
+
+                self.out.write_char('"')?;
+                self.out.write_str(v)?;
+                self.out.write_char('"')?;
+
+                Ok(())
+            }
+            BigIntPolicy::Error if !safe => Err(stream::Error::msg(
+                "the integer is outside of the safe range for json numbers",
+            )),
+            _ => self.write_primitive(pos, v),
+        }
+    }
+
+    /**
+    Write a raw json fragment verbatim.
+    */
+    #[inline]
+    fn write_raw(&mut self, pos: &stack::Pos, v: &str) -> Result<(), stream::Error> {
+        if pos.is_key() {
+            return Err(stream::Error::msg(
+                "raw json is not supported as a json key",
+            ));
+        }
+
+        self.write_delim(pos, Self::next_delim(pos))?;
+
+        if self.config.single_line {
+            // Newlines can only appear in whitespace between json tokens
+            for (i, line) in v.split(|c| c == '\n' || c == '\r').enumerate() {
+                if i > 0 {
+                    self.out.write_char(' ')?;
+                }
+
+                self.out.write_str(line)?;
+            }
+        } else {
+            self.out.write_str(v)?;
+        }
+
+        self.raw = Raw::End;
+
+        Ok(())
+    }
+
+    #[inline]
+    fn write_newline(&mut self, pretty: Pretty) -> Result<(), fmt::Error> {
+        self.out.write_str(pretty.newline)?;
+
+        for _ in 0..self.depth {
+            self.out.write_str(pretty.indent)?;
+        }
+
+        Ok(())
     }
 }
 
@@ -65,11 +298,11 @@ where
     fn fmt(&mut self, v: stream::Arguments) -> Result<(), stream::Error> {
         let pos = self.stack.primitive()?;
 
-        if let Some(delim) = mem::replace(&mut self.delim, Self::next_delim(pos)) {
-            self.out.write_char(delim)?;
-        }
+        self.write_delim(&pos, Self::next_delim(&pos))?;
 
-        fmt::write(&mut Escape(&mut self.out), v)?;
+        self.out.write_char('"')?;
+        fmt::write(&mut Escape(&mut self.out, self.config.escape), v)?;
+        self.out.write_char('"')?;
 
         Ok(())
     }
@@ -78,36 +311,45 @@ where
     fn i64(&mut self, v: i64) -> Result<(), stream::Error> {
         let pos = self.stack.primitive()?;
 
-        if pos.is_key() {
-            return Err(stream::Error::msg(
-                "only strings are supported as json keys",
-            ));
-        }
+        self.write_primitive(&pos, itoa::Buffer::new().format(v))?;
 
-        if let Some(delim) = mem::replace(&mut self.delim, Self::next_delim(pos)) {
-            self.out.write_char(delim)?;
-        }
+        Ok(())
+    }
+
+    #[inline]
+    fn u64(&mut self, v: u64) -> Result<(), stream::Error> {
+        let pos = self.stack.primitive()?;
 
-        self.out.write_str(itoa::Buffer::new().format(v))?;
+        self.write_primitive(&pos, itoa::Buffer::new().format(v))?;
 
         Ok(())
     }
 
     #[inline]
-    fn u64(&mut self, v: u64) -> Result<(), stream::Error> {
+    fn i128(&mut self, v: i128) -> Result<(), stream::Error> {
         let pos = self.stack.primitive()?;
 
-        if pos.is_key() {
-            return Err(stream::Error::msg(
-                "only strings are supported as json keys",
-            ));
-        }
+        let safe = v >= -MAX_SAFE_INTEGER && v <= MAX_SAFE_INTEGER;
+        self.write_big_int(&pos, itoa::Buffer::new().format(v), safe)?;
 
-        if let Some(delim) = mem::replace(&mut self.delim, Self::next_delim(pos)) {
-            self.out.write_char(delim)?;
-        }
+        Ok(())
+    }
 
-        self.out.write_str(itoa::Buffer::new().format(v))?;
+    #[inline]
+    fn u128(&mut self, v: u128) -> Result<(), stream::Error> {
+        let pos = self.stack.primitive()?;
+
+        let safe = v <= MAX_SAFE_INTEGER as u128;
+        self.write_big_int(&pos, itoa::Buffer::new().format(v), safe)?;
+
+        Ok(())
+    }
+
+    #[inline]
+    fn number(&mut self, v: &str) -> Result<(), stream::Error> {
+        let pos = self.stack.primitive()?;
+
+        self.write_primitive(&pos, v)?;
 
         Ok(())
     }
@@ -122,10 +364,27 @@ where
             ));
         }
 
-        if let Some(delim) = mem::replace(&mut self.delim, Self::next_delim(pos)) {
-            self.out.write_char(delim)?;
+        if !v.is_finite() {
+            let v = match self.config.floats {
+                FloatPolicy::Null => "null",
+                FloatPolicy::String if v.is_nan() => "\"NaN\"",
+                FloatPolicy::String if v.is_sign_positive() => "\"Infinity\"",
+                FloatPolicy::String => "\"-Infinity\"",
+                FloatPolicy::Error => {
+                    return Err(stream::Error::msg(
+                        "non-finite floats are not supported in json",
+                    ))
+                }
+            };
+
+            self.write_delim(&pos, Self::next_delim(&pos))?;
+            self.out.write_str(v)?;
+
+            return Ok(());
         }
 
+        self.write_delim(&pos, Self::next_delim(&pos))?;
+
         self.out.write_str(ryu::Buffer::new().format(v))?;
 
         Ok(())
@@ -135,17 +394,7 @@ where
     fn bool(&mut self, v: bool) -> Result<(), stream::Error> {
         let pos = self.stack.primitive()?;
 
-        if pos.is_key() {
-            return Err(stream::Error::msg(
-                "only strings are supported as json keys",
-            ));
-        }
-
-        if let Some(delim) = mem::replace(&mut self.delim, Self::next_delim(pos)) {
-            self.out.write_char(delim)?;
-        }
-
-        self.out.write_str(if v { "true" } else { "false" })?;
+        self.write_primitive(&pos, if v { "true" } else { "false" })?;
 
         Ok(())
     }
@@ -155,16 +404,12 @@ where
         let pos = self.stack.primitive()?;
 
         if pos.is_key() {
-            return Err(stream::Error::msg(
-                "only strings are supported as json keys",
-            ));
+            self.check_key()?;
         }
 
-        if let Some(delim) = mem::replace(&mut self.delim, Self::next_delim(pos)) {
-            self.out.write_char(delim)?;
-        }
+        self.write_delim(&pos, Self::next_delim(&pos))?;
 
-        self.out.write_char(v)?;
+        escape_str(v.encode_utf8(&mut [0; 4]), &mut self.out, self.config.escape)?;
 
         Ok(())
     }
@@ -173,11 +418,13 @@ where
     fn str(&mut self, v: &str) -> Result<(), stream::Error> {
         let pos = self.stack.primitive()?;
 
-        if let Some(delim) = mem::replace(&mut self.delim, Self::next_delim(pos)) {
-            self.out.write_char(delim)?;
+        if self.raw == Raw::Begin {
+            return self.write_raw(&pos, v);
         }
 
-        escape_str(&v, &mut self.out)?;
+        self.write_delim(&pos, Self::next_delim(&pos))?;
+
+        escape_str(v, &mut self.out, self.config.escape)?;
 
         Ok(())
     }
@@ -192,9 +439,7 @@ where
             ));
         }
 
-        if let Some(delim) = mem::replace(&mut self.delim, Self::next_delim(pos)) {
-            self.out.write_char(delim)?;
-        }
+        self.write_delim(&pos, Self::next_delim(&pos))?;
 
         self.out.write_str("null")?;
 
@@ -203,17 +448,16 @@ where
 
     #[inline]
     fn seq_begin(&mut self, _: Option<usize>) -> Result<(), stream::Error> {
-        if self.stack.seq_begin()?.is_key() {
+        let pos = self.stack.seq_begin()?;
+
+        if pos.is_key() {
             return Err(stream::Error::msg(
                 "only strings are supported as json keys",
             ));
         }
 
-        if let Some(delim) = self.delim.take() {
-            self.out.write_char(delim)?;
-        }
-
-        self.out.write_char('[')?;
+        self.write_delim(&pos, None)?;
+        self.write_begin('[')?;
 
         Ok(())
     }
@@ -229,25 +473,24 @@ where
     fn seq_end(&mut self) -> Result<(), stream::Error> {
         let pos = self.stack.seq_end()?;
 
-        self.delim = Self::next_delim(pos);
-        self.out.write_char(']')?;
+        self.delim = Self::next_delim(&pos);
+        self.write_end(']')?;
 
         Ok(())
     }
 
     #[inline]
     fn map_begin(&mut self, _: Option<usize>) -> Result<(), stream::Error> {
-        if self.stack.map_begin()?.is_key() {
+        let pos = self.stack.map_begin()?;
+
+        if pos.is_key() {
             return Err(stream::Error::msg(
                 "only strings are supported as json keys",
             ));
         }
 
-        if let Some(delim) = self.delim.take() {
-            self.out.write_char(delim)?;
-        }
-
-        self.out.write_char('{')?;
+        self.write_delim(&pos, None)?;
+        self.write_begin('{')?;
 
         Ok(())
     }
@@ -270,12 +513,34 @@ where
     fn map_end(&mut self) -> Result<(), stream::Error> {
         let pos = self.stack.map_end()?;
 
-        self.delim = Self::next_delim(pos);
-        self.out.write_char('}')?;
+        self.delim = Self::next_delim(&pos);
+        self.write_end('}')?;
+
+        Ok(())
+    }
+
+    #[inline]
+    fn tagged_begin(&mut self, tag: stream::Tag) -> Result<(), stream::Error> {
+        if tag == RAW_JSON_TAG {
+            self.raw = Raw::Begin;
+        }
 
         Ok(())
     }
 
+    #[inline]
+    fn tagged_end(&mut self) -> Result<(), stream::Error> {
+        match self.raw {
+            Raw::None => Ok(()),
+            Raw::Begin => Err(stream::Error::msg("raw json must be a string")),
+            Raw::End => {
+                self.raw = Raw::None;
+
+                Ok(())
+            }
+        }
+    }
+
     #[inline]
     fn end(&mut self) -> Result<(), stream::Error> {
         self.stack.end()?;
@@ -290,9 +555,28 @@ https://github.com/dtolnay/miniserde
 */
 
 #[inline]
-fn escape_str(value: &str, mut out: impl Write) -> Result<(), fmt::Error> {
+pub(crate) fn escape_str(
+    value: &str,
+    mut out: impl Write,
+    escape: EscapePolicy,
+) -> Result<(), fmt::Error> {
     out.write_char('"')?;
+    escape_chars(value, &mut out, escape)?;
+    out.write_char('"')?;
+
+    Ok(())
+}
 
+#[inline]
+fn escape_chars(value: &str, out: impl Write, escape: EscapePolicy) -> Result<(), fmt::Error> {
+    match escape {
+        EscapePolicy::Minimal => escape_minimal(value, out),
+        EscapePolicy::Ascii | EscapePolicy::Html => escape_extended(value, out, escape),
+    }
+}
+
+#[inline]
+fn escape_minimal(value: &str, mut out: impl Write) -> Result<(), fmt::Error> {
     let bytes = value.as_bytes();
     let mut start = 0;
 
@@ -330,7 +614,68 @@ fn escape_str(value: &str, mut out: impl Write) -> Result<(), fmt::Error> {
         out.write_str(&value[start..])?;
     }
 
-    out.write_char('"')?;
+    Ok(())
+}
+
+/**
+Escape strings using `\uXXXX` sequences for characters beyond the minimal set.
+
+The `Ascii` policy escapes every non-ASCII character.
+The `Html` policy escapes `<`, `>`, `&`, U+2028 and U+2029.
+*/
+fn escape_extended(
+    value: &str,
+    mut out: impl Write,
+    policy: EscapePolicy,
+) -> Result<(), fmt::Error> {
+    let mut start = 0;
+
+    for (i, c) in value.char_indices() {
+        let escape = if c.is_ascii() {
+            match ESCAPE[c as usize] {
+                0 if policy == EscapePolicy::Html && (c == '<' || c == '>' || c == '&') => U,
+                escape => escape,
+            }
+        } else {
+            match policy {
+                EscapePolicy::Ascii => U,
+                _ if c == '\u{2028}' || c == '\u{2029}' => U,
+                _ => 0,
+            }
+        };
+
+        if escape == 0 {
+            continue;
+        }
+
+        if start < i {
+            out.write_str(&value[start..i])?;
+        }
+
+        match escape {
+            self::BB => out.write_str("\\b")?,
+            self::TT => out.write_str("\\t")?,
+            self::NN => out.write_str("\\n")?,
+            self::FF => out.write_str("\\f")?,
+            self::RR => out.write_str("\\r")?,
+            self::QU => out.write_str("\\\"")?,
+            self::BS => out.write_str("\\\\")?,
+            self::U => {
+                let mut utf16 = [0; 2];
+
+                for unit in c.encode_utf16(&mut utf16) {
+                    write!(out, "\\u{:04x}", unit)?;
+                }
+            }
+            _ => unreachable!(),
+        }
+
+        start = i + c.len_utf8();
+    }
+
+    if start != value.len() {
+        out.write_str(&value[start..])?;
+    }
 
     Ok(())
 }
@@ -367,13 +712,13 @@ static ESCAPE: [u8; 256] = [
     0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0, // F
 ];
 
-struct Escape<W>(W);
+struct Escape<W>(W, EscapePolicy);
 
 impl<W> Write for Escape<W>
 where
     W: Write,
 {
     fn write_str(&mut self, s: &str) -> Result<(), fmt::Error> {
-        escape_str(s, &mut self.0)
+        escape_chars(s, &mut self.0, self.1)
     }
 }
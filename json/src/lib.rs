@@ -15,14 +15,58 @@ extern crate std;
 #[cfg(not(feature = "std"))]
 extern crate core as std;
 
+mod config;
 mod fmt;
-pub use self::fmt::to_fmt;
+mod parse;
+mod pretty;
+mod raw;
+mod slice;
 
+pub use self::{
+    config::{
+        BigIntPolicy,
+        Config,
+        EscapePolicy,
+        FloatPolicy,
+        KeyPolicy,
+    },
+    fmt::{
+        to_fmt,
+        to_fmt_pretty,
+    },
+    parse::{
+        from_slice,
+        from_str,
+        JsonStr,
+    },
+    pretty::Pretty,
+    raw::RawJson,
+    slice::{
+        to_slice,
+        SliceError,
+    },
+};
+
+#[cfg(feature = "std")]
+mod canonical;
+#[cfg(feature = "std")]
+mod ndjson;
 #[cfg(feature = "std")]
 mod std_support;
 
+#[cfg(feature = "std")]
+pub use self::{
+    canonical::{
+        to_string_canonical,
+        to_writer_canonical,
+    },
+    ndjson::NdJsonWriter,
+};
+
 #[cfg(feature = "std")]
 pub use self::std_support::{
     to_string,
+    to_string_pretty,
     to_writer,
+    to_writer_pretty,
 };
@@ -0,0 +1,100 @@
+use crate::std::{
+    io::Write,
+    string::String,
+};
+
+use crate::{
+    config::Config,
+    fmt::Fmt,
+};
+
+/**
+A writer for newline delimited json (NDJSON or JSON Lines).
+
+Each value is written as a single line of compact json followed by `\n`.
+The output buffer and stack are reused between values.
+
+Records are always written in full: a value is buffered until it's complete,
+then written using `write_all` and flushed. If a value fails to write then
+nothing is written for it, and the writer can continue with the next value.
+*/
+pub struct NdJsonWriter<W> {
+    fmt: Fmt<String>,
+    inner: W,
+}
+
+impl<W> NdJsonWriter<W>
+where
+    W: Write,
+{
+    /**
+    Create a writer using the default options.
+    */
+    pub fn new(inner: W) -> Self {
+        NdJsonWriter::with_config(inner, Config::default())
+    }
+
+    /**
+    Create a writer using the given options.
+
+    Pretty and trailing newline options are ignored,
+    because each value must be written on a single line.
+    */
+    pub fn with_config(inner: W, config: Config) -> Self {
+        let config = Config {
+            single_line: true,
+            ..config.compact().trailing_newline(false)
+        };
+
+        NdJsonWriter {
+            fmt: Fmt::new(String::new(), config),
+            inner,
+        }
+    }
+
+    /**
+    Write a [`sval::Value`] as a single line.
+    */
+    pub fn write(&mut self, v: impl sval::Value) -> Result<(), sval::Error> {
+        if let Err(err) = self.fmt.stream(v) {
+            self.fmt.out.clear();
+            return Err(err);
+        }
+
+        debug_assert!(
+            !self.fmt.out.contains('\n'),
+            "compact json must not contain newlines"
+        );
+
+        self.fmt.out.push('\n');
+
+        let written = self
+            .inner
+            .write_all(self.fmt.out.as_bytes())
+            .and_then(|_| self.inner.flush());
+        self.fmt.out.clear();
+
+        written.map_err(sval::Error::from_error)
+    }
+
+    /**
+    Get a reference to the underlying writer.
+    */
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /**
+    Get a mutable reference to the underlying writer.
+    */
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /**
+    Get the underlying writer.
+    */
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
@@ -0,0 +1,478 @@
+use sval::{
+    stream::Stream,
+    value,
+};
+
+use crate::std::{
+    char,
+    fmt::{
+        self,
+        Write,
+    },
+    str,
+};
+
+/**
+The maximum depth of nested maps and sequences a document can contain.
+*/
+const MAX_DEPTH: usize = 128;
+
+/**
+Parse a json string and stream it into a [`sval::stream::Stream`].
+
+Strings without escapes are streamed directly from the input without copying,
+so a [`sval::stream::BorrowedStream`] can keep them.
+Strings with escapes are unescaped while they're being streamed as a format.
+
+If the input isn't valid json then the returned error will contain the byte offset
+where the problem was found. The offset is included in the error's `Display` output,
+like `unterminated string at byte offset 7`, whether or not the `std` feature is enabled.
+*/
+pub fn from_str(json: &str, stream: impl Stream) -> Result<(), sval::Error> {
+    sval::stream(JsonStr::new(json), stream)
+}
+
+/**
+Parse a json byte slice and stream it into a [`sval::stream::Stream`].
+
+The input must be valid UTF-8.
+*/
+pub fn from_slice(json: &[u8], stream: impl Stream) -> Result<(), sval::Error> {
+    sval::stream(JsonStr::from_slice(json)?, stream)
+}
+
+/**
+A json document that can be streamed as a [`sval::Value`].
+
+The document is parsed each time it's streamed, so it can be re-encoded
+into any other format without building an intermediate tree.
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct JsonStr<'a>(&'a str);
+
+impl<'a> JsonStr<'a> {
+    /**
+    Treat a string as a json document.
+
+    The string isn't validated until it's streamed.
+    */
+    pub fn new(json: &'a str) -> Self {
+        JsonStr(json)
+    }
+
+    /**
+    Treat a byte slice as a json document.
+
+    The slice must be valid UTF-8.
+    */
+    pub fn from_slice(json: &'a [u8]) -> Result<Self, sval::Error> {
+        str::from_utf8(json)
+            .map(JsonStr)
+            .map_err(|e| error_at("invalid utf-8", e.valid_up_to()))
+    }
+
+    /**
+    Get the underlying json string.
+    */
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+}
+
+impl<'a> sval::Value for JsonStr<'a> {
+    fn stream(&self, stream: &mut value::Stream) -> Result<(), value::Error> {
+        Reader::new(self.0, |_| None).document(stream)
+    }
+
+    fn stream_borrowed<'v>(
+        &'v self,
+        stream: &mut value::Stream<'_, 'v>,
+    ) -> Result<(), value::Error> {
+        Reader::new(self.0, Some).document(stream)
+    }
+}
+
+struct Reader<'a, 'v> {
+    json: &'a str,
+    pos: usize,
+    depth: usize,
+    // Lends strings without escapes to the stream, if they live long enough
+    borrow: fn(&'a str) -> Option<&'v str>,
+}
+
+impl<'a, 'v> Reader<'a, 'v> {
+    fn new(json: &'a str, borrow: fn(&'a str) -> Option<&'v str>) -> Self {
+        Reader {
+            json,
+            pos: 0,
+            depth: 0,
+            borrow,
+        }
+    }
+
+    fn document(&mut self, stream: &mut value::Stream<'_, 'v>) -> Result<(), sval::Error> {
+        self.value(stream)?;
+
+        self.skip_whitespace();
+        if self.pos < self.json.len() {
+            return Err(self.error("trailing characters"));
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn peek(&self) -> Option<u8> {
+        self.json.as_bytes().get(self.pos).cloned()
+    }
+
+    #[inline]
+    fn skip_whitespace(&mut self) {
+        while let Some(b) = self.peek() {
+            match b {
+                b' ' | b'\t' | b'\n' | b'\r' => self.pos += 1,
+                _ => break,
+            }
+        }
+    }
+
+    fn error(&self, msg: &'static str) -> sval::Error {
+        error_at(msg, self.pos)
+    }
+
+    fn value(&mut self, stream: &mut value::Stream<'_, 'v>) -> Result<(), sval::Error> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some(b'{') => self.map(stream),
+            Some(b'[') => self.seq(stream),
+            Some(b'"') => self.string(stream),
+            Some(b't') => {
+                self.literal("true")?;
+                stream.bool(true)
+            }
+            Some(b'f') => {
+                self.literal("false")?;
+                stream.bool(false)
+            }
+            Some(b'n') => {
+                self.literal("null")?;
+                stream.none()
+            }
+            Some(b'-') | Some(b'0'..=b'9') => self.number(stream),
+            Some(_) => Err(self.error("expected a value")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn literal(&mut self, expected: &'static str) -> Result<(), sval::Error> {
+        if self.json[self.pos..].starts_with(expected) {
+            self.pos += expected.len();
+            Ok(())
+        } else {
+            Err(self.error("expected a value"))
+        }
+    }
+
+    fn enter(&mut self) -> Result<(), sval::Error> {
+        if self.depth >= MAX_DEPTH {
+            return Err(self.error("recursion limit exceeded"));
+        }
+
+        self.depth += 1;
+        self.pos += 1;
+
+        Ok(())
+    }
+
+    fn map(&mut self, stream: &mut value::Stream<'_, 'v>) -> Result<(), sval::Error> {
+        self.enter()?;
+        stream.map_begin(None)?;
+
+        self.skip_whitespace();
+        if let Some(b'}') = self.peek() {
+            self.pos += 1;
+        } else {
+            loop {
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(b'"') => {
+                        stream.map_key_begin()?;
+                        self.string(stream)?;
+                    }
+                    Some(_) => return Err(self.error("expected a string key")),
+                    None => return Err(self.error("unexpected end of input")),
+                }
+
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(b':') => self.pos += 1,
+                    Some(_) => return Err(self.error("expected `:`")),
+                    None => return Err(self.error("unexpected end of input")),
+                }
+
+                stream.map_value_begin()?;
+                self.value(stream)?;
+
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(b',') => self.pos += 1,
+                    Some(b'}') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    Some(_) => return Err(self.error("expected `,` or `}`")),
+                    None => return Err(self.error("unexpected end of input")),
+                }
+            }
+        }
+
+        self.depth -= 1;
+        stream.map_end()
+    }
+
+    fn seq(&mut self, stream: &mut value::Stream<'_, 'v>) -> Result<(), sval::Error> {
+        self.enter()?;
+        stream.seq_begin(None)?;
+
+        self.skip_whitespace();
+        if let Some(b']') = self.peek() {
+            self.pos += 1;
+        } else {
+            loop {
+                stream.seq_elem_begin()?;
+                self.value(stream)?;
+
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(b',') => self.pos += 1,
+                    Some(b']') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    Some(_) => return Err(self.error("expected `,` or `]`")),
+                    None => return Err(self.error("unexpected end of input")),
+                }
+            }
+        }
+
+        self.depth -= 1;
+        stream.seq_end()
+    }
+
+    fn string(&mut self, stream: &mut value::Stream<'_, 'v>) -> Result<(), sval::Error> {
+        // Skip the opening quote
+        self.pos += 1;
+
+        let start = self.pos;
+        let mut escaped = false;
+
+        loop {
+            match self.peek() {
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    escaped = true;
+                    self.escape()?;
+                }
+                Some(b) if b < 0x20 => return Err(self.error("control character in string")),
+                Some(_) => self.pos += 1,
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+
+        // The quotes and backslashes are ASCII, so they're always on char boundaries
+        let raw = &self.json[start..self.pos];
+        self.pos += 1;
+
+        if escaped {
+            return stream.fmt(format_args!("{}", Unescape(raw)));
+        }
+
+        match (self.borrow)(raw) {
+            Some(raw) => stream.str_borrowed(raw),
+            None => stream.str(raw),
+        }
+    }
+
+    /**
+    Validate an escape sequence so it can be unescaped later without failing.
+    */
+    fn escape(&mut self) -> Result<(), sval::Error> {
+        let start = self.pos;
+
+        // Skip the backslash
+        self.pos += 1;
+
+        match self.peek() {
+            Some(b'"') | Some(b'\\') | Some(b'/') | Some(b'b') | Some(b'f') | Some(b'n')
+            | Some(b'r') | Some(b't') => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(b'u') => {
+                self.pos += 1;
+                let code = self.hex()?;
+
+                match code {
+                    0xD800..=0xDBFF => {
+                        if !self.json[self.pos..].starts_with("\\u") {
+                            return Err(error_at("unpaired surrogate in escape", start));
+                        }
+
+                        self.pos += 2;
+                        match self.hex()? {
+                            0xDC00..=0xDFFF => Ok(()),
+                            _ => Err(error_at("unpaired surrogate in escape", start)),
+                        }
+                    }
+                    0xDC00..=0xDFFF => Err(error_at("unpaired surrogate in escape", start)),
+                    _ => Ok(()),
+                }
+            }
+            Some(_) => Err(self.error("invalid escape")),
+            None => Err(self.error("unterminated string")),
+        }
+    }
+
+    fn hex(&mut self) -> Result<u32, sval::Error> {
+        match self.json.get(self.pos..self.pos + 4).and_then(parse_hex) {
+            Some(code) => {
+                self.pos += 4;
+                Ok(code)
+            }
+            None => Err(self.error("invalid unicode escape")),
+        }
+    }
+
+    fn number(&mut self, stream: &mut value::Stream<'_, 'v>) -> Result<(), sval::Error> {
+        let start = self.pos;
+
+        let negative = self.peek() == Some(b'-');
+        if negative {
+            self.pos += 1;
+        }
+
+        match self.peek() {
+            Some(b'0') => self.pos += 1,
+            Some(b'1'..=b'9') => self.digits(),
+            _ => return Err(self.error("invalid number")),
+        }
+
+        let mut float = false;
+
+        if let Some(b'.') = self.peek() {
+            float = true;
+            self.pos += 1;
+            self.required_digits()?;
+        }
+
+        match self.peek() {
+            Some(b'e') | Some(b'E') => {
+                float = true;
+                self.pos += 1;
+
+                match self.peek() {
+                    Some(b'+') | Some(b'-') => self.pos += 1,
+                    _ => (),
+                }
+                self.required_digits()?;
+            }
+            _ => (),
+        }
+
+        let num = &self.json[start..self.pos];
+
+        if !float {
+            if negative {
+                if let Ok(v) = num.parse::<i64>() {
+                    return stream.i64(v);
+                }
+                if let Ok(v) = num.parse::<i128>() {
+                    return stream.i128(v);
+                }
+            } else {
+                if let Ok(v) = num.parse::<u64>() {
+                    return stream.u64(v);
+                }
+                if let Ok(v) = num.parse::<u128>() {
+                    return stream.u128(v);
+                }
+            }
+        }
+
+        match num.parse::<f64>() {
+            Ok(v) if v.is_finite() => stream.f64(v),
+            _ => Err(error_at("number out of range", start)),
+        }
+    }
+
+    fn digits(&mut self) {
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn required_digits(&mut self) -> Result<(), sval::Error> {
+        match self.peek() {
+            Some(b'0'..=b'9') => {
+                self.digits();
+                Ok(())
+            }
+            _ => Err(self.error("invalid number")),
+        }
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<u32> {
+    if hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        u32::from_str_radix(hex, 16).ok()
+    } else {
+        None
+    }
+}
+
+/**
+A string containing escape sequences that have already been validated.
+*/
+struct Unescape<'a>(&'a str);
+
+impl<'a> fmt::Display for Unescape<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut rest = self.0;
+
+        while let Some(i) = rest.find('\\') {
+            f.write_str(&rest[..i])?;
+
+            let escape = &rest[i + 1..];
+            let (c, len) = match escape.as_bytes()[0] {
+                b'b' => ('\x08', 1),
+                b'f' => ('\x0C', 1),
+                b'n' => ('\n', 1),
+                b'r' => ('\r', 1),
+                b't' => ('\t', 1),
+                b'u' => {
+                    let code = parse_hex(&escape[1..5]).ok_or(fmt::Error)?;
+
+                    if code >= 0xD800 && code <= 0xDBFF {
+                        let low = parse_hex(&escape[7..11]).ok_or(fmt::Error)?;
+                        let code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+
+                        (char::from_u32(code).ok_or(fmt::Error)?, 11)
+                    } else {
+                        (char::from_u32(code).ok_or(fmt::Error)?, 5)
+                    }
+                }
+                b => (b as char, 1),
+            };
+
+            f.write_char(c)?;
+            rest = &escape[len..];
+        }
+
+        f.write_str(rest)
+    }
+}
+
+fn error_at(msg: &'static str, offset: usize) -> sval::Error {
+    sval::Error::msg_at(msg, offset)
+}
@@ -0,0 +1,56 @@
+use crate::{
+    config::Config,
+    std::fmt::Write,
+};
+
+/**
+Options for writing json using indentation and newlines.
+
+The default options indent with two spaces and break lines with `\n`.
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Pretty {
+    pub(crate) indent: &'static str,
+    pub(crate) newline: &'static str,
+}
+
+impl Default for Pretty {
+    fn default() -> Self {
+        Pretty {
+            indent: "  ",
+            newline: "\n",
+        }
+    }
+}
+
+impl Pretty {
+    /**
+    Create the default options for pretty json.
+    */
+    pub fn new() -> Self {
+        Pretty::default()
+    }
+
+    /**
+    Set the string to write for each level of indentation.
+    */
+    pub fn indent(mut self, indent: &'static str) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /**
+    Set the string to write for each newline.
+    */
+    pub fn newline(mut self, newline: &'static str) -> Self {
+        self.newline = newline;
+        self
+    }
+
+    /**
+    Write a [`sval::Value`] to a formatter.
+    */
+    pub fn to_fmt(self, fmt: impl Write, v: impl sval::Value) -> Result<(), sval::Error> {
+        Config::from(self).to_fmt(fmt, v)
+    }
+}
@@ -0,0 +1,70 @@
+use sval::{
+    stream::Tag,
+    value,
+};
+
+use crate::parse::JsonStr;
+
+/**
+The tag used to recognize raw json in a stream.
+*/
+pub(crate) const RAW_JSON_TAG: Tag = Tag::Custom("sval_json::RawJson");
+
+/**
+A fragment of json that's written verbatim.
+
+The json writers in this library will write the fragment as-is, without
+parsing or escaping it. Other streams see the fragment as a string,
+tagged with `Tag::Custom("sval_json::RawJson")`.
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct RawJson<'a>(&'a str);
+
+impl<'a> RawJson<'a> {
+    /**
+    Treat a string as a raw json fragment, checking that it's valid json.
+    */
+    pub fn new(json: &'a str) -> Result<Self, sval::Error> {
+        sval::stream(JsonStr::new(json), Validate)?;
+
+        Ok(RawJson(json))
+    }
+
+    /**
+    Treat a string as a raw json fragment, without checking that it's valid json.
+
+    The string will be written as-is, so if it isn't valid json then the
+    output won't be either.
+    */
+    pub fn new_unchecked(json: &'a str) -> Self {
+        RawJson(json)
+    }
+
+    /**
+    Get the underlying json string.
+    */
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+
+    /**
+    Get a value that parses the fragment instead of writing it verbatim.
+    */
+    pub fn parse(&self) -> JsonStr<'a> {
+        JsonStr::new(self.0)
+    }
+}
+
+impl<'a> sval::Value for RawJson<'a> {
+    fn stream(&self, stream: &mut value::Stream) -> Result<(), value::Error> {
+        stream.tagged(RAW_JSON_TAG, self.0)
+    }
+}
+
+struct Validate;
+
+impl sval::stream::Stream for Validate {
+    fn fmt(&mut self, _: sval::stream::Arguments) -> Result<(), sval::stream::Error> {
+        Ok(())
+    }
+}
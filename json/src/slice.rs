@@ -0,0 +1,156 @@
+use crate::{
+    config::Config,
+    std::fmt::{
+        self,
+        Write,
+    },
+};
+
+/**
+Write a [`sval::Value`] into a byte buffer.
+
+The number of bytes written is returned.
+If the buffer isn't big enough then the returned error will contain
+the number of bytes needed to write the value.
+*/
+pub fn to_slice(buf: &mut [u8], v: impl sval::Value) -> Result<usize, SliceError> {
+    Config::default().to_slice(buf, v)
+}
+
+impl Config {
+    /**
+    Write a [`sval::Value`] into a byte buffer.
+    */
+    pub fn to_slice(self, buf: &mut [u8], v: impl sval::Value) -> Result<usize, SliceError> {
+        let mut writer = SliceWriter { buf, len: 0 };
+
+        self.to_fmt(&mut writer, v).map_err(SliceError::stream)?;
+
+        if writer.len > writer.buf.len() {
+            Err(SliceError::buffer_too_small(writer.len))
+        } else {
+            Ok(writer.len)
+        }
+    }
+}
+
+/**
+An error writing json into a byte buffer.
+*/
+pub struct SliceError(SliceErrorInner);
+
+enum SliceErrorInner {
+    BufferTooSmall { needed: usize },
+    Stream(sval::Error),
+}
+
+impl SliceError {
+    fn buffer_too_small(needed: usize) -> Self {
+        SliceError(SliceErrorInner::BufferTooSmall { needed })
+    }
+
+    fn stream(err: sval::Error) -> Self {
+        SliceError(SliceErrorInner::Stream(err))
+    }
+
+    /**
+    Whether the error was caused by the buffer being too small.
+    */
+    pub fn is_buffer_too_small(&self) -> bool {
+        self.needed().is_some()
+    }
+
+    /**
+    The number of bytes needed to write the value, if the buffer was too small.
+    */
+    pub fn needed(&self) -> Option<usize> {
+        match self.0 {
+            SliceErrorInner::BufferTooSmall { needed } => Some(needed),
+            SliceErrorInner::Stream(_) => None,
+        }
+    }
+
+    /**
+    Convert into an [`sval::Error`].
+
+    When `std` is available, an error caused by the buffer being too small
+    keeps this `SliceError` as its source, so the number of bytes needed
+    can still be retrieved.
+    */
+    pub fn into_error(self) -> sval::Error {
+        match self.0 {
+            SliceErrorInner::BufferTooSmall { needed } => buffer_too_small_error(needed),
+            SliceErrorInner::Stream(err) => err,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+fn buffer_too_small_error(needed: usize) -> sval::Error {
+    sval::Error::from_error(SliceError::buffer_too_small(needed))
+}
+
+#[cfg(not(feature = "std"))]
+fn buffer_too_small_error(_: usize) -> sval::Error {
+    sval::Error::msg("buffer too small")
+}
+
+// When `std` is available, `sval::Error` already converts from any `std::error::Error`
+// using `sval::Error::from_error`, so the `SliceError` is kept as the source
+#[cfg(not(feature = "std"))]
+impl From<SliceError> for sval::Error {
+    fn from(err: SliceError) -> Self {
+        err.into_error()
+    }
+}
+
+#[cfg(feature = "std")]
+impl crate::std::error::Error for SliceError {}
+
+impl fmt::Debug for SliceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            SliceErrorInner::BufferTooSmall { needed } => f
+                .debug_struct("BufferTooSmall")
+                .field("needed", &needed)
+                .finish(),
+            SliceErrorInner::Stream(ref err) => f.debug_tuple("Stream").field(err).finish(),
+        }
+    }
+}
+
+impl fmt::Display for SliceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            SliceErrorInner::BufferTooSmall { needed } => {
+                write!(f, "buffer too small: {} bytes are needed", needed)
+            }
+            SliceErrorInner::Stream(ref err) => err.fmt(f),
+        }
+    }
+}
+
+/**
+Write into a byte buffer.
+
+Once the buffer is full, the remaining output is counted but not written,
+so the total number of bytes needed is known.
+*/
+struct SliceWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> Write for SliceWriter<'a> {
+    fn write_str(&mut self, s: &str) -> Result<(), fmt::Error> {
+        let end = self.len + s.len();
+
+        if end <= self.buf.len() {
+            self.buf[self.len..end].copy_from_slice(s.as_bytes());
+        }
+
+        self.len = end;
+
+        Ok(())
+    }
+}
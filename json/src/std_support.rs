@@ -1,7 +1,16 @@
-use crate::std::string::String;
 use crate::std::{
     fmt,
-    io::Write,
+    io::{
+        self,
+        Write,
+    },
+    string::String,
+    vec::Vec,
+};
+
+use crate::{
+    config::Config,
+    pretty::Pretty,
 };
 
 /**
@@ -15,21 +24,140 @@ pub fn to_string(v: impl sval::Value) -> Result<String, sval::Error> {
     Ok(out)
 }
 
+/**
+Write a [`sval::Value`] to a string, using indentation and newlines.
+*/
+pub fn to_string_pretty(v: impl sval::Value) -> Result<String, sval::Error> {
+    Pretty::default().to_string(v)
+}
+
 /**
 Write a [`sval::Value`] to a writer.
 */
 pub fn to_writer(writer: impl Write, v: impl sval::Value) -> Result<(), sval::Error> {
-    crate::to_fmt(Writer(writer), v)
+    Config::default().to_writer(writer, v)
+}
+
+/**
+Write a [`sval::Value`] to a writer, using indentation and newlines.
+*/
+pub fn to_writer_pretty(writer: impl Write, v: impl sval::Value) -> Result<(), sval::Error> {
+    Pretty::default().to_writer(writer, v)
+}
+
+impl Pretty {
+    /**
+    Write a [`sval::Value`] to a string.
+    */
+    pub fn to_string(self, v: impl sval::Value) -> Result<String, sval::Error> {
+        let mut out = String::new();
+
+        self.to_fmt(&mut out, v)?;
+
+        Ok(out)
+    }
+
+    /**
+    Write a [`sval::Value`] to a writer.
+    */
+    pub fn to_writer(self, writer: impl Write, v: impl sval::Value) -> Result<(), sval::Error> {
+        Config::from(self).to_writer(writer, v)
+    }
+}
+
+impl Config {
+    /**
+    Write a [`sval::Value`] to a string.
+    */
+    pub fn to_string(self, v: impl sval::Value) -> Result<String, sval::Error> {
+        let mut out = String::new();
+
+        self.to_fmt(&mut out, v)?;
+
+        Ok(out)
+    }
+
+    /**
+    Write a [`sval::Value`] to a writer.
+
+    The json is buffered and written using `write_all`,
+    then the writer is flushed.
+    If the writer fails then the original `io::Error` can be retrieved
+    from the returned error using [`sval::Error::source`] or [`sval::Error::into_io_error`].
+    */
+    pub fn to_writer(self, writer: impl Write, v: impl sval::Value) -> Result<(), sval::Error> {
+        let mut writer = Writer::new(writer);
+
+        match self.to_fmt(&mut writer, v) {
+            Ok(()) => writer.flush(),
+            Err(err) => Err(writer.take_err().unwrap_or(err)),
+        }
+    }
 }
 
-struct Writer<W>(W);
+/**
+A buffered adapter from `io::Write` to `fmt::Write`.
+*/
+pub(crate) struct Writer<W> {
+    buf: Vec<u8>,
+    err: Option<io::Error>,
+    inner: W,
+}
+
+impl<W> Writer<W>
+where
+    W: Write,
+{
+    /**
+    The size of the buffer to fill before writing to the underlying writer.
+    */
+    const CAPACITY: usize = 8 * 1024;
+
+    pub(crate) fn new(inner: W) -> Self {
+        Writer {
+            buf: Vec::with_capacity(Self::CAPACITY),
+            err: None,
+            inner,
+        }
+    }
+
+    /**
+    Write any buffered json to the underlying writer and flush it.
+    */
+    pub(crate) fn flush(&mut self) -> Result<(), sval::Error> {
+        self.write_buf()
+            .and_then(|_| self.inner.flush())
+            .map_err(sval::Error::from_error)
+    }
+
+    /**
+    Take the io error that caused a write to fail.
+    */
+    pub(crate) fn take_err(&mut self) -> Option<sval::Error> {
+        self.err.take().map(sval::Error::from_error)
+    }
+
+    fn write_buf(&mut self) -> Result<(), io::Error> {
+        let written = self.inner.write_all(&self.buf);
+        self.buf.clear();
+
+        written
+    }
+}
 
 impl<W> fmt::Write for Writer<W>
 where
     W: Write,
 {
     fn write_str(&mut self, s: &str) -> Result<(), fmt::Error> {
-        self.0.write(s.as_bytes()).map_err(|_| fmt::Error)?;
+        self.buf.extend_from_slice(s.as_bytes());
+
+        if self.buf.len() >= Self::CAPACITY {
+            if let Err(err) = self.write_buf() {
+                self.err = Some(err);
+                return Err(fmt::Error);
+            }
+        }
 
         Ok(())
     }
@@ -4,8 +4,8 @@ extern crate serde;
 #[macro_use]
 extern crate sval;
 
-#[macro_use]
-extern crate miniserde;
+// `miniserde`'s `#[macro_use]` exports clash with `serde`'s derives
+use miniserde::MiniSerialize;
 
 #[test]
 fn sval_json_is_valid() {
@@ -17,6 +17,981 @@ fn sval_json_is_valid() {
     serde_json::from_str::<Twitter>(&json).unwrap();
 }
 
+#[test]
+fn sval_json_pretty_is_valid() {
+    let s: Twitter =
+        serde_json::from_str(&std::fs::read_to_string("twitter.json").unwrap()).unwrap();
+
+    let compact = sval_json::to_string(&s).unwrap();
+    let pretty = sval_json::to_string_pretty(&s).unwrap();
+
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&compact).unwrap(),
+        serde_json::from_str::<serde_json::Value>(&pretty).unwrap()
+    );
+}
+
+#[test]
+fn sval_json_pretty_matches_serde_json() {
+    #[derive(Serialize, Value)]
+    struct Data {
+        id: u64,
+        name: String,
+        tags: Vec<String>,
+        nested: Vec<Nested>,
+        empty: Vec<Nested>,
+    }
+
+    #[derive(Serialize, Value)]
+    struct Nested {
+        a: Option<i32>,
+        b: bool,
+    }
+
+    let data = Data {
+        id: 1,
+        name: String::from("data"),
+        tags: vec![String::from("a"), String::from("b")],
+        nested: vec![
+            Nested {
+                a: Some(1),
+                b: true,
+            },
+            Nested { a: None, b: false },
+        ],
+        empty: vec![],
+    };
+
+    assert_eq!(
+        serde_json::to_string_pretty(&data).unwrap(),
+        sval_json::to_string_pretty(&data).unwrap()
+    );
+}
+
+#[test]
+fn sval_json_pretty_empty() {
+    #[derive(Value)]
+    struct Data {
+        map: std::collections::BTreeMap<String, i32>,
+        seq: Vec<i32>,
+        nested: Vec<Vec<i32>>,
+    }
+
+    let json = sval_json::to_string_pretty(Data {
+        map: Default::default(),
+        seq: Vec::new(),
+        nested: vec![vec![]],
+    })
+    .unwrap();
+
+    assert_eq!(
+        "{\n  \"map\": {},\n  \"seq\": [],\n  \"nested\": [\n    []\n  ]\n}",
+        json
+    );
+}
+
+#[test]
+fn sval_json_pretty_custom() {
+    let json = sval_json::Pretty::new()
+        .indent("\t")
+        .newline("\r\n")
+        .to_string(vec![vec![1, 2], vec![3]])
+        .unwrap();
+
+    assert_eq!("[\r\n\t[\r\n\t\t1,\r\n\t\t2\r\n\t],\r\n\t[\r\n\t\t3\r\n\t]\r\n]", json);
+
+    let mut buf = Vec::new();
+    sval_json::to_writer_pretty(&mut buf, vec![1]).unwrap();
+
+    assert_eq!("[\n  1\n]", String::from_utf8(buf).unwrap());
+}
+
+#[test]
+fn sval_json_non_finite_floats() {
+    let floats = vec![1.5, f64::NAN, f64::INFINITY, f64::NEG_INFINITY];
+
+    assert_eq!("[1.5,null,null,null]", sval_json::to_string(&floats).unwrap());
+
+    assert_eq!(
+        "[1.5,null,null,null]",
+        sval_json::Config::new()
+            .floats(sval_json::FloatPolicy::Null)
+            .to_string(&floats)
+            .unwrap()
+    );
+
+    assert_eq!(
+        "[1.5,\"NaN\",\"Infinity\",\"-Infinity\"]",
+        sval_json::Config::new()
+            .floats(sval_json::FloatPolicy::String)
+            .to_string(&floats)
+            .unwrap()
+    );
+
+    for v in &[f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        assert!(sval_json::Config::new()
+            .floats(sval_json::FloatPolicy::Error)
+            .to_string(v)
+            .is_err());
+    }
+
+    assert_eq!(
+        "1.5",
+        sval_json::Config::new()
+            .floats(sval_json::FloatPolicy::Error)
+            .to_string(1.5)
+            .unwrap()
+    );
+}
+
+#[test]
+fn sval_json_key_policy() {
+    use std::collections::BTreeMap;
+
+    let mut ints = BTreeMap::new();
+    ints.insert(-1i64, "a");
+    ints.insert(2i64, "b");
+
+    let mut chars = BTreeMap::new();
+    chars.insert('a', 1);
+    chars.insert('"', 2);
+
+    let mut bools = BTreeMap::new();
+    bools.insert(true, 1);
+
+    let stringify = sval_json::Config::new().keys(sval_json::KeyPolicy::Stringify);
+
+    assert_eq!(
+        serde_json::to_string(&ints).unwrap(),
+        stringify.to_string(&ints).unwrap()
+    );
+    assert_eq!(
+        serde_json::to_string(&chars).unwrap(),
+        stringify.to_string(&chars).unwrap()
+    );
+    assert_eq!("{\"true\":1}", stringify.to_string(&bools).unwrap());
+
+    assert!(sval_json::to_string(&ints).is_err());
+    assert!(sval_json::to_string(&chars).is_err());
+    assert!(sval_json::to_string(&bools).is_err());
+
+    struct FloatKey;
+
+    impl sval::Value for FloatKey {
+        fn stream(&self, stream: &mut sval::value::Stream) -> Result<(), sval::value::Error> {
+            stream.map_begin(Some(1))?;
+            stream.map_key(1.5)?;
+            stream.map_value(1)?;
+            stream.map_end()
+        }
+    }
+
+    assert!(stringify.to_string(FloatKey).is_err());
+}
+
+#[test]
+fn sval_json_char_is_string() {
+    assert_eq!(
+        serde_json::to_string(&['a', '"', '\n']).unwrap(),
+        sval_json::to_string(&['a', '"', '\n'][..]).unwrap()
+    );
+}
+
+#[test]
+fn sval_json_config() {
+    let v = vec![1, 2];
+
+    assert_eq!(
+        sval_json::to_string(&v).unwrap(),
+        sval_json::Config::new().to_string(&v).unwrap()
+    );
+    assert_eq!(
+        sval_json::to_string_pretty(&v).unwrap(),
+        sval_json::Config::new()
+            .pretty(sval_json::Pretty::new())
+            .to_string(&v)
+            .unwrap()
+    );
+    assert_eq!(
+        sval_json::to_string(&v).unwrap(),
+        sval_json::Config::from(sval_json::Pretty::new())
+            .compact()
+            .to_string(&v)
+            .unwrap()
+    );
+
+    let config = sval_json::Config::new().trailing_newline(true);
+
+    assert_eq!("[1,2]\n", config.to_string(&v).unwrap());
+
+    let mut buf = Vec::new();
+    config.to_writer(&mut buf, &v).unwrap();
+    assert_eq!("[1,2]\n", String::from_utf8(buf).unwrap());
+
+    let mut json = String::new();
+    config
+        .pretty(sval_json::Pretty::new().newline("\r\n"))
+        .to_fmt(&mut json, &v)
+        .unwrap();
+    assert_eq!("[\r\n  1,\r\n  2\r\n]\r\n", json);
+}
+
+#[test]
+fn sval_json_escape_policy() {
+    let s = "<a href=\"x\">&</a> é \u{2028} 😀\n";
+
+    assert_eq!(
+        serde_json::to_string(s).unwrap(),
+        sval_json::to_string(s).unwrap()
+    );
+
+    assert_eq!(
+        "\"<a href=\\\"x\\\">&</a> \\u00e9 \\u2028 \\ud83d\\ude00\\n\"",
+        sval_json::Config::new()
+            .escape(sval_json::EscapePolicy::Ascii)
+            .to_string(s)
+            .unwrap()
+    );
+
+    assert_eq!(
+        "\"\\u003ca href=\\\"x\\\"\\u003e\\u0026\\u003c/a\\u003e é \\u2028 😀\\n\"",
+        sval_json::Config::new()
+            .escape(sval_json::EscapePolicy::Html)
+            .to_string(s)
+            .unwrap()
+    );
+
+    // Escaped strings are still valid json
+    for escape in &[sval_json::EscapePolicy::Ascii, sval_json::EscapePolicy::Html] {
+        let config = sval_json::Config::new().escape(*escape);
+
+        assert_eq!(
+            s,
+            serde_json::from_str::<String>(&config.to_string(s).unwrap()).unwrap()
+        );
+        assert_eq!(
+            format!("{}", 'é'),
+            serde_json::from_str::<String>(&config.to_string('é').unwrap()).unwrap()
+        );
+        assert_eq!(
+            s,
+            serde_json::from_str::<String>(&config.to_string(Display(s)).unwrap()).unwrap()
+        );
+    }
+
+    struct Display<'a>(&'a str);
+
+    impl<'a> sval::Value for Display<'a> {
+        fn stream(&self, stream: &mut sval::value::Stream) -> Result<(), sval::value::Error> {
+            stream.fmt(format_args!("{}", self.0))
+        }
+    }
+}
+
+#[test]
+fn sval_json_big_int_policy() {
+    let big = vec![1u128, 1 << 53, u128::MAX];
+    let signed = vec![-(1i128 << 53), -1, i128::MIN];
+
+    assert_eq!(
+        serde_json::to_string(&big).unwrap(),
+        sval_json::to_string(&big).unwrap()
+    );
+    assert_eq!(
+        serde_json::to_string(&signed).unwrap(),
+        sval_json::to_string(&signed).unwrap()
+    );
+
+    let string = sval_json::Config::new().big_ints(sval_json::BigIntPolicy::String);
+
+    assert_eq!(
+        "[1,\"9007199254740992\",\"340282366920938463463374607431768211455\"]",
+        string.to_string(&big).unwrap()
+    );
+    assert_eq!(
+        "[\"-9007199254740992\",-1,\"-170141183460469231731687303715884105728\"]",
+        string.to_string(&signed).unwrap()
+    );
+    assert_eq!("[9007199254740991]", string.to_string(&[(1u128 << 53) - 1][..]).unwrap());
+
+    let error = sval_json::Config::new().big_ints(sval_json::BigIntPolicy::Error);
+
+    assert_eq!("[-9007199254740991,1]", error.to_string(&[-((1i128 << 53) - 1), 1][..]).unwrap());
+    assert!(error.to_string(1u128 << 53).is_err());
+    assert!(error.to_string(-(1i128 << 53)).is_err());
+}
+
+#[test]
+fn sval_json_number() {
+    struct Number(&'static str);
+
+    impl sval::Value for Number {
+        fn stream(&self, stream: &mut sval::value::Stream) -> Result<(), sval::value::Error> {
+            stream.number(self.0)
+        }
+    }
+
+    let big = "-340282366920938463463374607431768211456.000000000000000000001";
+
+    assert_eq!(big, sval_json::to_string(Number(big)).unwrap());
+    assert_eq!(
+        "[1.50,2e-3]",
+        sval_json::to_string(&[Number("1.50"), Number("2e-3")][..]).unwrap()
+    );
+    assert_eq!(
+        format!("\"{}\"", big),
+        serde_json::to_string(&sval::serde::to_serialize(Number(big))).unwrap()
+    );
+
+    assert_eq!(
+        "[1.5,0.002]",
+        sval_json::to_string_canonical(&[Number("1.50"), Number("2e-3")][..]).unwrap()
+    );
+    assert!(sval_json::to_string_canonical(Number("1e400")).is_err());
+
+    assert!(sval_json::to_string(Number("1.")).is_err());
+    assert!(sval_json::to_string(Number("NaN")).is_err());
+}
+
+#[test]
+fn sval_json_tags() {
+    struct Tags;
+
+    impl sval::Value for Tags {
+        fn stream(&self, stream: &mut sval::value::Stream) -> Result<(), sval::value::Error> {
+            stream.map_begin(Some(2))?;
+
+            stream
+                .map_key_begin()?
+                .tagged(sval::stream::Tag::Uri, "https://docs.rs/sval")?;
+            stream.map_value_begin()?.tagged_begin(sval::stream::Tag::Custom("ids"))?;
+            stream.seq_begin(Some(2))?;
+            stream.seq_elem(1)?;
+            stream.seq_elem(2)?;
+            stream.seq_end()?;
+            stream.tagged_end()?;
+
+            stream.map_key("at")?;
+            stream
+                .map_value_begin()?
+                .tagged(sval::stream::Tag::Rfc3339, "1985-04-12T23:20:50.52Z")?;
+
+            stream.map_end()
+        }
+    }
+
+    assert_eq!(
+        "{\"https://docs.rs/sval\":[1,2],\"at\":\"1985-04-12T23:20:50.52Z\"}",
+        sval_json::to_string(Tags).unwrap()
+    );
+    assert_eq!(
+        "{\"at\":\"1985-04-12T23:20:50.52Z\",\"https://docs.rs/sval\":[1,2]}",
+        sval_json::to_string_canonical(Tags).unwrap()
+    );
+}
+
+#[test]
+fn sval_json_to_writer() {
+    use std::io;
+
+    // A writer that only accepts one byte at a time
+    struct Partial(Vec<u8>, bool);
+
+    impl io::Write for Partial {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            match buf.first() {
+                Some(b) => {
+                    self.0.push(*b);
+                    Ok(1)
+                }
+                None => Ok(0),
+            }
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.1 = true;
+            Ok(())
+        }
+    }
+
+    // A writer that fails after a number of bytes
+    struct Fail(usize);
+
+    impl io::Write for Fail {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if buf.len() > self.0 {
+                return Err(io::Error::new(io::ErrorKind::BrokenPipe, "broken pipe"));
+            }
+
+            self.0 -= buf.len();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    // A writer that fails when flushed
+    struct FailFlush;
+
+    impl io::Write for FailFlush {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Err(io::Error::new(io::ErrorKind::Interrupted, "interrupted"))
+        }
+    }
+
+    let s: Twitter =
+        serde_json::from_str(&std::fs::read_to_string("twitter.json").unwrap()).unwrap();
+    let expected = sval_json::to_string(&s).unwrap();
+
+    let mut partial = Partial(Vec::new(), false);
+    sval_json::to_writer(&mut partial, &s).unwrap();
+    assert_eq!(expected, String::from_utf8(partial.0).unwrap());
+    assert!(partial.1);
+
+    // The error is returned while writing a buffered chunk
+    let err = sval_json::to_writer(Fail(1024), &s).unwrap_err();
+    assert_eq!(
+        io::ErrorKind::BrokenPipe,
+        err.source()
+            .and_then(|err| err.downcast_ref::<io::Error>())
+            .unwrap()
+            .kind()
+    );
+
+    // The error is returned while writing the final chunk
+    let err = sval_json::to_writer(Fail(1), vec![1, 2, 3]).unwrap_err();
+    assert_eq!(io::ErrorKind::BrokenPipe, err.into_io_error().kind());
+
+    // The error is returned while flushing the writer
+    let err = sval_json::to_writer(FailFlush, vec![1, 2, 3]).unwrap_err();
+    assert_eq!(io::ErrorKind::Interrupted, err.into_io_error().kind());
+}
+
+#[test]
+fn sval_json_ndjson() {
+    use std::{
+        collections::BTreeMap,
+        io,
+    };
+
+    #[derive(Default)]
+    struct Lines {
+        buf: Vec<u8>,
+        flushed: Vec<usize>,
+    }
+
+    impl io::Write for Lines {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.buf.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.flushed.push(self.buf.len());
+            Ok(())
+        }
+    }
+
+    #[derive(Value)]
+    struct Record {
+        id: u32,
+        msg: &'static str,
+    }
+
+    let mut invalid = BTreeMap::new();
+    invalid.insert(1, 1);
+
+    let mut writer = sval_json::NdJsonWriter::with_config(
+        Lines::default(),
+        sval_json::Config::new()
+            .pretty(sval_json::Pretty::new())
+            .trailing_newline(true),
+    );
+
+    writer
+        .write(Record {
+            id: 1,
+            msg: "multi\nline",
+        })
+        .unwrap();
+    assert!(writer.write(&invalid).is_err());
+    writer.write(vec![1, 2]).unwrap();
+    writer.write("done").unwrap();
+
+    let lines = writer.into_inner();
+
+    assert_eq!(
+        "{\"id\":1,\"msg\":\"multi\\nline\"}\n[1,2]\n\"done\"\n",
+        String::from_utf8(lines.buf).unwrap()
+    );
+    assert_eq!(vec![29, 35, 42], lines.flushed);
+}
+
+#[test]
+fn sval_json_raw_json() {
+    #[derive(Value)]
+    struct Doc<'a> {
+        id: u32,
+        cached: sval_json::RawJson<'a>,
+    }
+
+    let cached = sval_json::RawJson::new("{\n  \"a\": [1, 2.50, \"x\"]\n}").unwrap();
+
+    assert_eq!(
+        "{\"id\":1,\"cached\":{\n  \"a\": [1, 2.50, \"x\"]\n}}",
+        sval_json::to_string(Doc { id: 1, cached }).unwrap()
+    );
+    assert_eq!(
+        "[\n  true,\n  {\"a\":1}\n]",
+        sval_json::to_string_pretty(vec![
+            sval_json::RawJson::new("true").unwrap(),
+            sval_json::RawJson::new("{\"a\":1}").unwrap(),
+        ])
+        .unwrap()
+    );
+
+    // Raw json is written on a single line in NDJSON
+    let mut writer = sval_json::NdJsonWriter::new(Vec::new());
+    writer.write(Doc { id: 1, cached }).unwrap();
+    assert_eq!(
+        "{\"id\":1,\"cached\":{   \"a\": [1, 2.50, \"x\"] }}\n",
+        String::from_utf8(writer.into_inner()).unwrap()
+    );
+
+    // Other streams see a string, or can parse the fragment
+    assert_eq!(
+        "\"true\"",
+        serde_json::to_string(&sval::serde::to_serialize(
+            sval_json::RawJson::new("true").unwrap()
+        ))
+        .unwrap()
+    );
+    assert_eq!(
+        "{\"a\":[1,2.5,\"x\"]}",
+        serde_json::to_string(&sval::serde::to_serialize(cached.parse())).unwrap()
+    );
+
+    assert!(sval_json::RawJson::new("{\"a\":}").is_err());
+    assert_eq!(
+        "[1,}",
+        sval_json::to_string(sval_json::RawJson::new_unchecked("[1,}")).unwrap()
+    );
+
+    struct RawKey;
+
+    impl sval::Value for RawKey {
+        fn stream(&self, stream: &mut sval::value::Stream) -> Result<(), sval::value::Error> {
+            stream.map_begin(Some(1))?;
+            stream.map_key(sval_json::RawJson::new_unchecked("\"a\""))?;
+            stream.map_value(1)?;
+            stream.map_end()
+        }
+    }
+
+    assert!(sval_json::to_string(RawKey).is_err());
+}
+
+#[test]
+fn sval_json_to_slice() {
+    let v = vec![Some("a"), None];
+
+    let mut buf = [0; 16];
+    let len = sval_json::to_slice(&mut buf, &v).unwrap();
+    assert_eq!(b"[\"a\",null]", &buf[..len]);
+
+    let mut buf = [0; 10];
+    let len = sval_json::to_slice(&mut buf, &v).unwrap();
+    assert_eq!(b"[\"a\",null]", &buf[..len]);
+
+    let mut buf = [0; 9];
+    let err = sval_json::to_slice(&mut buf, &v).unwrap_err();
+    assert!(err.is_buffer_too_small());
+    assert_eq!(Some(10), err.needed());
+    assert_eq!("buffer too small: 10 bytes are needed", err.to_string());
+
+    // The original error is kept as the source when converting
+    let err = err.into_error();
+    let source = err
+        .source()
+        .and_then(|err| err.downcast_ref::<sval_json::SliceError>())
+        .expect("missing source");
+    assert_eq!(Some(10), source.needed());
+
+    fn write(buf: &mut [u8], v: impl sval::Value) -> Result<usize, sval::Error> {
+        Ok(sval_json::to_slice(buf, v)?)
+    }
+
+    // Converting with `?` also keeps the original error
+    let mut buf = [0; 9];
+    let err = write(&mut buf, &v).unwrap_err();
+    let source = err
+        .source()
+        .and_then(|err| err.downcast_ref::<sval_json::SliceError>())
+        .expect("missing source");
+    assert_eq!(Some(10), source.needed());
+
+    let mut buf = [0; 32];
+    let len = sval_json::Config::new()
+        .pretty(sval_json::Pretty::new())
+        .to_slice(&mut buf, &v)
+        .unwrap();
+    assert_eq!(b"[\n  \"a\",\n  null\n]", &buf[..len]);
+
+    // Errors from the stream aren't about the buffer size
+    let mut buf = [0; 32];
+    let err = sval_json::Config::new()
+        .floats(sval_json::FloatPolicy::Error)
+        .to_slice(&mut buf, f64::NAN)
+        .unwrap_err();
+    assert!(!err.is_buffer_too_small());
+    assert_eq!(None, err.needed());
+}
+
+#[test]
+fn sval_json_canonical() {
+    // Examples from RFC 8785
+    let json = r#"{
+        "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+        "string": "€$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+        "literals": [null, true, false]
+    }"#;
+
+    assert_eq!(
+        r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#,
+        sval_json::to_string_canonical(sval_json::JsonStr::new(json)).unwrap()
+    );
+
+    let json = r#"{
+        "€": "Euro Sign",
+        "\r": "Carriage Return",
+        "דּ": "Hebrew Letter Dalet With Dagesh",
+        "1": "One",
+        "😀": "Emoji: Grinning Face",
+        "\u0080": "Control",
+        "ö": "Latin Small Letter O With Diaeresis"
+    }"#;
+
+    let sorted = sval_json::to_string_canonical(sval_json::JsonStr::new(json)).unwrap();
+    let values: Vec<_> = sorted
+        .split(',')
+        .map(|entry| {
+            let value = entry.split("\":\"").nth(1).unwrap();
+            value.trim_end_matches(['"', '}'])
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            "Carriage Return",
+            "One",
+            "Control",
+            "Latin Small Letter O With Diaeresis",
+            "Euro Sign",
+            "Emoji: Grinning Face",
+            "Hebrew Letter Dalet With Dagesh",
+        ],
+        values
+    );
+
+    for (v, expected) in &[
+        (0.0, "0"),
+        (-0.0, "0"),
+        (1.0, "1"),
+        (-1.5, "-1.5"),
+        (1e20, "100000000000000000000"),
+        (1e21, "1e+21"),
+        (1e-6, "0.000001"),
+        (1e-7, "1e-7"),
+        (123e-20, "1.23e-18"),
+        (5e-324, "5e-324"),
+        (f64::MAX, "1.7976931348623157e+308"),
+        (9007199254740992.0, "9007199254740992"),
+        (295147905179352830000.0, "295147905179352830000"),
+    ] {
+        assert_eq!(*expected, sval_json::to_string_canonical(*v).unwrap());
+    }
+
+    #[derive(Value)]
+    struct Signed<'a> {
+        signature: &'a str,
+        payload: sval_json::RawJson<'a>,
+        id: u64,
+    }
+
+    let mut buf = Vec::new();
+    sval_json::to_writer_canonical(
+        &mut buf,
+        Signed {
+            signature: "abc",
+            payload: sval_json::RawJson::new("{ \"b\": 1.0, \"a\": [2e1] }").unwrap(),
+            id: 1,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        "{\"id\":1,\"payload\":{\"a\":[20],\"b\":1},\"signature\":\"abc\"}",
+        String::from_utf8(buf).unwrap()
+    );
+
+    // The writer is flushed once the json is written
+    struct FailFlush;
+
+    impl std::io::Write for FailFlush {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Err(std::io::Error::new(std::io::ErrorKind::Interrupted, "interrupted"))
+        }
+    }
+
+    let err = sval_json::to_writer_canonical(FailFlush, vec![1, 2, 3]).unwrap_err();
+    assert_eq!(std::io::ErrorKind::Interrupted, err.into_io_error().kind());
+
+    assert!(sval_json::to_string_canonical(f64::NAN).is_err());
+
+    // Integers are supported as long as a 64bit float can represent them exactly
+    for (v, expected) in &[
+        ((1i128 << 53) - 1, "9007199254740991"),
+        (-((1i128 << 53) - 1), "-9007199254740991"),
+        (1i128 << 53, "9007199254740992"),
+        (-(1i128 << 53), "-9007199254740992"),
+        ((1i128 << 53) + 2, "9007199254740994"),
+        (1i128 << 64, "18446744073709552000"),
+        (i128::min_value(), "-1.7014118346046923e+38"),
+    ] {
+        assert_eq!(*expected, sval_json::to_string_canonical(*v).unwrap());
+    }
+    assert_eq!(
+        "9007199254740992",
+        sval_json::to_string_canonical(1u64 << 53).unwrap()
+    );
+    assert_eq!(
+        "1.7014118346046923e+38",
+        sval_json::to_string_canonical(1u128 << 127).unwrap()
+    );
+    assert!(sval_json::to_string_canonical((1u64 << 53) + 1).is_err());
+    assert!(sval_json::to_string_canonical(-(1i64 << 53) - 1).is_err());
+    assert!(sval_json::to_string_canonical(u64::max_value()).is_err());
+    assert!(sval_json::to_string_canonical(u128::max_value()).is_err());
+    assert!(sval_json::to_string_canonical(sval_json::JsonStr::new("9007199254740993")).is_err());
+    assert!(sval_json::to_string_canonical(sval_json::JsonStr::new("{\"a\":1,\"a\":2}")).is_err());
+}
+
+#[test]
+fn sval_json_tagged_matches_serde_json() {
+    #[derive(Serialize, Value)]
+    #[serde(rename_all = "kebab-case", tag = "type")]
+    #[sval(rename_all = "kebab-case", tag = "type")]
+    enum Internal {
+        UnitKind,
+        StructFields { field_a: i32 },
+    }
+
+    #[derive(Serialize, Value)]
+    #[serde(rename_all = "SCREAMING_SNAKE_CASE", tag = "t", content = "c")]
+    #[sval(rename_all = "SCREAMING_SNAKE_CASE", tag = "t", content = "c")]
+    enum Adjacent {
+        UnitKind,
+        NewType(i32),
+        TwoValues(i32, i32),
+        StructFields { field_a: i32 },
+    }
+
+    #[derive(Serialize, Value)]
+    #[serde(untagged)]
+    #[sval(untagged)]
+    enum Untagged {
+        NewType(i32),
+        TwoValues(i32, i32),
+        StructFields { field_a: i32 },
+    }
+
+    fn assert_json(v: impl serde::Serialize + sval::Value) {
+        assert_eq!(
+            serde_json::to_string(&v).unwrap(),
+            sval_json::to_string(&v).unwrap()
+        );
+    }
+
+    assert_json(Internal::UnitKind);
+    assert_json(Internal::StructFields { field_a: 1 });
+
+    assert_json(Adjacent::UnitKind);
+    assert_json(Adjacent::NewType(1));
+    assert_json(Adjacent::TwoValues(1, 2));
+    assert_json(Adjacent::StructFields { field_a: 1 });
+
+    assert_json(Untagged::NewType(1));
+    assert_json(Untagged::TwoValues(1, 2));
+    assert_json(Untagged::StructFields { field_a: 1 });
+}
+
+#[test]
+fn sval_json_parse_roundtrip() {
+    let json = std::fs::read_to_string("twitter.json").unwrap();
+
+    let roundtrip = sval_json::to_string(sval_json::JsonStr::new(&json)).unwrap();
+
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+        serde_json::from_str::<serde_json::Value>(&roundtrip).unwrap()
+    );
+}
+
+#[test]
+fn sval_json_parse_matches_serde_json() {
+    for json in &[
+        "null",
+        "true",
+        " [ 1 , -2 , 3.5 , 1e3 , -0.25E-2 ] ",
+        "18446744073709551615",
+        "-9223372036854775808",
+        r#"{"a":{"b":[{},[],""]},"c":null}"#,
+        r#""quote \" slash \/ backslash \\ controls \b\f\n\r\t""#,
+        r#""unicode \u00e9 \u2603 \ud83d\ude00""#,
+        r#"{"key \"with\" escapes":1}"#,
+    ] {
+        assert_eq!(
+            serde_json::to_string(&serde_json::from_str::<serde_json::Value>(json).unwrap())
+                .unwrap(),
+            sval_json::to_string(sval_json::JsonStr::new(json)).unwrap(),
+            "{}",
+            json
+        );
+    }
+}
+
+#[test]
+fn sval_json_parse_into_stream() {
+    #[derive(Default)]
+    struct Count {
+        keys: usize,
+        strs: usize,
+        nums: usize,
+    }
+
+    impl sval::stream::Stream for Count {
+        fn fmt(&mut self, _: sval::stream::Arguments) -> Result<(), sval::stream::Error> {
+            self.strs += 1;
+            Ok(())
+        }
+
+        fn str(&mut self, _: &str) -> Result<(), sval::stream::Error> {
+            self.strs += 1;
+            Ok(())
+        }
+
+        fn u64(&mut self, _: u64) -> Result<(), sval::stream::Error> {
+            self.nums += 1;
+            Ok(())
+        }
+
+        fn map_key(&mut self) -> Result<(), sval::stream::Error> {
+            self.keys += 1;
+            Ok(())
+        }
+    }
+
+    let mut count = Count::default();
+    sval_json::from_str(r#"{"a": [1, 2], "b\n": "c"}"#, &mut count).unwrap();
+
+    assert_eq!(2, count.keys);
+    assert_eq!(3, count.strs);
+    assert_eq!(2, count.nums);
+
+    let mut count = Count::default();
+    sval_json::from_slice(b"[1, 2, 3]", &mut count).unwrap();
+
+    assert_eq!(3, count.nums);
+}
+
+#[test]
+fn sval_json_parse_borrowed() {
+    #[derive(Default)]
+    struct Strs<'v> {
+        borrowed: Vec<&'v str>,
+        owned: Vec<String>,
+    }
+
+    impl<'v> sval::stream::Stream for Strs<'v> {
+        fn fmt(&mut self, args: sval::stream::Arguments) -> Result<(), sval::stream::Error> {
+            self.owned.push(args.to_string());
+            Ok(())
+        }
+    }
+
+    impl<'v> sval::stream::BorrowedStream<'v> for Strs<'v> {
+        fn str_borrowed(&mut self, v: &'v str) -> Result<(), sval::stream::Error> {
+            self.borrowed.push(v);
+            Ok(())
+        }
+    }
+
+    let json = sval_json::JsonStr::new(r#"{"a": ["b", "c\n"]}"#);
+
+    let mut strs = Strs::default();
+    sval::stream_borrowed(&json, &mut strs).unwrap();
+
+    assert_eq!(vec!["a", "b"], strs.borrowed);
+    assert_eq!(vec!["c\n"], strs.owned);
+}
+
+#[test]
+fn sval_json_parse_errors() {
+    for (json, err) in &[
+        ("", "unexpected end of input at byte offset 0"),
+        ("[1, 2", "unexpected end of input at byte offset 5"),
+        ("[1 2]", "expected `,` or `]` at byte offset 3"),
+        ("{\"a\" 1}", "expected `:` at byte offset 5"),
+        ("{1: 2}", "expected a string key at byte offset 1"),
+        ("{\"a\": 1,}", "expected a string key at byte offset 8"),
+        ("[tru]", "expected a value at byte offset 1"),
+        ("01", "trailing characters at byte offset 1"),
+        ("-", "invalid number at byte offset 1"),
+        ("1.", "invalid number at byte offset 2"),
+        ("1e400", "number out of range at byte offset 0"),
+        ("\"abc", "unterminated string at byte offset 4"),
+        ("\"a\tb\"", "control character in string at byte offset 2"),
+        ("\"\\x\"", "invalid escape at byte offset 2"),
+        ("\"\\u12\"", "invalid unicode escape at byte offset 3"),
+        ("\"\\ud83d\"", "unpaired surrogate in escape at byte offset 1"),
+        ("{} {}", "trailing characters at byte offset 3"),
+    ] {
+        assert_eq!(
+            *err,
+            sval_json::to_string(sval_json::JsonStr::new(json))
+                .unwrap_err()
+                .to_string(),
+            "{}",
+            json
+        );
+    }
+
+    struct Validate;
+
+    impl sval::stream::Stream for Validate {
+        fn fmt(&mut self, _: sval::stream::Arguments) -> Result<(), sval::stream::Error> {
+            Ok(())
+        }
+    }
+
+    let deep = "[".repeat(200);
+    assert!(sval_json::from_str(&deep, Validate).is_err());
+
+    assert_eq!(
+        "invalid utf-8 at byte offset 3",
+        sval_json::JsonStr::from_slice(b"\"a\"\xff")
+            .unwrap_err()
+            .to_string()
+    );
+}
+
 #[derive(Serialize, Deserialize, MiniSerialize, Value)]
 pub struct Twitter {
     statuses: Vec<Status>,
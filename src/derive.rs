@@ -15,3 +15,539 @@ macro_rules! derive_from_serde {
         $($without)*
     };
 }
+
+use crate::{
+    stream,
+    value::{
+        self,
+        collect,
+        Value,
+    },
+};
+
+/**
+Stream a value using a custom function.
+
+This is used by `#[sval(with = "path")]`.
+*/
+pub struct With<'a, T: ?Sized>(
+    pub &'a T,
+    pub fn(&T, &mut value::Stream) -> Result<(), value::Error>,
+);
+
+impl<'a, T: ?Sized> Value for With<'a, T> {
+    #[inline]
+    fn stream(&self, stream: &mut value::Stream) -> Result<(), value::Error> {
+        (self.1)(self.0, stream)
+    }
+}
+
+/**
+Stream the entries of a map into the map currently being streamed.
+
+This is used by `#[sval(flatten)]`.
+*/
+#[inline]
+pub fn flatten(stream: &mut value::Stream, v: impl Value) -> Result<(), value::Error> {
+    stream.map_flatten(v)
+}
+
+/**
+A stream that strips the outer map or struct from a value.
+
+Everything nested within that map is passed through to the underlying stream.
+*/
+pub(crate) struct Flatten<S> {
+    depth: usize,
+    stream: S,
+}
+
+impl<S> Flatten<S> {
+    #[inline]
+    pub(crate) fn new(stream: S) -> Self {
+        Flatten { depth: 0, stream }
+    }
+
+    #[inline]
+    fn nested(&mut self) -> Result<&mut S, stream::Error> {
+        if self.depth == 0 {
+            return Err(stream::Error::msg("only maps can be flattened"));
+        }
+
+        Ok(&mut self.stream)
+    }
+
+    #[inline]
+    fn nested_begin(&mut self) -> Result<&mut S, stream::Error> {
+        self.nested()?;
+        self.depth += 1;
+
+        Ok(&mut self.stream)
+    }
+
+    #[inline]
+    fn nested_end(&mut self) -> Result<&mut S, stream::Error> {
+        // The outer map is only ended by `map_end` or `struct_end`
+        if self.depth <= 1 {
+            return Err(stream::Error::msg("unbalanced flattened value"));
+        }
+
+        self.depth -= 1;
+
+        Ok(&mut self.stream)
+    }
+}
+
+impl<'v, S> collect::Stream<'v> for Flatten<S>
+where
+    S: collect::Stream<'v>,
+{
+    #[inline]
+    fn str_borrowed(&mut self, v: &'v str) -> Result<(), stream::Error> {
+        self.nested()?.str_borrowed(v)
+    }
+
+    #[inline]
+    fn is_borrowed(&self) -> bool {
+        self.stream.is_borrowed()
+    }
+
+    #[inline]
+    fn map_key_collect(&mut self, k: collect::Value) -> Result<(), stream::Error> {
+        self.nested()?.map_key_collect(k)
+    }
+
+    #[inline]
+    fn map_value_collect(&mut self, v: collect::Value) -> Result<(), stream::Error> {
+        self.nested()?.map_value_collect(v)
+    }
+
+    #[inline]
+    fn seq_elem_collect(&mut self, v: collect::Value) -> Result<(), stream::Error> {
+        self.nested()?.seq_elem_collect(v)
+    }
+
+    #[inline]
+    fn some_collect(&mut self, v: collect::Value) -> Result<(), stream::Error> {
+        // A present optional value flattens to its value
+        if self.depth == 0 {
+            return v.stream(self);
+        }
+
+        self.stream.some_collect(v)
+    }
+
+    #[inline]
+    fn tagged_collect(&mut self, tag: stream::Tag, v: collect::Value) -> Result<(), stream::Error> {
+        // A tagged value flattens to its value
+        if self.depth == 0 {
+            return v.stream(self);
+        }
+
+        self.stream.tagged_collect(tag, v)
+    }
+
+    #[inline]
+    fn struct_field_collect(
+        &mut self,
+        index: u32,
+        field: &'static str,
+        v: collect::Value,
+    ) -> Result<(), stream::Error> {
+        // The fields of the outer struct are flattened into map entries
+        if self.depth == 1 {
+            self.stream.map_key()?;
+            self.stream.str(field)?;
+            return self.stream.map_value_collect(v);
+        }
+
+        self.nested()?.struct_field_collect(index, field, v)
+    }
+
+    #[inline]
+    fn tuple_elem_collect(&mut self, index: u32, v: collect::Value) -> Result<(), stream::Error> {
+        self.nested()?.tuple_elem_collect(index, v)
+    }
+
+    #[inline]
+    fn newtype_variant_collect(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+        v: collect::Value,
+    ) -> Result<(), stream::Error> {
+        self.nested()?.newtype_variant_collect(ty, index, variant, v)
+    }
+
+    #[inline]
+    fn tuple_variant_elem_collect(
+        &mut self,
+        index: u32,
+        v: collect::Value,
+    ) -> Result<(), stream::Error> {
+        self.nested()?.tuple_variant_elem_collect(index, v)
+    }
+
+    #[inline]
+    fn struct_variant_field_collect(
+        &mut self,
+        index: u32,
+        field: &'static str,
+        v: collect::Value,
+    ) -> Result<(), stream::Error> {
+        self.nested()?.struct_variant_field_collect(index, field, v)
+    }
+}
+
+impl<S> stream::Stream for Flatten<S>
+where
+    S: stream::Stream,
+{
+    #[inline]
+    fn begin(&mut self) -> Result<(), stream::Error> {
+        Ok(())
+    }
+
+    #[inline]
+    fn fmt(&mut self, args: stream::Arguments) -> Result<(), stream::Error> {
+        self.nested()?.fmt(args)
+    }
+
+    #[inline]
+    fn i8(&mut self, v: i8) -> Result<(), stream::Error> {
+        self.nested()?.i8(v)
+    }
+
+    #[inline]
+    fn i16(&mut self, v: i16) -> Result<(), stream::Error> {
+        self.nested()?.i16(v)
+    }
+
+    #[inline]
+    fn i32(&mut self, v: i32) -> Result<(), stream::Error> {
+        self.nested()?.i32(v)
+    }
+
+    #[inline]
+    fn u8(&mut self, v: u8) -> Result<(), stream::Error> {
+        self.nested()?.u8(v)
+    }
+
+    #[inline]
+    fn u16(&mut self, v: u16) -> Result<(), stream::Error> {
+        self.nested()?.u16(v)
+    }
+
+    #[inline]
+    fn u32(&mut self, v: u32) -> Result<(), stream::Error> {
+        self.nested()?.u32(v)
+    }
+
+    #[inline]
+    fn f32(&mut self, v: f32) -> Result<(), stream::Error> {
+        self.nested()?.f32(v)
+    }
+
+    #[inline]
+    fn i64(&mut self, v: i64) -> Result<(), stream::Error> {
+        self.nested()?.i64(v)
+    }
+
+    #[inline]
+    fn u64(&mut self, v: u64) -> Result<(), stream::Error> {
+        self.nested()?.u64(v)
+    }
+
+    #[inline]
+    fn i128(&mut self, v: i128) -> Result<(), stream::Error> {
+        self.nested()?.i128(v)
+    }
+
+    #[inline]
+    fn u128(&mut self, v: u128) -> Result<(), stream::Error> {
+        self.nested()?.u128(v)
+    }
+
+    #[inline]
+    fn f64(&mut self, v: f64) -> Result<(), stream::Error> {
+        self.nested()?.f64(v)
+    }
+
+    #[inline]
+    fn number(&mut self, v: &str) -> Result<(), stream::Error> {
+        self.nested()?.number(v)
+    }
+
+    #[inline]
+    fn bool(&mut self, v: bool) -> Result<(), stream::Error> {
+        self.nested()?.bool(v)
+    }
+
+    #[inline]
+    fn char(&mut self, v: char) -> Result<(), stream::Error> {
+        self.nested()?.char(v)
+    }
+
+    #[inline]
+    fn str(&mut self, v: &str) -> Result<(), stream::Error> {
+        self.nested()?.str(v)
+    }
+
+    #[inline]
+    fn bytes(&mut self, v: &[u8]) -> Result<(), stream::Error> {
+        self.nested()?.bytes(v)
+    }
+
+    #[inline]
+    fn none(&mut self) -> Result<(), stream::Error> {
+        // An empty value flattens to no entries
+        if self.depth == 0 {
+            return Ok(());
+        }
+
+        self.stream.none()
+    }
+
+    #[inline]
+    fn unit(&mut self) -> Result<(), stream::Error> {
+        // A unit flattens to no entries
+        if self.depth == 0 {
+            return Ok(());
+        }
+
+        self.stream.unit()
+    }
+
+    #[inline]
+    fn some_begin(&mut self) -> Result<(), stream::Error> {
+        if self.depth == 0 {
+            return Ok(());
+        }
+
+        self.stream.some_begin()
+    }
+
+    #[inline]
+    fn some_end(&mut self) -> Result<(), stream::Error> {
+        if self.depth == 0 {
+            return Ok(());
+        }
+
+        self.stream.some_end()
+    }
+
+    #[inline]
+    fn tagged_begin(&mut self, tag: stream::Tag) -> Result<(), stream::Error> {
+        if self.depth == 0 {
+            return Ok(());
+        }
+
+        self.stream.tagged_begin(tag)
+    }
+
+    #[inline]
+    fn tagged_end(&mut self) -> Result<(), stream::Error> {
+        if self.depth == 0 {
+            return Ok(());
+        }
+
+        self.stream.tagged_end()
+    }
+
+    #[inline]
+    fn map_begin(&mut self, len: Option<usize>) -> Result<(), stream::Error> {
+        if self.depth == 0 {
+            self.depth += 1;
+            return Ok(());
+        }
+
+        self.nested_begin()?.map_begin(len)
+    }
+
+    #[inline]
+    fn map_key(&mut self) -> Result<(), stream::Error> {
+        self.nested()?.map_key()
+    }
+
+    #[inline]
+    fn map_value(&mut self) -> Result<(), stream::Error> {
+        self.nested()?.map_value()
+    }
+
+    #[inline]
+    fn map_end(&mut self) -> Result<(), stream::Error> {
+        if self.depth == 1 {
+            self.depth -= 1;
+            return Ok(());
+        }
+
+        self.nested_end()?.map_end()
+    }
+
+    #[inline]
+    fn seq_begin(&mut self, len: Option<usize>) -> Result<(), stream::Error> {
+        self.nested_begin()?.seq_begin(len)
+    }
+
+    #[inline]
+    fn seq_elem(&mut self) -> Result<(), stream::Error> {
+        self.nested()?.seq_elem()
+    }
+
+    #[inline]
+    fn seq_end(&mut self) -> Result<(), stream::Error> {
+        self.nested_end()?.seq_end()
+    }
+
+    #[inline]
+    fn struct_begin(&mut self, ty: &'static str, len: usize) -> Result<(), stream::Error> {
+        if self.depth == 0 {
+            self.depth += 1;
+            return Ok(());
+        }
+
+        self.nested_begin()?.struct_begin(ty, len)
+    }
+
+    #[inline]
+    fn struct_field(&mut self, index: u32, field: &'static str) -> Result<(), stream::Error> {
+        if self.depth == 1 {
+            self.stream.map_key()?;
+            self.stream.str(field)?;
+            return self.stream.map_value();
+        }
+
+        self.nested()?.struct_field(index, field)
+    }
+
+    #[inline]
+    fn struct_end(&mut self) -> Result<(), stream::Error> {
+        if self.depth == 1 {
+            self.depth -= 1;
+            return Ok(());
+        }
+
+        self.nested_end()?.struct_end()
+    }
+
+    #[inline]
+    fn tuple_begin(&mut self, ty: Option<&'static str>, len: usize) -> Result<(), stream::Error> {
+        self.nested_begin()?.tuple_begin(ty, len)
+    }
+
+    #[inline]
+    fn tuple_elem(&mut self, index: u32) -> Result<(), stream::Error> {
+        self.nested()?.tuple_elem(index)
+    }
+
+    #[inline]
+    fn tuple_end(&mut self) -> Result<(), stream::Error> {
+        self.nested_end()?.tuple_end()
+    }
+
+    #[inline]
+    fn unit_variant(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<(), stream::Error> {
+        self.nested()?.unit_variant(ty, index, variant)
+    }
+
+    #[inline]
+    fn newtype_variant_begin(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<(), stream::Error> {
+        self.nested_begin()?.newtype_variant_begin(ty, index, variant)
+    }
+
+    #[inline]
+    fn newtype_variant_end(&mut self) -> Result<(), stream::Error> {
+        self.nested_end()?.newtype_variant_end()
+    }
+
+    #[inline]
+    fn tuple_variant_begin(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<(), stream::Error> {
+        self.nested_begin()?.tuple_variant_begin(ty, index, variant, len)
+    }
+
+    #[inline]
+    fn tuple_variant_elem(&mut self, index: u32) -> Result<(), stream::Error> {
+        self.nested()?.tuple_variant_elem(index)
+    }
+
+    #[inline]
+    fn tuple_variant_end(&mut self) -> Result<(), stream::Error> {
+        self.nested_end()?.tuple_variant_end()
+    }
+
+    #[inline]
+    fn struct_variant_begin(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<(), stream::Error> {
+        self.nested_begin()?.struct_variant_begin(ty, index, variant, len)
+    }
+
+    #[inline]
+    fn struct_variant_field(
+        &mut self,
+        index: u32,
+        field: &'static str,
+    ) -> Result<(), stream::Error> {
+        self.nested()?.struct_variant_field(index, field)
+    }
+
+    #[inline]
+    fn struct_variant_end(&mut self) -> Result<(), stream::Error> {
+        self.nested_end()?.struct_variant_end()
+    }
+
+    #[inline]
+    fn end(&mut self) -> Result<(), stream::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::stream::Stream;
+
+    struct Empty;
+
+    impl Stream for Empty {
+        fn fmt(&mut self, _: stream::Arguments) -> Result<(), stream::Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn flatten_unbalanced_end() {
+        let mut flatten = Flatten::new(collect::Default(Empty));
+
+        assert!(flatten.seq_end().is_err());
+
+        flatten.map_begin(None).unwrap();
+        assert!(flatten.seq_end().is_err());
+
+        flatten.seq_begin(None).unwrap();
+        flatten.seq_end().unwrap();
+        flatten.map_end().unwrap();
+    }
+}
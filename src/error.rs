@@ -1,7 +1,11 @@
 use crate::std::fmt;
 
 #[cfg(feature = "std")]
-use crate::std::string::String;
+use crate::std::{
+    boxed::Box,
+    error,
+    string::String,
+};
 
 /**
 An error encountered while visiting a value.
@@ -20,6 +24,17 @@ impl Error {
     pub fn msg(msg: &'static str) -> Self {
         Error(ErrorInner::Static(msg))
     }
+
+    /**
+    Capture a static message as an error, along with the byte offset
+    in the input where it happened.
+
+    This doesn't need `std`, so it can be used by no-std parsers.
+    */
+    #[inline]
+    pub fn msg_at(msg: &'static str, offset: usize) -> Self {
+        Error(ErrorInner::StaticAt(msg, offset))
+    }
 }
 
 impl fmt::Debug for Error {
@@ -34,19 +49,28 @@ impl fmt::Display for Error {
     }
 }
 
-#[derive(Clone)]
 enum ErrorInner {
     Static(&'static str),
+    StaticAt(&'static str, usize),
     #[cfg(feature = "std")]
     Owned(String),
+    #[cfg(feature = "std")]
+    Source(Box<dyn error::Error + Send + Sync>),
 }
 
 impl fmt::Debug for ErrorInner {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             ErrorInner::Static(msg) => msg.fmt(f),
+            ErrorInner::StaticAt(msg, offset) => f
+                .debug_struct("Error")
+                .field("msg", msg)
+                .field("offset", offset)
+                .finish(),
             #[cfg(feature = "std")]
             ErrorInner::Owned(ref msg) => msg.fmt(f),
+            #[cfg(feature = "std")]
+            ErrorInner::Source(ref err) => err.fmt(f),
         }
     }
 }
@@ -55,8 +79,11 @@ impl fmt::Display for ErrorInner {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             ErrorInner::Static(msg) => msg.fmt(f),
+            ErrorInner::StaticAt(msg, offset) => write!(f, "{} at byte offset {}", msg, offset),
             #[cfg(feature = "std")]
             ErrorInner::Owned(ref msg) => msg.fmt(f),
+            #[cfg(feature = "std")]
+            ErrorInner::Source(ref err) => err.fmt(f),
         }
     }
 }
@@ -79,8 +106,6 @@ mod std_support {
     use super::*;
 
     use crate::std::{
-        boxed::Box,
-        error,
         io,
         string::ToString,
     };
@@ -91,6 +116,24 @@ mod std_support {
             Error(ErrorInner::Owned(err.to_string()))
         }
 
+        /**
+        Capture a standard error.
+
+        The original error is kept and can be retrieved using the `source` method.
+        Converting with `From` does the same thing.
+        */
+        pub fn from_error(err: impl error::Error + Send + Sync + 'static) -> Self {
+            Error(ErrorInner::Source(Box::new(err)))
+        }
+
+        /** Get the original error captured by `from_error`, if there is one. */
+        pub fn source(&self) -> Option<&(dyn error::Error + Send + Sync + 'static)> {
+            match self.0 {
+                ErrorInner::Source(ref err) => Some(&**err),
+                _ => None,
+            }
+        }
+
         /** Get a reference to a standard error. */
         pub fn as_error(&self) -> &(dyn error::Error + Send + Sync + 'static) {
             &self.0
@@ -101,18 +144,30 @@ mod std_support {
             Box::new(self.0)
         }
 
-        /** Convert into an io error. */
+        /**
+        Convert into an io error.
+
+        If the original error captured by `from_error` is an io error then it's returned as-is.
+        */
         pub fn into_io_error(self) -> io::Error {
-            io::Error::new(io::ErrorKind::Other, self.into_error())
+            let err: Box<dyn error::Error + Send + Sync> = match self.0 {
+                ErrorInner::Source(err) => match err.downcast::<io::Error>() {
+                    Ok(err) => return *err,
+                    Err(err) => err,
+                },
+                inner => Box::new(inner),
+            };
+
+            io::Error::new(io::ErrorKind::Other, err)
         }
     }
 
     impl<E> From<E> for Error
     where
-        E: error::Error,
+        E: error::Error + Send + Sync + 'static,
     {
         fn from(err: E) -> Self {
-            Error(ErrorInner::Owned(err.to_string()))
+            Error::from_error(err)
         }
     }
 
@@ -144,7 +199,17 @@ mod std_support {
         fn description(&self) -> &str {
             match self {
                 ErrorInner::Static(msg) => msg,
+                ErrorInner::StaticAt(msg, _) => msg,
                 ErrorInner::Owned(msg) => msg,
+                #[allow(deprecated)]
+                ErrorInner::Source(err) => err.description(),
+            }
+        }
+
+        fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+            match self {
+                ErrorInner::Source(err) => err.source(),
+                _ => None,
             }
         }
     }
@@ -160,4 +225,34 @@ mod tests {
     fn convert_fmt_error_into_error() {
         let _ = Error::from(fmt::Error);
     }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn io_error_roundtrip() {
+        use crate::std::{
+            io,
+            string::ToString,
+        };
+
+        let err = Error::from_error(io::Error::new(io::ErrorKind::WriteZero, "write zero"));
+
+        assert_eq!("write zero", err.to_string());
+        assert!(err
+            .source()
+            .and_then(|err| err.downcast_ref::<io::Error>())
+            .is_some());
+        assert_eq!(io::ErrorKind::WriteZero, err.into_io_error().kind());
+
+        assert_eq!(io::ErrorKind::Other, Error::msg("msg").into_io_error().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn msg_at_includes_offset() {
+        use crate::std::string::ToString;
+
+        let err = Error::msg_at("unexpected end of input", 3);
+
+        assert_eq!("unexpected end of input at byte offset 3", err.to_string());
+    }
 }
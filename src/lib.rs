@@ -55,10 +55,41 @@ use sval::Value;
 pub struct Data {
     id: u32,
     title: String,
+    status: Status,
+}
+
+#[derive(Value)]
+pub enum Status {
+    Draft,
+    #[sval(rename = "published")]
+    Published { at: u64 },
 }
 # }
 ```
 
+Fields can be customized using `#[sval]` attributes:
+
+- `#[sval(rename = "name")]`: stream the field using a different name.
+- `#[sval(skip)]`: don't stream the field.
+- `#[sval(skip_if = "path")]`: don't stream the field if the function
+  at `path`, with the signature `fn(&T) -> bool`, returns `true`.
+- `#[sval(flatten)]`: stream the entries of the field's map into the parent map.
+- `#[sval(with = "path")]`: stream the field using the function at `path`,
+  with the signature `fn(&T, &mut value::Stream) -> Result<(), value::Error>`.
+
+Structs and enums can be customized using `#[sval]` attributes:
+
+- `#[sval(rename_all = "rule")]`: rename all fields of a struct, or all variants of an enum,
+  using one of `lowercase`, `UPPERCASE`, `PascalCase`, `camelCase`, `snake_case`,
+  `SCREAMING_SNAKE_CASE`, `kebab-case` or `SCREAMING-KEBAB-CASE`.
+- `#[sval(tag = "tag")]`: stream enum variants as a map, with the variant name
+  in the `tag` entry alongside the variant's fields.
+- `#[sval(tag = "tag", content = "content")]`: stream enum variants as a map,
+  with the variant name in the `tag` entry and its fields in the `content` entry.
+- `#[sval(untagged)]`: stream enum variants without their names.
+
+Variants can also be renamed individually using `#[sval(rename = "name")]`.
+
 The trait can also be implemented manually:
 
 ```
@@ -379,3 +410,17 @@ Stream the structure of a [`Value`] using the given [`Stream`].
 pub fn stream(value: impl Value, stream: impl Stream) -> Result<(), Error> {
     value::stream(value, value::collect::Default(stream))
 }
+
+/**
+Stream the structure of a [`Value`] using the given [`stream::BorrowedStream`].
+
+The value is streamed using [`Value::stream_borrowed`], so strings it lends
+using [`value::Stream::str_borrowed`] are borrowed for `'v`,
+and the stream can keep them without copying.
+*/
+pub fn stream_borrowed<'v>(
+    value: &'v (impl Value + ?Sized),
+    stream: impl stream::BorrowedStream<'v>,
+) -> Result<(), Error> {
+    value::stream_borrowed(value, value::collect::Borrowed(stream))
+}
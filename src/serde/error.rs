@@ -38,7 +38,7 @@ where
     #[cfg(feature = "std")]
     {
         let _ = msg;
-        move |err| crate::Error::from(err)
+        move |err| crate::Error::custom(err)
     }
 
     #[cfg(not(feature = "std"))]
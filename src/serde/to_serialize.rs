@@ -15,6 +15,11 @@ use serde_lib::ser::{
     Serialize,
     SerializeMap,
     SerializeSeq,
+    SerializeStruct,
+    SerializeStructVariant,
+    SerializeTuple,
+    SerializeTupleStruct,
+    SerializeTupleVariant,
     Serializer,
 };
 
@@ -65,6 +70,11 @@ where
     Serializer(S),
     SerializeSeq(S::SerializeSeq),
     SerializeMap(S::SerializeMap),
+    SerializeStruct(S::SerializeStruct),
+    SerializeTuple(S::SerializeTuple),
+    SerializeTupleStruct(S::SerializeTupleStruct),
+    SerializeTupleVariant(S::SerializeTupleVariant),
+    SerializeStructVariant(S::SerializeStructVariant),
 }
 
 impl<S> Stream<S>
@@ -140,6 +150,70 @@ where
             )),
         }
     }
+
+    #[inline]
+    fn expect_serialize_struct(&mut self) -> Result<&mut S::SerializeStruct, stream::Error> {
+        match self {
+            Current::SerializeStruct(strct) => Ok(strct),
+            _ => Err(stream::Error::msg(
+                "invalid serializer value (expected a struct)",
+            )),
+        }
+    }
+
+    #[inline]
+    fn take_serialize_struct(self) -> Result<S::SerializeStruct, stream::Error> {
+        match self {
+            Current::SerializeStruct(strct) => Ok(strct),
+            _ => Err(stream::Error::msg(
+                "invalid serializer value (expected a struct)",
+            )),
+        }
+    }
+
+    #[inline]
+    fn expect_serialize_tuple_variant(
+        &mut self,
+    ) -> Result<&mut S::SerializeTupleVariant, stream::Error> {
+        match self {
+            Current::SerializeTupleVariant(variant) => Ok(variant),
+            _ => Err(stream::Error::msg(
+                "invalid serializer value (expected a tuple variant)",
+            )),
+        }
+    }
+
+    #[inline]
+    fn take_serialize_tuple_variant(self) -> Result<S::SerializeTupleVariant, stream::Error> {
+        match self {
+            Current::SerializeTupleVariant(variant) => Ok(variant),
+            _ => Err(stream::Error::msg(
+                "invalid serializer value (expected a tuple variant)",
+            )),
+        }
+    }
+
+    #[inline]
+    fn expect_serialize_struct_variant(
+        &mut self,
+    ) -> Result<&mut S::SerializeStructVariant, stream::Error> {
+        match self {
+            Current::SerializeStructVariant(variant) => Ok(variant),
+            _ => Err(stream::Error::msg(
+                "invalid serializer value (expected a struct variant)",
+            )),
+        }
+    }
+
+    #[inline]
+    fn take_serialize_struct_variant(self) -> Result<S::SerializeStructVariant, stream::Error> {
+        match self {
+            Current::SerializeStructVariant(variant) => Ok(variant),
+            _ => Err(stream::Error::msg(
+                "invalid serializer value (expected a struct variant)",
+            )),
+        }
+    }
 }
 
 impl<S> Stream<S>
@@ -208,6 +282,10 @@ where
             Some(Pos::Key) => self.serialize_key(v),
             Some(Pos::Value) => self.serialize_value(v),
             Some(Pos::Elem) => self.serialize_elem(v),
+            Some(Pos::StructField(field)) => self.serialize_struct_field(field, v),
+            Some(Pos::TupleElem) => self.serialize_tuple_elem(v),
+            Some(Pos::TupleVariantElem) => self.serialize_tuple_variant_elem(v),
+            Some(Pos::StructVariantField(field)) => self.serialize_struct_variant_field(field, v),
             None => self.serialize_primitive(v),
         }
     }
@@ -236,6 +314,53 @@ where
             .map_err(err("error map serializing value"))
     }
 
+    #[inline]
+    fn serialize_struct_field(
+        &mut self,
+        field: &'static str,
+        v: impl Serialize,
+    ) -> Result<(), stream::Error> {
+        self.expect()?
+            .expect_serialize_struct()?
+            .serialize_field(field, &v)
+            .map_err(err("error serializing struct field"))
+    }
+
+    #[inline]
+    fn serialize_tuple_elem(&mut self, v: impl Serialize) -> Result<(), stream::Error> {
+        match self.expect()? {
+            Current::SerializeTuple(tuple) => tuple
+                .serialize_element(&v)
+                .map_err(err("error serializing tuple element")),
+            Current::SerializeTupleStruct(tuple) => tuple
+                .serialize_field(&v)
+                .map_err(err("error serializing tuple element")),
+            _ => Err(stream::Error::msg(
+                "invalid serializer value (expected a tuple)",
+            )),
+        }
+    }
+
+    #[inline]
+    fn serialize_tuple_variant_elem(&mut self, v: impl Serialize) -> Result<(), stream::Error> {
+        self.expect()?
+            .expect_serialize_tuple_variant()?
+            .serialize_field(&v)
+            .map_err(err("error serializing tuple variant element"))
+    }
+
+    #[inline]
+    fn serialize_struct_variant_field(
+        &mut self,
+        field: &'static str,
+        v: impl Serialize,
+    ) -> Result<(), stream::Error> {
+        self.expect()?
+            .expect_serialize_struct_variant()?
+            .serialize_field(field, &v)
+            .map_err(err("error serializing struct variant field"))
+    }
+
     #[inline]
     fn serialize_primitive(&mut self, v: impl Serialize) -> Result<(), stream::Error> {
         let ser = self.take()?.take_serializer()?;
@@ -249,7 +374,7 @@ where
     }
 }
 
-impl<S> value::collect::Stream for Stream<S>
+impl<'v, S> value::collect::Stream<'v> for Stream<S>
 where
     S: Serializer,
 {
@@ -285,6 +410,120 @@ where
             }
         }
     }
+
+    #[inline]
+    fn struct_field_collect(
+        &mut self,
+        index: u32,
+        field: &'static str,
+        v: value::collect::Value,
+    ) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_struct_field(field, ToSerialize(v)),
+            Some(buffered) => {
+                buffered.struct_field(index, field)?;
+                v.stream(value::collect::Default(buffered))
+            }
+        }
+    }
+
+    #[inline]
+    fn tuple_elem_collect(
+        &mut self,
+        index: u32,
+        v: value::collect::Value,
+    ) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_tuple_elem(ToSerialize(v)),
+            Some(buffered) => {
+                buffered.tuple_elem(index)?;
+                v.stream(value::collect::Default(buffered))
+            }
+        }
+    }
+
+    #[inline]
+    fn some_collect(&mut self, v: value::collect::Value) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_any(Some(ToSerialize(v))),
+            Some(buffered) => {
+                buffered.some_begin()?;
+                v.stream(value::collect::Default(&mut *buffered))?;
+                buffered.some_end()
+            }
+        }
+    }
+
+    #[inline]
+    fn tagged_collect(
+        &mut self,
+        tag: stream::Tag,
+        v: value::collect::Value,
+    ) -> Result<(), stream::Error> {
+        // `serde` doesn't have tags, so the value is serialized as-is
+        match self.buffer() {
+            None => self.serialize_any(ToSerialize(v)),
+            Some(buffered) => {
+                buffered.tagged_begin(tag)?;
+                v.stream(value::collect::Default(&mut *buffered))?;
+                buffered.tagged_end()
+            }
+        }
+    }
+
+    #[inline]
+    fn newtype_variant_collect(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+        v: value::collect::Value,
+    ) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_any(NewtypeVariant {
+                ty,
+                index,
+                variant,
+                value: ToSerialize(v),
+            }),
+            Some(buffered) => {
+                buffered.newtype_variant_begin(ty, index, variant)?;
+                v.stream(value::collect::Default(&mut *buffered))?;
+                buffered.newtype_variant_end()
+            }
+        }
+    }
+
+    #[inline]
+    fn tuple_variant_elem_collect(
+        &mut self,
+        index: u32,
+        v: value::collect::Value,
+    ) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_tuple_variant_elem(ToSerialize(v)),
+            Some(buffered) => {
+                buffered.tuple_variant_elem(index)?;
+                v.stream(value::collect::Default(buffered))
+            }
+        }
+    }
+
+    #[inline]
+    fn struct_variant_field_collect(
+        &mut self,
+        index: u32,
+        field: &'static str,
+        v: value::collect::Value,
+    ) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_struct_variant_field(field, ToSerialize(v)),
+            Some(buffered) => {
+                buffered.struct_variant_field(index, field)?;
+                v.stream(value::collect::Default(buffered))
+            }
+        }
+    }
 }
 
 impl<S> stream::Stream for Stream<S>
@@ -297,7 +536,10 @@ where
             None => {
                 match self.take()? {
                     Current::Serializer(ser) => {
-                        let seq = ser.serialize_seq(len).map(Current::SerializeSeq)?;
+                        let seq = ser
+                            .serialize_seq(len)
+                            .map(Current::SerializeSeq)
+                            .map_err(err("error beginning sequence"))?;
                         self.current = Some(seq);
                     }
                     current => {
@@ -352,7 +594,10 @@ where
             None => {
                 match self.take()? {
                     Current::Serializer(ser) => {
-                        let map = ser.serialize_map(len).map(Current::SerializeMap)?;
+                        let map = ser
+                            .serialize_map(len)
+                            .map(Current::SerializeMap)
+                            .map_err(err("error beginning map"))?;
                         self.current = Some(map);
                     }
                     current => {
@@ -413,108 +658,606 @@ where
     }
 
     #[inline]
-    fn i64(&mut self, v: i64) -> Result<(), stream::Error> {
+    fn struct_begin(&mut self, ty: &'static str, len: usize) -> Result<(), stream::Error> {
         match self.buffer() {
-            None => self.serialize_any(v),
-            Some(buffered) => buffered.i64(v),
+            None => {
+                match self.take()? {
+                    Current::Serializer(ser) => {
+                        let strct = ser
+                            .serialize_struct(ty, len)
+                            .map(Current::SerializeStruct)
+                            .map_err(err("error beginning struct"))?;
+                        self.current = Some(strct);
+                    }
+                    current => {
+                        self.buffer_begin().struct_begin(ty, len)?;
+                        self.current = Some(current);
+                    }
+                }
+
+                Ok(())
+            }
+            Some(buffered) => buffered.struct_begin(ty, len),
         }
     }
 
     #[inline]
-    fn u64(&mut self, v: u64) -> Result<(), stream::Error> {
+    fn struct_field(&mut self, index: u32, field: &'static str) -> Result<(), stream::Error> {
         match self.buffer() {
-            None => self.serialize_any(v),
-            Some(buffered) => buffered.u64(v),
+            None => {
+                self.pos = Some(Pos::StructField(field));
+
+                Ok(())
+            }
+            Some(buffered) => buffered.struct_field(index, field),
         }
     }
 
     #[inline]
-    fn i128(&mut self, v: i128) -> Result<(), stream::Error> {
+    fn struct_end(&mut self) -> Result<(), stream::Error> {
         match self.buffer() {
-            None => self.serialize_any(v),
-            Some(buffered) => buffered.i128(v),
+            None => {
+                let strct = self.take()?.take_serialize_struct()?;
+                self.ok = Some(strct.end().map_err(err("error completing struct"))?);
+
+                Ok(())
+            }
+            Some(buffered) => {
+                buffered.struct_end()?;
+
+                if buffered.is_streamable() {
+                    self.buffer_end()?;
+                }
+
+                Ok(())
+            }
         }
     }
 
     #[inline]
-    fn u128(&mut self, v: u128) -> Result<(), stream::Error> {
+    fn tuple_begin(&mut self, ty: Option<&'static str>, len: usize) -> Result<(), stream::Error> {
         match self.buffer() {
-            None => self.serialize_any(v),
-            Some(buffered) => buffered.u128(v),
+            None => {
+                match (self.take()?, ty) {
+                    (Current::Serializer(ser), Some(ty)) => {
+                        let tuple = ser
+                            .serialize_tuple_struct(ty, len)
+                            .map(Current::SerializeTupleStruct)
+                            .map_err(err("error beginning tuple"))?;
+                        self.current = Some(tuple);
+                    }
+                    (Current::Serializer(ser), None) => {
+                        let tuple = ser
+                            .serialize_tuple(len)
+                            .map(Current::SerializeTuple)
+                            .map_err(err("error beginning tuple"))?;
+                        self.current = Some(tuple);
+                    }
+                    (current, _) => {
+                        self.buffer_begin().tuple_begin(ty, len)?;
+                        self.current = Some(current);
+                    }
+                }
+
+                Ok(())
+            }
+            Some(buffered) => buffered.tuple_begin(ty, len),
         }
     }
 
     #[inline]
-    fn f64(&mut self, v: f64) -> Result<(), stream::Error> {
+    fn tuple_elem(&mut self, index: u32) -> Result<(), stream::Error> {
         match self.buffer() {
-            None => self.serialize_any(v),
-            Some(buffered) => buffered.f64(v),
+            None => {
+                self.pos = Some(Pos::TupleElem);
+
+                Ok(())
+            }
+            Some(buffered) => buffered.tuple_elem(index),
         }
     }
 
     #[inline]
-    fn bool(&mut self, v: bool) -> Result<(), stream::Error> {
+    fn tuple_end(&mut self) -> Result<(), stream::Error> {
         match self.buffer() {
-            None => self.serialize_any(v),
-            Some(buffered) => buffered.bool(v),
+            None => {
+                self.ok = Some(match self.take()? {
+                    Current::SerializeTuple(tuple) => {
+                        tuple.end().map_err(err("error completing tuple"))?
+                    }
+                    Current::SerializeTupleStruct(tuple) => {
+                        tuple.end().map_err(err("error completing tuple"))?
+                    }
+                    _ => {
+                        return Err(stream::Error::msg(
+                            "invalid serializer value (expected a tuple)",
+                        ))
+                    }
+                });
+
+                Ok(())
+            }
+            Some(buffered) => {
+                buffered.tuple_end()?;
+
+                if buffered.is_streamable() {
+                    self.buffer_end()?;
+                }
+
+                Ok(())
+            }
         }
     }
 
     #[inline]
-    fn char(&mut self, v: char) -> Result<(), stream::Error> {
+    fn unit_variant(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<(), stream::Error> {
         match self.buffer() {
-            None => self.serialize_any(v),
-            Some(buffered) => buffered.char(v),
+            None => self.serialize_any(UnitVariant { ty, index, variant }),
+            Some(buffered) => buffered.unit_variant(ty, index, variant),
         }
     }
 
     #[inline]
-    fn str(&mut self, v: &str) -> Result<(), stream::Error> {
+    fn newtype_variant_begin(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<(), stream::Error> {
+        // The value of a newtype variant isn't known yet
+        // so it always needs to be buffered
         match self.buffer() {
-            None => self.serialize_any(v),
-            Some(buffered) => buffered.str(v),
+            None => self.buffer_begin().newtype_variant_begin(ty, index, variant),
+            Some(buffered) => buffered.newtype_variant_begin(ty, index, variant),
         }
     }
 
     #[inline]
-    fn none(&mut self) -> Result<(), stream::Error> {
+    fn newtype_variant_end(&mut self) -> Result<(), stream::Error> {
         match self.buffer() {
-            None => self.serialize_any(Option::None::<()>),
-            Some(buffered) => buffered.none(),
+            None => Err(stream::Error::msg(
+                "invalid serializer value (expected a newtype variant)",
+            )),
+            Some(buffered) => {
+                buffered.newtype_variant_end()?;
+
+                if buffered.is_streamable() {
+                    self.buffer_end()?;
+                }
+
+                Ok(())
+            }
         }
     }
 
     #[inline]
-    fn fmt(&mut self, v: fmt::Arguments) -> Result<(), stream::Error> {
+    fn tuple_variant_begin(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<(), stream::Error> {
         match self.buffer() {
-            None => self.serialize_any(v),
-            Some(buffered) => buffered.fmt(v),
+            None => {
+                match self.take()? {
+                    Current::Serializer(ser) => {
+                        let variant = ser
+                            .serialize_tuple_variant(ty, index, variant, len)
+                            .map(Current::SerializeTupleVariant)
+                            .map_err(err("error beginning tuple variant"))?;
+                        self.current = Some(variant);
+                    }
+                    current => {
+                        self.buffer_begin()
+                            .tuple_variant_begin(ty, index, variant, len)?;
+                        self.current = Some(current);
+                    }
+                }
+
+                Ok(())
+            }
+            Some(buffered) => buffered.tuple_variant_begin(ty, index, variant, len),
         }
     }
-}
-
-enum Pos {
-    Key,
-    Value,
-    Elem,
-}
 
-struct Tokens<'a>(&'a [value::owned::Token]);
+    #[inline]
+    fn tuple_variant_elem(&mut self, index: u32) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => {
+                self.pos = Some(Pos::TupleVariantElem);
 
-struct TokensReader<'a> {
-    idx: usize,
-    tokens: &'a [value::owned::Token],
-}
+                Ok(())
+            }
+            Some(buffered) => buffered.tuple_variant_elem(index),
+        }
+    }
 
-impl<'a> Tokens<'a> {
     #[inline]
-    fn reader(&self) -> TokensReader<'a> {
-        TokensReader {
-            idx: 0,
-            tokens: self.0,
+    fn tuple_variant_end(&mut self) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => {
+                let variant = self.take()?.take_serialize_tuple_variant()?;
+                self.ok = Some(
+                    variant
+                        .end()
+                        .map_err(err("error completing tuple variant"))?,
+                );
+
+                Ok(())
+            }
+            Some(buffered) => {
+                buffered.tuple_variant_end()?;
+
+                if buffered.is_streamable() {
+                    self.buffer_end()?;
+                }
+
+                Ok(())
+            }
         }
     }
-}
+
+    #[inline]
+    fn struct_variant_begin(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => {
+                match self.take()? {
+                    Current::Serializer(ser) => {
+                        let variant = ser
+                            .serialize_struct_variant(ty, index, variant, len)
+                            .map(Current::SerializeStructVariant)
+                            .map_err(err("error beginning struct variant"))?;
+                        self.current = Some(variant);
+                    }
+                    current => {
+                        self.buffer_begin()
+                            .struct_variant_begin(ty, index, variant, len)?;
+                        self.current = Some(current);
+                    }
+                }
+
+                Ok(())
+            }
+            Some(buffered) => buffered.struct_variant_begin(ty, index, variant, len),
+        }
+    }
+
+    #[inline]
+    fn struct_variant_field(
+        &mut self,
+        index: u32,
+        field: &'static str,
+    ) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => {
+                self.pos = Some(Pos::StructVariantField(field));
+
+                Ok(())
+            }
+            Some(buffered) => buffered.struct_variant_field(index, field),
+        }
+    }
+
+    #[inline]
+    fn struct_variant_end(&mut self) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => {
+                let variant = self.take()?.take_serialize_struct_variant()?;
+                self.ok = Some(
+                    variant
+                        .end()
+                        .map_err(err("error completing struct variant"))?,
+                );
+
+                Ok(())
+            }
+            Some(buffered) => {
+                buffered.struct_variant_end()?;
+
+                if buffered.is_streamable() {
+                    self.buffer_end()?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    #[inline]
+    fn i8(&mut self, v: i8) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_any(v),
+            Some(buffered) => buffered.i8(v),
+        }
+    }
+
+    #[inline]
+    fn i16(&mut self, v: i16) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_any(v),
+            Some(buffered) => buffered.i16(v),
+        }
+    }
+
+    #[inline]
+    fn i32(&mut self, v: i32) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_any(v),
+            Some(buffered) => buffered.i32(v),
+        }
+    }
+
+    #[inline]
+    fn u8(&mut self, v: u8) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_any(v),
+            Some(buffered) => buffered.u8(v),
+        }
+    }
+
+    #[inline]
+    fn u16(&mut self, v: u16) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_any(v),
+            Some(buffered) => buffered.u16(v),
+        }
+    }
+
+    #[inline]
+    fn u32(&mut self, v: u32) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_any(v),
+            Some(buffered) => buffered.u32(v),
+        }
+    }
+
+    #[inline]
+    fn f32(&mut self, v: f32) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_any(v),
+            Some(buffered) => buffered.f32(v),
+        }
+    }
+
+    #[inline]
+    fn i64(&mut self, v: i64) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_any(v),
+            Some(buffered) => buffered.i64(v),
+        }
+    }
+
+    #[inline]
+    fn u64(&mut self, v: u64) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_any(v),
+            Some(buffered) => buffered.u64(v),
+        }
+    }
+
+    #[inline]
+    fn i128(&mut self, v: i128) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_any(v),
+            Some(buffered) => buffered.i128(v),
+        }
+    }
+
+    #[inline]
+    fn u128(&mut self, v: u128) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_any(v),
+            Some(buffered) => buffered.u128(v),
+        }
+    }
+
+    #[inline]
+    fn f64(&mut self, v: f64) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_any(v),
+            Some(buffered) => buffered.f64(v),
+        }
+    }
+
+    #[inline]
+    fn number(&mut self, v: &str) -> Result<(), stream::Error> {
+        // `serde` doesn't have arbitrary-precision numbers,
+        // so they're serialized as strings
+        match self.buffer() {
+            None => self.serialize_any(v),
+            Some(buffered) => buffered.number(v),
+        }
+    }
+
+    #[inline]
+    fn bool(&mut self, v: bool) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_any(v),
+            Some(buffered) => buffered.bool(v),
+        }
+    }
+
+    #[inline]
+    fn char(&mut self, v: char) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_any(v),
+            Some(buffered) => buffered.char(v),
+        }
+    }
+
+    #[inline]
+    fn str(&mut self, v: &str) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_any(v),
+            Some(buffered) => buffered.str(v),
+        }
+    }
+
+    #[inline]
+    fn bytes(&mut self, v: &[u8]) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_any(Bytes(v)),
+            Some(buffered) => buffered.bytes(v),
+        }
+    }
+
+    #[inline]
+    fn none(&mut self) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_any(Option::None::<()>),
+            Some(buffered) => buffered.none(),
+        }
+    }
+
+    #[inline]
+    fn unit(&mut self) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_any(()),
+            Some(buffered) => buffered.unit(),
+        }
+    }
+
+    #[inline]
+    fn some_begin(&mut self) -> Result<(), stream::Error> {
+        // The value of an optional value isn't known yet
+        // so it always needs to be buffered
+        match self.buffer() {
+            None => self.buffer_begin().some_begin(),
+            Some(buffered) => buffered.some_begin(),
+        }
+    }
+
+    #[inline]
+    fn some_end(&mut self) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => Err(stream::Error::msg(
+                "invalid serializer value (expected an optional value)",
+            )),
+            Some(buffered) => {
+                buffered.some_end()?;
+
+                if buffered.is_streamable() {
+                    self.buffer_end()?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    #[inline]
+    fn tagged_begin(&mut self, tag: stream::Tag) -> Result<(), stream::Error> {
+        // `serde` doesn't have tags, so unless the tagged value
+        // is already being buffered it's serialized as-is
+        match self.buffer() {
+            None => Ok(()),
+            Some(buffered) => buffered.tagged_begin(tag),
+        }
+    }
+
+    #[inline]
+    fn tagged_end(&mut self) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => Ok(()),
+            Some(buffered) => buffered.tagged_end(),
+        }
+    }
+
+    #[inline]
+    fn fmt(&mut self, v: fmt::Arguments) -> Result<(), stream::Error> {
+        match self.buffer() {
+            None => self.serialize_any(v),
+            Some(buffered) => buffered.fmt(v),
+        }
+    }
+}
+
+enum Pos {
+    Key,
+    Value,
+    Elem,
+    StructField(&'static str),
+    TupleElem,
+    TupleVariantElem,
+    StructVariantField(&'static str),
+}
+
+struct UnitVariant {
+    ty: &'static str,
+    index: u32,
+    variant: &'static str,
+}
+
+impl Serialize for UnitVariant {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_unit_variant(self.ty, self.index, self.variant)
+    }
+}
+
+struct NewtypeVariant<V> {
+    ty: &'static str,
+    index: u32,
+    variant: &'static str,
+    value: V,
+}
+
+impl<V> Serialize for NewtypeVariant<V>
+where
+    V: Serialize,
+{
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_variant(self.ty, self.index, self.variant, &self.value)
+    }
+}
+
+struct Bytes<'a>(&'a [u8]);
+
+impl<'a> Serialize for Bytes<'a> {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+struct Tokens<'a>(&'a [value::owned::Token]);
+
+struct TokensReader<'a> {
+    idx: usize,
+    tokens: &'a [value::owned::Token],
+}
+
+impl<'a> Tokens<'a> {
+    #[inline]
+    fn reader(&self) -> TokensReader<'a> {
+        TokensReader {
+            idx: 0,
+            tokens: self.0,
+        }
+    }
+}
 
 impl<'a> TokensReader<'a> {
     fn next_serializable(&mut self, depth: stack::Depth) -> Tokens<'a> {
@@ -568,6 +1311,41 @@ impl<'a> Serialize for Tokens<'a> {
             None => serializer.serialize_none(),
             Some(token) => {
                 match token.kind {
+                    Kind::Signed8(v) => {
+                        reader.expect_empty().map_err(S::Error::custom)?;
+
+                        v.serialize(serializer)
+                    }
+                    Kind::Signed16(v) => {
+                        reader.expect_empty().map_err(S::Error::custom)?;
+
+                        v.serialize(serializer)
+                    }
+                    Kind::Signed32(v) => {
+                        reader.expect_empty().map_err(S::Error::custom)?;
+
+                        v.serialize(serializer)
+                    }
+                    Kind::Unsigned8(v) => {
+                        reader.expect_empty().map_err(S::Error::custom)?;
+
+                        v.serialize(serializer)
+                    }
+                    Kind::Unsigned16(v) => {
+                        reader.expect_empty().map_err(S::Error::custom)?;
+
+                        v.serialize(serializer)
+                    }
+                    Kind::Unsigned32(v) => {
+                        reader.expect_empty().map_err(S::Error::custom)?;
+
+                        v.serialize(serializer)
+                    }
+                    Kind::Float32(v) => {
+                        reader.expect_empty().map_err(S::Error::custom)?;
+
+                        v.serialize(serializer)
+                    }
                     Kind::Signed(v) => {
                         reader.expect_empty().map_err(S::Error::custom)?;
 
@@ -593,6 +1371,11 @@ impl<'a> Serialize for Tokens<'a> {
 
                         v.serialize(serializer)
                     }
+                    Kind::Number(ref v) => {
+                        reader.expect_empty().map_err(S::Error::custom)?;
+
+                        serializer.serialize_str(v)
+                    }
                     Kind::Bool(v) => {
                         reader.expect_empty().map_err(S::Error::custom)?;
 
@@ -608,11 +1391,59 @@ impl<'a> Serialize for Tokens<'a> {
 
                         v.serialize(serializer)
                     }
+                    Kind::Bytes(ref v) => {
+                        reader.expect_empty().map_err(S::Error::custom)?;
+
+                        serializer.serialize_bytes(v)
+                    }
                     Kind::None => {
                         reader.expect_empty().map_err(S::Error::custom)?;
 
                         serializer.serialize_none()
                     }
+                    Kind::Unit => {
+                        reader.expect_empty().map_err(S::Error::custom)?;
+
+                        serializer.serialize_unit()
+                    }
+                    Kind::SomeBegin => {
+                        let value = reader.next_serializable(token.depth.clone());
+
+                        match reader.next() {
+                            Some(next) => match next.kind {
+                                Kind::SomeEnd => {
+                                    reader.expect_empty().map_err(S::Error::custom)?;
+                                }
+                                _ => return Err(S::Error::custom(
+                                    "unexpected token value (expected an optional value end)",
+                                )),
+                            },
+                            None => return Err(S::Error::custom(
+                                "unexpected end of tokens (expected an optional value end)",
+                            )),
+                        }
+
+                        serializer.serialize_some(&value)
+                    }
+                    Kind::TaggedBegin(_) => {
+                        let value = reader.next_serializable(token.depth.clone());
+
+                        match reader.next() {
+                            Some(next) => match next.kind {
+                                Kind::TaggedEnd => {
+                                    reader.expect_empty().map_err(S::Error::custom)?;
+                                }
+                                _ => return Err(S::Error::custom(
+                                    "unexpected token value (expected a tagged value end)",
+                                )),
+                            },
+                            None => return Err(S::Error::custom(
+                                "unexpected end of tokens (expected a tagged value end)",
+                            )),
+                        }
+
+                        value.serialize(serializer)
+                    }
                     Kind::MapBegin(len) => {
                         let mut map = serializer.serialize_map(len)?;
 
@@ -662,6 +1493,140 @@ impl<'a> Serialize for Tokens<'a> {
 
                         seq.end()
                     }
+                    Kind::StructBegin(ty, len) => {
+                        let mut strct = serializer.serialize_struct(ty, len)?;
+
+                        while let Some(next) = reader.next() {
+                            match next.kind {
+                                Kind::StructField(_, field) => {
+                                    let value = reader.next_serializable(next.depth.clone());
+
+                                    strct.serialize_field(field, &value)?;
+                                }
+                                Kind::StructEnd => {
+                                    reader.expect_empty().map_err(S::Error::custom)?;
+                                    break;
+                                }
+                                _ => return Err(S::Error::custom(
+                                    "unexpected token value (expected a field, or struct end)",
+                                )),
+                            }
+                        }
+
+                        strct.end()
+                    }
+                    Kind::TupleBegin(Some(ty), len) => {
+                        let mut tuple = serializer.serialize_tuple_struct(ty, len)?;
+
+                        while let Some(next) = reader.next() {
+                            match next.kind {
+                                Kind::TupleElem(_) => {
+                                    let elem = reader.next_serializable(next.depth.clone());
+
+                                    tuple.serialize_field(&elem)?;
+                                }
+                                Kind::TupleEnd => {
+                                    reader.expect_empty().map_err(S::Error::custom)?;
+                                    break;
+                                }
+                                _ => return Err(S::Error::custom(
+                                    "unexpected token value (expected an element, or tuple end)",
+                                )),
+                            }
+                        }
+
+                        tuple.end()
+                    }
+                    Kind::TupleBegin(None, len) => {
+                        let mut tuple = serializer.serialize_tuple(len)?;
+
+                        while let Some(next) = reader.next() {
+                            match next.kind {
+                                Kind::TupleElem(_) => {
+                                    let elem = reader.next_serializable(next.depth.clone());
+
+                                    tuple.serialize_element(&elem)?;
+                                }
+                                Kind::TupleEnd => {
+                                    reader.expect_empty().map_err(S::Error::custom)?;
+                                    break;
+                                }
+                                _ => return Err(S::Error::custom(
+                                    "unexpected token value (expected an element, or tuple end)",
+                                )),
+                            }
+                        }
+
+                        tuple.end()
+                    }
+                    Kind::UnitVariant(ty, index, variant) => {
+                        reader.expect_empty().map_err(S::Error::custom)?;
+
+                        serializer.serialize_unit_variant(ty, index, variant)
+                    }
+                    Kind::NewtypeVariantBegin(ty, index, variant) => {
+                        let value = reader.next_serializable(token.depth.clone());
+
+                        match reader.next() {
+                            Some(next) => match next.kind {
+                                Kind::NewtypeVariantEnd => {
+                                    reader.expect_empty().map_err(S::Error::custom)?;
+                                }
+                                _ => return Err(S::Error::custom(
+                                    "unexpected token value (expected a newtype variant end)",
+                                )),
+                            },
+                            None => return Err(S::Error::custom(
+                                "unexpected end of tokens (expected a newtype variant end)",
+                            )),
+                        }
+
+                        serializer.serialize_newtype_variant(ty, index, variant, &value)
+                    }
+                    Kind::TupleVariantBegin(ty, index, variant, len) => {
+                        let mut tuple = serializer.serialize_tuple_variant(ty, index, variant, len)?;
+
+                        while let Some(next) = reader.next() {
+                            match next.kind {
+                                Kind::TupleVariantElem(_) => {
+                                    let elem = reader.next_serializable(next.depth.clone());
+
+                                    tuple.serialize_field(&elem)?;
+                                }
+                                Kind::TupleVariantEnd => {
+                                    reader.expect_empty().map_err(S::Error::custom)?;
+                                    break;
+                                }
+                                _ => return Err(S::Error::custom(
+                                    "unexpected token value (expected an element, or tuple variant end)",
+                                )),
+                            }
+                        }
+
+                        tuple.end()
+                    }
+                    Kind::StructVariantBegin(ty, index, variant, len) => {
+                        let mut strct = serializer.serialize_struct_variant(ty, index, variant, len)?;
+
+                        while let Some(next) = reader.next() {
+                            match next.kind {
+                                Kind::StructVariantField(_, field) => {
+                                    let value = reader.next_serializable(next.depth.clone());
+
+                                    strct.serialize_field(field, &value)?;
+                                }
+                                Kind::StructVariantEnd => {
+                                    reader.expect_empty().map_err(S::Error::custom)?;
+                                    break;
+                                }
+                                _ => return Err(S::Error::custom(
+                                    "unexpected token value (expected a field, or struct variant end)",
+                                )),
+                            }
+                        }
+
+                        strct.end()
+                    }
                     _ => Err(S::Error::custom(
                         "unexpected token value (expected a primitive, map, or sequence)",
                     )),
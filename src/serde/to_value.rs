@@ -37,17 +37,40 @@ where
 
 struct Serializer<T>(T);
 
-impl<'a, 'b> ser::Serializer for Serializer<&'a mut value::Stream<'b>> {
+/**
+A serializer for structs and tuples that tracks the index of the next field.
+*/
+struct Indexed<T> {
+    stream: T,
+    index: u32,
+}
+
+impl<T> Indexed<T> {
+    #[inline]
+    fn begin(stream: T) -> Self {
+        Indexed { stream, index: 0 }
+    }
+
+    #[inline]
+    fn next_index(&mut self) -> u32 {
+        let index = self.index;
+        self.index += 1;
+
+        index
+    }
+}
+
+impl<'a, 'b, 'c> ser::Serializer for Serializer<&'a mut value::Stream<'b, 'c>> {
     type Ok = ();
     type Error = Error;
 
-    type SerializeSeq = Serializer<&'a mut value::Stream<'b>>;
-    type SerializeTuple = Serializer<&'a mut value::Stream<'b>>;
-    type SerializeTupleStruct = Serializer<&'a mut value::Stream<'b>>;
-    type SerializeTupleVariant = Serializer<&'a mut value::Stream<'b>>;
-    type SerializeMap = Serializer<&'a mut value::Stream<'b>>;
-    type SerializeStruct = Serializer<&'a mut value::Stream<'b>>;
-    type SerializeStructVariant = Serializer<&'a mut value::Stream<'b>>;
+    type SerializeSeq = Serializer<&'a mut value::Stream<'b, 'c>>;
+    type SerializeTuple = Indexed<&'a mut value::Stream<'b, 'c>>;
+    type SerializeTupleStruct = Indexed<&'a mut value::Stream<'b, 'c>>;
+    type SerializeTupleVariant = Indexed<&'a mut value::Stream<'b, 'c>>;
+    type SerializeMap = Serializer<&'a mut value::Stream<'b, 'c>>;
+    type SerializeStruct = Indexed<&'a mut value::Stream<'b, 'c>>;
+    type SerializeStructVariant = Indexed<&'a mut value::Stream<'b, 'c>>;
 
     #[inline]
     fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
@@ -57,19 +80,19 @@ impl<'a, 'b> ser::Serializer for Serializer<&'a mut value::Stream<'b>> {
 
     #[inline]
     fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
-        self.serialize_i64(i64::from(v))?;
+        self.0.i8(v)?;
         Ok(())
     }
 
     #[inline]
     fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
-        self.serialize_i64(i64::from(v))?;
+        self.0.i16(v)?;
         Ok(())
     }
 
     #[inline]
     fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
-        self.serialize_i64(i64::from(v))?;
+        self.0.i32(v)?;
         Ok(())
     }
 
@@ -81,19 +104,19 @@ impl<'a, 'b> ser::Serializer for Serializer<&'a mut value::Stream<'b>> {
 
     #[inline]
     fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
-        self.serialize_u64(u64::from(v))?;
+        self.0.u8(v)?;
         Ok(())
     }
 
     #[inline]
     fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
-        self.serialize_u64(u64::from(v))?;
+        self.0.u16(v)?;
         Ok(())
     }
 
     #[inline]
     fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
-        self.serialize_u64(u64::from(v))?;
+        self.0.u32(v)?;
         Ok(())
     }
 
@@ -117,7 +140,7 @@ impl<'a, 'b> ser::Serializer for Serializer<&'a mut value::Stream<'b>> {
 
     #[inline]
     fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
-        self.serialize_f64(f64::from(v))?;
+        self.0.f32(v)?;
         Ok(())
     }
 
@@ -150,19 +173,14 @@ impl<'a, 'b> ser::Serializer for Serializer<&'a mut value::Stream<'b>> {
 
     #[inline]
     fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
-        self.0.seq_begin(Some(v.len()))?;
-
-        for b in v {
-            self.0.seq_elem(b)?;
-        }
-
-        self.0.seq_end()?;
+        self.0.bytes(v)?;
         Ok(())
     }
 
     #[inline]
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
-        self.serialize_unit()
+        self.0.none()?;
+        Ok(())
     }
 
     #[inline]
@@ -170,13 +188,13 @@ impl<'a, 'b> ser::Serializer for Serializer<&'a mut value::Stream<'b>> {
     where
         T: ?Sized + Serialize,
     {
-        value.serialize(self)?;
+        self.0.some(ToValue(value))?;
         Ok(())
     }
 
     #[inline]
     fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
-        self.0.none()?;
+        self.0.unit()?;
         Ok(())
     }
 
@@ -188,11 +206,12 @@ impl<'a, 'b> ser::Serializer for Serializer<&'a mut value::Stream<'b>> {
     #[inline]
     fn serialize_unit_variant(
         self,
-        _: &'static str,
-        _: u32,
+        name: &'static str,
+        variant_index: u32,
         variant: &'static str,
     ) -> Result<Self::Ok, Self::Error> {
-        self.serialize_str(variant)
+        self.0.unit_variant(name, variant_index, variant)?;
+        Ok(())
     }
 
     #[inline]
@@ -210,23 +229,15 @@ impl<'a, 'b> ser::Serializer for Serializer<&'a mut value::Stream<'b>> {
     #[inline]
     fn serialize_newtype_variant<T>(
         self,
-        _: &'static str,
-        _: u32,
+        name: &'static str,
+        variant_index: u32,
         variant: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
         T: ?Sized + Serialize,
     {
-        self.0.map_begin(Some(1))?;
-        self.0.map_key(variant)?;
-
-        self.0.map_value_begin()?.seq_begin(Some(1))?;
-        self.0.seq_elem(ToValue(value))?;
-        self.0.seq_end()?;
-
-        self.0.map_end()?;
-
+        self.0.newtype_variant(name, variant_index, variant, ToValue(value))?;
         Ok(())
     }
 
@@ -238,34 +249,30 @@ impl<'a, 'b> ser::Serializer for Serializer<&'a mut value::Stream<'b>> {
 
     #[inline]
     fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
-        self.0.seq_begin(Some(len))?;
-        Ok(self)
+        self.0.tuple_begin(None, len)?;
+        Ok(Indexed::begin(self.0))
     }
 
     #[inline]
     fn serialize_tuple_struct(
         self,
-        _: &'static str,
+        name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleStruct, Self::Error> {
-        self.0.seq_begin(Some(len))?;
-        Ok(self)
+        self.0.tuple_begin(Some(name), len)?;
+        Ok(Indexed::begin(self.0))
     }
 
     #[inline]
     fn serialize_tuple_variant(
         self,
-        _: &'static str,
-        _: u32,
+        name: &'static str,
+        variant_index: u32,
         variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
-        self.0.map_begin(Some(1))?;
-        self.0.map_key(variant)?;
-
-        self.0.map_value_begin()?.seq_begin(Some(len))?;
-
-        Ok(self)
+        self.0.tuple_variant_begin(name, variant_index, variant, len)?;
+        Ok(Indexed::begin(self.0))
     }
 
     #[inline]
@@ -277,31 +284,27 @@ impl<'a, 'b> ser::Serializer for Serializer<&'a mut value::Stream<'b>> {
     #[inline]
     fn serialize_struct(
         self,
-        _: &'static str,
+        name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStruct, Self::Error> {
-        self.0.map_begin(Some(len))?;
-        Ok(self)
+        self.0.struct_begin(name, len)?;
+        Ok(Indexed::begin(self.0))
     }
 
     #[inline]
     fn serialize_struct_variant(
         self,
-        _: &'static str,
-        _: u32,
+        name: &'static str,
+        variant_index: u32,
         variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
-        self.0.map_begin(Some(1))?;
-        self.0.map_key(variant)?;
-
-        self.0.map_value_begin()?.map_begin(Some(len))?;
-
-        Ok(self)
+        self.0.struct_variant_begin(name, variant_index, variant, len)?;
+        Ok(Indexed::begin(self.0))
     }
 }
 
-impl<'a, 'b> SerializeSeq for Serializer<&'a mut value::Stream<'b>> {
+impl<'a, 'b, 'c> SerializeSeq for Serializer<&'a mut value::Stream<'b, 'c>> {
     type Ok = ();
     type Error = Error;
 
@@ -321,7 +324,7 @@ impl<'a, 'b> SerializeSeq for Serializer<&'a mut value::Stream<'b>> {
     }
 }
 
-impl<'a, 'b> SerializeTuple for Serializer<&'a mut value::Stream<'b>> {
+impl<'a, 'b, 'c> SerializeTuple for Indexed<&'a mut value::Stream<'b, 'c>> {
     type Ok = ();
     type Error = Error;
 
@@ -330,18 +333,19 @@ impl<'a, 'b> SerializeTuple for Serializer<&'a mut value::Stream<'b>> {
     where
         T: ?Sized + Serialize,
     {
-        self.0.seq_elem(ToValue(value))?;
+        let index = self.next_index();
+        self.stream.tuple_elem(index, ToValue(value))?;
         Ok(())
     }
 
     #[inline]
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        self.0.seq_end()?;
+        self.stream.tuple_end()?;
         Ok(())
     }
 }
 
-impl<'a, 'b> SerializeTupleStruct for Serializer<&'a mut value::Stream<'b>> {
+impl<'a, 'b, 'c> SerializeTupleStruct for Indexed<&'a mut value::Stream<'b, 'c>> {
     type Ok = ();
     type Error = Error;
 
@@ -350,18 +354,19 @@ impl<'a, 'b> SerializeTupleStruct for Serializer<&'a mut value::Stream<'b>> {
     where
         T: ?Sized + Serialize,
     {
-        self.0.seq_elem(ToValue(value))?;
+        let index = self.next_index();
+        self.stream.tuple_elem(index, ToValue(value))?;
         Ok(())
     }
 
     #[inline]
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        self.0.seq_end()?;
+        self.stream.tuple_end()?;
         Ok(())
     }
 }
 
-impl<'a, 'b> SerializeTupleVariant for Serializer<&'a mut value::Stream<'b>> {
+impl<'a, 'b, 'c> SerializeTupleVariant for Indexed<&'a mut value::Stream<'b, 'c>> {
     type Ok = ();
     type Error = Error;
 
@@ -370,20 +375,19 @@ impl<'a, 'b> SerializeTupleVariant for Serializer<&'a mut value::Stream<'b>> {
     where
         T: ?Sized + Serialize,
     {
-        self.0.seq_elem(ToValue(value))?;
+        let index = self.next_index();
+        self.stream.tuple_variant_elem(index, ToValue(value))?;
         Ok(())
     }
 
     #[inline]
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        self.0.seq_end()?;
-        self.0.map_end()?;
-
+        self.stream.tuple_variant_end()?;
         Ok(())
     }
 }
 
-impl<'a, 'b> SerializeMap for Serializer<&'a mut value::Stream<'b>> {
+impl<'a, 'b, 'c> SerializeMap for Serializer<&'a mut value::Stream<'b, 'c>> {
     type Ok = ();
     type Error = Error;
 
@@ -412,7 +416,7 @@ impl<'a, 'b> SerializeMap for Serializer<&'a mut value::Stream<'b>> {
     }
 }
 
-impl<'a, 'b> SerializeStruct for Serializer<&'a mut value::Stream<'b>> {
+impl<'a, 'b, 'c> SerializeStruct for Indexed<&'a mut value::Stream<'b, 'c>> {
     type Ok = ();
     type Error = Error;
 
@@ -421,19 +425,25 @@ impl<'a, 'b> SerializeStruct for Serializer<&'a mut value::Stream<'b>> {
     where
         T: ?Sized + Serialize,
     {
-        self.0.map_key(key)?;
-        self.0.map_value(ToValue(value))?;
+        let index = self.next_index();
+        self.stream.struct_field(index, key, ToValue(value))?;
+        Ok(())
+    }
+
+    #[inline]
+    fn skip_field(&mut self, _: &'static str) -> Result<(), Self::Error> {
+        self.next_index();
         Ok(())
     }
 
     #[inline]
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        self.0.map_end()?;
+        self.stream.struct_end()?;
         Ok(())
     }
 }
 
-impl<'a, 'b> SerializeStructVariant for Serializer<&'a mut value::Stream<'b>> {
+impl<'a, 'b, 'c> SerializeStructVariant for Indexed<&'a mut value::Stream<'b, 'c>> {
     type Ok = ();
     type Error = Error;
 
@@ -442,16 +452,20 @@ impl<'a, 'b> SerializeStructVariant for Serializer<&'a mut value::Stream<'b>> {
     where
         T: ?Sized + Serialize,
     {
-        self.0.map_key(key)?;
-        self.0.map_value(ToValue(value))?;
+        let index = self.next_index();
+        self.stream.struct_variant_field(index, key, ToValue(value))?;
         Ok(())
     }
 
     #[inline]
-    fn end(self) -> Result<Self::Ok, Self::Error> {
-        self.0.map_end()?;
-        self.0.map_end()?;
+    fn skip_field(&mut self, _: &'static str) -> Result<(), Self::Error> {
+        self.next_index();
+        Ok(())
+    }
 
+    #[inline]
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.stream.struct_variant_end()?;
         Ok(())
     }
 }
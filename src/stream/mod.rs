@@ -4,6 +4,8 @@ A stream for datastructures.
 
 pub mod stack;
 
+mod tag;
+
 use crate::std::fmt;
 
 #[doc(inline)]
@@ -12,6 +14,7 @@ pub use crate::Error;
 pub use self::{
     fmt::Arguments,
     stack::Stack,
+    tag::Tag,
 };
 
 /**
@@ -36,6 +39,33 @@ pub trait Stream {
     */
     fn fmt(&mut self, args: Arguments) -> Result<(), Error>;
 
+    /**
+    Stream an 8bit signed integer.
+
+    By default, the value is widened and streamed as a signed integer.
+    */
+    fn i8(&mut self, v: i8) -> Result<(), Error> {
+        self.i64(i64::from(v))
+    }
+
+    /**
+    Stream a 16bit signed integer.
+
+    By default, the value is widened and streamed as a signed integer.
+    */
+    fn i16(&mut self, v: i16) -> Result<(), Error> {
+        self.i64(i64::from(v))
+    }
+
+    /**
+    Stream a 32bit signed integer.
+
+    By default, the value is widened and streamed as a signed integer.
+    */
+    fn i32(&mut self, v: i32) -> Result<(), Error> {
+        self.i64(i64::from(v))
+    }
+
     /**
     Stream a signed integer.
     */
@@ -43,6 +73,33 @@ pub trait Stream {
         self.fmt(format_args!("{:?}", v))
     }
 
+    /**
+    Stream an 8bit unsigned integer.
+
+    By default, the value is widened and streamed as an unsigned integer.
+    */
+    fn u8(&mut self, v: u8) -> Result<(), Error> {
+        self.u64(u64::from(v))
+    }
+
+    /**
+    Stream a 16bit unsigned integer.
+
+    By default, the value is widened and streamed as an unsigned integer.
+    */
+    fn u16(&mut self, v: u16) -> Result<(), Error> {
+        self.u64(u64::from(v))
+    }
+
+    /**
+    Stream a 32bit unsigned integer.
+
+    By default, the value is widened and streamed as an unsigned integer.
+    */
+    fn u32(&mut self, v: u32) -> Result<(), Error> {
+        self.u64(u64::from(v))
+    }
+
     /**
     Stream an unsigned integer.
     */
@@ -64,6 +121,15 @@ pub trait Stream {
         self.fmt(format_args!("{:?}", v))
     }
 
+    /**
+    Stream a 32bit floating point value.
+
+    By default, the value is widened and streamed as a floating point value.
+    */
+    fn f32(&mut self, v: f32) -> Result<(), Error> {
+        self.f64(f64::from(v))
+    }
+
     /**
     Stream a floating point value.
     */
@@ -71,6 +137,19 @@ pub trait Stream {
         self.fmt(format_args!("{:?}", v))
     }
 
+    /**
+    Stream an arbitrary-precision number.
+
+    The number is a decimal literal, like `-1.05e10`, that's already been validated.
+    It may not fit in any of the other numeric types, so streams that can represent
+    it natively should write it as-is.
+
+    By default, the number is streamed as a string.
+    */
+    fn number(&mut self, v: &str) -> Result<(), Error> {
+        self.str(v)
+    }
+
     /**
     Stream a boolean.
     */
@@ -93,13 +172,80 @@ pub trait Stream {
         self.fmt(format_args!("{:?}", v))
     }
 
+    /**
+    Stream a buffer of bytes.
+
+    By default, bytes are streamed as a sequence of unsigned integers.
+    */
+    fn bytes(&mut self, v: &[u8]) -> Result<(), Error> {
+        self.seq_begin(Some(v.len()))?;
+
+        for b in v {
+            self.seq_elem()?;
+            self.u64(u64::from(*b))?;
+        }
+
+        self.seq_end()
+    }
+
     /**
     Stream an empty value.
+
+    This is used for optional values that aren't present, like `Option::None`.
     */
     fn none(&mut self) -> Result<(), Error> {
         self.fmt(format_args!("{:?}", ()))
     }
 
+    /**
+    Stream a unit value.
+
+    By default, the unit is streamed as an empty value.
+    */
+    fn unit(&mut self) -> Result<(), Error> {
+        self.none()
+    }
+
+    /**
+    Begin an optional value that's present, like `Option::Some`.
+
+    The optional value is followed by a single value and must be completed
+    by calling `some_end`.
+
+    By default, the value is streamed as-is.
+    */
+    fn some_begin(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /**
+    End an optional value that's present.
+    */
+    fn some_end(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /**
+    Begin a tagged value.
+
+    The tag gives the value a well-known or user-defined meaning,
+    like an RFC 3339 timestamp or a UUID. The tagged value is followed
+    by a single value and must be completed by calling `tagged_end`.
+
+    By default, the tag is ignored and the value is streamed as-is.
+    */
+    fn tagged_begin(&mut self, tag: Tag) -> Result<(), Error> {
+        let _ = tag;
+        Ok(())
+    }
+
+    /**
+    End a tagged value.
+    */
+    fn tagged_end(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
     /**
     Begin a map.
     */
@@ -157,6 +303,211 @@ pub trait Stream {
         Ok(())
     }
 
+    /**
+    Begin a struct.
+
+    The struct has a fixed set of fields, where the name of each field
+    is known upfront. The struct must be completed by calling `struct_end`.
+
+    By default, the struct is streamed as a map.
+    */
+    fn struct_begin(&mut self, ty: &'static str, len: usize) -> Result<(), Error> {
+        let _ = ty;
+        self.map_begin(Some(len))
+    }
+
+    /**
+    Begin a struct field.
+
+    The `index` is the position of the field in the struct's definition,
+    so it can be used to identify the field instead of its name.
+    Fields may be skipped, so indexes aren't always sequential.
+    The field will be implicitly ended by the stream methods that follow it.
+
+    By default, the field is streamed as a map entry,
+    where the key is the name of the field.
+    */
+    fn struct_field(&mut self, index: u32, field: &'static str) -> Result<(), Error> {
+        let _ = index;
+
+        self.map_key()?;
+        self.str(field)?;
+        self.map_value()
+    }
+
+    /**
+    End a struct.
+    */
+    fn struct_end(&mut self) -> Result<(), Error> {
+        self.map_end()
+    }
+
+    /**
+    Begin a tuple.
+
+    The tuple has a fixed number of elements. Tuples that are defined as types
+    have a name, and anonymous tuples don't. The tuple must be completed by
+    calling `tuple_end`.
+
+    By default, the tuple is streamed as a sequence.
+    */
+    fn tuple_begin(&mut self, ty: Option<&'static str>, len: usize) -> Result<(), Error> {
+        let _ = ty;
+        self.seq_begin(Some(len))
+    }
+
+    /**
+    Begin a tuple element.
+
+    The `index` is the position of the element in the tuple.
+    The element will be implicitly ended by the stream methods that follow it.
+
+    By default, the element is streamed as a sequence element.
+    */
+    fn tuple_elem(&mut self, index: u32) -> Result<(), Error> {
+        let _ = index;
+        self.seq_elem()
+    }
+
+    /**
+    End a tuple.
+    */
+    fn tuple_end(&mut self) -> Result<(), Error> {
+        self.seq_end()
+    }
+
+    /**
+    Stream a unit enum variant.
+
+    By default, the variant is streamed as a string containing its name.
+    */
+    fn unit_variant(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        let _ = (ty, index);
+        self.str(variant)
+    }
+
+    /**
+    Begin a newtype enum variant.
+
+    The variant is followed by a single value and must be completed
+    by calling `newtype_variant_end`.
+
+    By default, the variant is streamed as a map with a single entry,
+    where the key is the name of the variant.
+    */
+    fn newtype_variant_begin(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        let _ = (ty, index);
+
+        self.map_begin(Some(1))?;
+        self.map_key()?;
+        self.str(variant)?;
+        self.map_value()
+    }
+
+    /**
+    End a newtype enum variant.
+    */
+    fn newtype_variant_end(&mut self) -> Result<(), Error> {
+        self.map_end()
+    }
+
+    /**
+    Begin a tuple enum variant.
+
+    By default, the variant is streamed as a map with a single entry,
+    where the key is the name of the variant and the value is a sequence.
+    */
+    fn tuple_variant_begin(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<(), Error> {
+        let _ = (ty, index);
+
+        self.map_begin(Some(1))?;
+        self.map_key()?;
+        self.str(variant)?;
+        self.map_value()?;
+        self.seq_begin(Some(len))
+    }
+
+    /**
+    Begin a tuple enum variant element.
+
+    The `index` is the position of the element in the variant.
+    The element will be implicitly ended by the stream methods that follow it.
+    */
+    fn tuple_variant_elem(&mut self, index: u32) -> Result<(), Error> {
+        let _ = index;
+        self.seq_elem()
+    }
+
+    /**
+    End a tuple enum variant.
+    */
+    fn tuple_variant_end(&mut self) -> Result<(), Error> {
+        self.seq_end()?;
+        self.map_end()
+    }
+
+    /**
+    Begin a struct enum variant.
+
+    By default, the variant is streamed as a map with a single entry,
+    where the key is the name of the variant and the value is a map.
+    */
+    fn struct_variant_begin(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<(), Error> {
+        let _ = (ty, index);
+
+        self.map_begin(Some(1))?;
+        self.map_key()?;
+        self.str(variant)?;
+        self.map_value()?;
+        self.map_begin(Some(len))
+    }
+
+    /**
+    Begin a struct enum variant field.
+
+    The `index` is the position of the field in the variant's definition,
+    so it can be used to identify the field instead of its name.
+    Fields may be skipped, so indexes aren't always sequential.
+    The field will be implicitly ended by the stream methods that follow it.
+    */
+    fn struct_variant_field(&mut self, index: u32, field: &'static str) -> Result<(), Error> {
+        let _ = index;
+
+        self.map_key()?;
+        self.str(field)?;
+        self.map_value()
+    }
+
+    /**
+    End a struct enum variant.
+    */
+    fn struct_variant_end(&mut self) -> Result<(), Error> {
+        self.map_end()?;
+        self.map_end()
+    }
+
     /**
     End the stream.
     */
@@ -179,11 +530,41 @@ where
         (**self).fmt(args)
     }
 
+    #[inline]
+    fn i8(&mut self, v: i8) -> Result<(), Error> {
+        (**self).i8(v)
+    }
+
+    #[inline]
+    fn i16(&mut self, v: i16) -> Result<(), Error> {
+        (**self).i16(v)
+    }
+
+    #[inline]
+    fn i32(&mut self, v: i32) -> Result<(), Error> {
+        (**self).i32(v)
+    }
+
     #[inline]
     fn i64(&mut self, v: i64) -> Result<(), Error> {
         (**self).i64(v)
     }
 
+    #[inline]
+    fn u8(&mut self, v: u8) -> Result<(), Error> {
+        (**self).u8(v)
+    }
+
+    #[inline]
+    fn u16(&mut self, v: u16) -> Result<(), Error> {
+        (**self).u16(v)
+    }
+
+    #[inline]
+    fn u32(&mut self, v: u32) -> Result<(), Error> {
+        (**self).u32(v)
+    }
+
     #[inline]
     fn u64(&mut self, v: u64) -> Result<(), Error> {
         (**self).u64(v)
@@ -199,11 +580,21 @@ where
         (**self).u128(v)
     }
 
+    #[inline]
+    fn f32(&mut self, v: f32) -> Result<(), Error> {
+        (**self).f32(v)
+    }
+
     #[inline]
     fn f64(&mut self, v: f64) -> Result<(), Error> {
         (**self).f64(v)
     }
 
+    #[inline]
+    fn number(&mut self, v: &str) -> Result<(), Error> {
+        (**self).number(v)
+    }
+
     #[inline]
     fn bool(&mut self, v: bool) -> Result<(), Error> {
         (**self).bool(v)
@@ -219,11 +610,41 @@ where
         (**self).str(v)
     }
 
+    #[inline]
+    fn bytes(&mut self, v: &[u8]) -> Result<(), Error> {
+        (**self).bytes(v)
+    }
+
     #[inline]
     fn none(&mut self) -> Result<(), Error> {
         (**self).none()
     }
 
+    #[inline]
+    fn unit(&mut self) -> Result<(), Error> {
+        (**self).unit()
+    }
+
+    #[inline]
+    fn some_begin(&mut self) -> Result<(), Error> {
+        (**self).some_begin()
+    }
+
+    #[inline]
+    fn some_end(&mut self) -> Result<(), Error> {
+        (**self).some_end()
+    }
+
+    #[inline]
+    fn tagged_begin(&mut self, tag: Tag) -> Result<(), Error> {
+        (**self).tagged_begin(tag)
+    }
+
+    #[inline]
+    fn tagged_end(&mut self) -> Result<(), Error> {
+        (**self).tagged_end()
+    }
+
     #[inline]
     fn map_begin(&mut self, len: Option<usize>) -> Result<(), Error> {
         (**self).map_begin(len)
@@ -259,12 +680,140 @@ where
         (**self).seq_end()
     }
 
+    #[inline]
+    fn struct_begin(&mut self, ty: &'static str, len: usize) -> Result<(), Error> {
+        (**self).struct_begin(ty, len)
+    }
+
+    #[inline]
+    fn struct_field(&mut self, index: u32, field: &'static str) -> Result<(), Error> {
+        (**self).struct_field(index, field)
+    }
+
+    #[inline]
+    fn struct_end(&mut self) -> Result<(), Error> {
+        (**self).struct_end()
+    }
+
+    #[inline]
+    fn tuple_begin(&mut self, ty: Option<&'static str>, len: usize) -> Result<(), Error> {
+        (**self).tuple_begin(ty, len)
+    }
+
+    #[inline]
+    fn tuple_elem(&mut self, index: u32) -> Result<(), Error> {
+        (**self).tuple_elem(index)
+    }
+
+    #[inline]
+    fn tuple_end(&mut self) -> Result<(), Error> {
+        (**self).tuple_end()
+    }
+
+    #[inline]
+    fn unit_variant(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        (**self).unit_variant(ty, index, variant)
+    }
+
+    #[inline]
+    fn newtype_variant_begin(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        (**self).newtype_variant_begin(ty, index, variant)
+    }
+
+    #[inline]
+    fn newtype_variant_end(&mut self) -> Result<(), Error> {
+        (**self).newtype_variant_end()
+    }
+
+    #[inline]
+    fn tuple_variant_begin(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<(), Error> {
+        (**self).tuple_variant_begin(ty, index, variant, len)
+    }
+
+    #[inline]
+    fn tuple_variant_elem(&mut self, index: u32) -> Result<(), Error> {
+        (**self).tuple_variant_elem(index)
+    }
+
+    #[inline]
+    fn tuple_variant_end(&mut self) -> Result<(), Error> {
+        (**self).tuple_variant_end()
+    }
+
+    #[inline]
+    fn struct_variant_begin(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<(), Error> {
+        (**self).struct_variant_begin(ty, index, variant, len)
+    }
+
+    #[inline]
+    fn struct_variant_field(&mut self, index: u32, field: &'static str) -> Result<(), Error> {
+        (**self).struct_variant_field(index, field)
+    }
+
+    #[inline]
+    fn struct_variant_end(&mut self) -> Result<(), Error> {
+        (**self).struct_variant_end()
+    }
+
     #[inline]
     fn end(&mut self) -> Result<(), Error> {
         (**self).end()
     }
 }
 
+/**
+A value stream that can receive strings borrowed from the value being streamed.
+
+A regular [`Stream`] only receives strings for the duration of a call,
+so it needs to copy them to keep them around. A `BorrowedStream<'v>` can
+also receive strings that live for `'v`, so it can keep references
+to them instead. Use the [`sval::stream_borrowed`](../fn.stream_borrowed.html)
+function to stream a value into a `BorrowedStream`.
+
+Only strings that a value lends using `value::Stream::str_borrowed`
+are received through `str_borrowed`. Other strings are still received through `str`.
+*/
+pub trait BorrowedStream<'v>: Stream {
+    /**
+    Stream a UTF8 string that's borrowed for `'v`.
+    */
+    fn str_borrowed(&mut self, v: &'v str) -> Result<(), Error> {
+        self.str(v)
+    }
+}
+
+impl<'v, T: ?Sized> BorrowedStream<'v> for &mut T
+where
+    T: BorrowedStream<'v>,
+{
+    #[inline]
+    fn str_borrowed(&mut self, v: &'v str) -> Result<(), Error> {
+        (**self).str_borrowed(v)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
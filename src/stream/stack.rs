@@ -9,7 +9,7 @@ The expected position in the stream.
 */
 #[derive(Clone)]
 pub struct Pos {
-    slot: u8,
+    slot: u32,
     depth: usize,
 }
 
@@ -62,7 +62,8 @@ A container for the stream state.
 Implementations of the [`Stream`](../trait.Stream.html) trait are encouraged to use a
 stack for validating their input.
 
-The stack is stateful, and keeps track of open maps and sequences.
+The stack is stateful, and keeps track of open maps, sequences, structs, tuples,
+optional values, tagged values and enum variants.
 
 # Validation
 
@@ -74,14 +75,35 @@ looking at. The stack enforces:
 - Map keys and values are only received within a map.
 - Map keys are always received before map values, and every key has a corresponding value.
 - Sequence elements are only received within a sequence.
-- Every map and sequence is ended, and in the right order.
-- Every map key, map value, and sequence element is followed by valid data.
+- Struct fields are only received within a struct.
+- Tuple elements are only received within a tuple.
+- Tuple variant elements are only received within a tuple variant.
+- Struct variant fields are only received within a struct variant.
+- A newtype variant, optional value or tagged value receives exactly one value.
+- Every map, sequence, struct, tuple, optional value, tagged value and enum variant is ended,
+  and in the right order.
+- Every map key, map value, sequence element, field, and variant value is followed by valid data.
+
+# Structs, tuples and enum variants
+
+Structs, tuples and enum variants are tracked like the containers they're streamed as by default.
+The fields in a struct, the value in a newtype variant and
+the fields in a struct variant are considered map values, and the elements in a tuple or
+tuple variant are considered sequence elements.
+
+# Optional and tagged values
+
+Optional and tagged values don't take a slot in the stack. The value they contain has
+the same position as the optional or tagged value itself, so a tagged map key is still
+a map key. The value is still reported as being one level deeper.
 
 # Depth
 
 By default, stacks have a fixed depth (currently ~16, but this may change) so they can
-work in no-std environments. Each call to `map_begin` or `seq_begin` will increase the
-current depth. If this depth is exceeded then calls to `map_begin` or `seq_begin` will fail.
+work in no-std environments. Each call to `map_begin`, `seq_begin`, `struct_begin`,
+`tuple_begin` or one of the `*_variant_begin` methods will increase
+the current depth. Calls to `some_begin` and `tagged_begin` don't count towards the fixed depth.
+If this depth is exceeded then those calls will fail.
 
 The fixed-depth limit can be removed by adding the `arbitrary-depth` feature to your `Cargo.toml`
 (this also requires the standard library):
@@ -94,44 +116,156 @@ features = ["arbitrary-depth"]
 #[derive(Clone)]
 pub struct Stack {
     inner: inner::Stack,
+    // The number of optional and tagged values that are open in any slot
+    wrapped: usize,
 }
 
 #[derive(Clone, Copy)]
-struct Slot(u8);
+struct Slot(u32);
 
 impl Slot {
-    const EMPTY: u8 = 0b0000_0000;
+    const EMPTY: u32 = 0b0000_0000_0000;
+
+    const DONE: u32 = 0b0000_0000_0001;
+
+    const ROOT: u32 = 0b0000_1000_0000;
+    const MAP: u32 = 0b0000_0100_0000;
+    const SEQ: u32 = 0b0000_0010_0000;
+
+    const KEY: u32 = 0b0000_0001_0000;
+    const VAL: u32 = 0b0000_0000_1000;
+    const ELEM: u32 = 0b0000_0000_0100;
+
+    const VARIANT: u32 = 0b0000_0000_0010;
+
+    const STRUCT: u32 = 0b0001_0000_0000;
+    const TUPLE: u32 = 0b0010_0000_0000;
+
+    const MASK_POS: u32 = 0b0000_1001_1100;
+
+    // Optional and tagged values don't take a slot of their own.
+    // The ones that are open in a slot are kept in its upper bits instead,
+    // as a stack with one bit for the kind of each value under a marker bit.
+    const WRAP_SHIFT: u32 = 16;
+    const MASK_WRAP: u32 = 0xffff_0000;
+
+    const WRAP_SOME: u32 = 0;
+    const WRAP_TAGGED: u32 = 1;
+
+    const MAP_DONE: u32 = Self::MAP | Self::DONE;
+
+    const MAP_KEY: u32 = Self::MAP | Self::KEY;
+    const MAP_KEY_DONE: u32 = Self::MAP_KEY | Self::DONE;
+
+    const MAP_VAL: u32 = Self::MAP | Self::VAL;
+    const MAP_VAL_DONE: u32 = Self::MAP_VAL | Self::DONE;
+
+    const SEQ_DONE: u32 = Self::SEQ | Self::DONE;
 
-    const DONE: u8 = 0b0000_0001;
+    const SEQ_ELEM: u32 = Self::SEQ | Self::ELEM;
+    const SEQ_ELEM_DONE: u32 = Self::SEQ_ELEM | Self::DONE;
 
-    const ROOT: u8 = 0b1000_0000;
-    const MAP: u8 = 0b0100_0000;
-    const SEQ: u8 = 0b0010_0000;
+    const STRUCT_DONE: u32 = Self::STRUCT | Self::MAP_DONE;
 
-    const KEY: u8 = 0b0001_0000;
-    const VAL: u8 = 0b0000_1000;
-    const ELEM: u8 = 0b0000_0100;
+    const STRUCT_VAL: u32 = Self::STRUCT | Self::MAP_VAL;
+    const STRUCT_VAL_DONE: u32 = Self::STRUCT_VAL | Self::DONE;
 
-    const MASK_POS: u8 = 0b1001_1100;
+    const TUPLE_DONE: u32 = Self::TUPLE | Self::SEQ_DONE;
 
-    const MAP_DONE: u8 = Self::MAP | Self::DONE;
+    const TUPLE_ELEM: u32 = Self::TUPLE | Self::SEQ_ELEM;
+    const TUPLE_ELEM_DONE: u32 = Self::TUPLE_ELEM | Self::DONE;
 
-    const MAP_KEY: u8 = Self::MAP | Self::KEY;
-    const MAP_KEY_DONE: u8 = Self::MAP_KEY | Self::DONE;
+    const NEWTYPE_VAL: u32 = Self::VARIANT | Self::VAL;
+    const NEWTYPE_VAL_DONE: u32 = Self::NEWTYPE_VAL | Self::DONE;
 
-    const MAP_VAL: u8 = Self::MAP | Self::VAL;
-    const MAP_VAL_DONE: u8 = Self::MAP_VAL | Self::DONE;
+    const TUPLE_VARIANT_DONE: u32 = Self::VARIANT | Self::SEQ_DONE;
 
-    const SEQ_DONE: u8 = Self::SEQ | Self::DONE;
+    const TUPLE_VARIANT_ELEM: u32 = Self::VARIANT | Self::SEQ_ELEM;
+    const TUPLE_VARIANT_ELEM_DONE: u32 = Self::TUPLE_VARIANT_ELEM | Self::DONE;
 
-    const SEQ_ELEM: u8 = Self::SEQ | Self::ELEM;
-    const SEQ_ELEM_DONE: u8 = Self::SEQ_ELEM | Self::DONE;
+    const STRUCT_VARIANT_DONE: u32 = Self::VARIANT | Self::MAP_DONE;
+
+    const STRUCT_VARIANT_VAL: u32 = Self::VARIANT | Self::MAP_VAL;
+    const STRUCT_VARIANT_VAL_DONE: u32 = Self::STRUCT_VARIANT_VAL | Self::DONE;
 
     #[inline]
     fn root() -> Self {
         Slot(Slot::ROOT)
     }
 
+    /**
+    Whether a map, sequence, struct, tuple, optional value, tagged value
+    or enum variant can begin in this slot.
+
+    The slot must:
+    - not be done and
+    - be the root or
+    - be a map key or
+    - be a map value or
+    - be a seq element or
+    - be a struct field or
+    - be a tuple element or
+    - be a newtype variant value or
+    - be a tuple variant element or
+    - be a struct variant field
+    */
+    #[inline]
+    fn can_begin(self) -> bool {
+        // Any optional or tagged values that are open in the slot don't matter
+        match self.0 & !Slot::MASK_WRAP {
+            Slot::ROOT
+            | Slot::MAP_KEY
+            | Slot::MAP_VAL
+            | Slot::SEQ_ELEM
+            | Slot::STRUCT_VAL
+            | Slot::TUPLE_ELEM
+            | Slot::NEWTYPE_VAL
+            | Slot::TUPLE_VARIANT_ELEM
+            | Slot::STRUCT_VARIANT_VAL => true,
+            _ => false,
+        }
+    }
+
+    /**
+    Open an optional or tagged value in this slot.
+    */
+    #[inline]
+    fn push_wrap(&mut self, kind: u32) -> Result<(), Error> {
+        let wrap = match self.0 >> Slot::WRAP_SHIFT {
+            0 => 0b10 | kind,
+            wrap if wrap & 0x8000 == 0 => (wrap << 1) | kind,
+            _ => return Err(Error::msg("nesting limit reached")),
+        };
+
+        self.0 = (self.0 & !Slot::MASK_WRAP) | (wrap << Slot::WRAP_SHIFT);
+
+        Ok(())
+    }
+
+    /**
+    Close the last optional or tagged value opened in this slot.
+
+    The value must be of the given kind, and the slot must be done.
+    */
+    #[inline]
+    fn pop_wrap(&mut self, kind: u32) -> bool {
+        match self.0 >> Slot::WRAP_SHIFT {
+            0 => false,
+            wrap if wrap & 1 == kind && self.0 & Slot::DONE == Slot::DONE => {
+                // Clear the marker bit along with the last kind
+                let wrap = match wrap >> 1 {
+                    1 => 0,
+                    wrap => wrap,
+                };
+
+                self.0 = (self.0 & !Slot::MASK_WRAP) | (wrap << Slot::WRAP_SHIFT);
+
+                true
+            }
+            _ => false,
+        }
+    }
+
     #[inline]
     fn pos(self, depth: usize) -> Pos {
         Pos {
@@ -155,6 +289,7 @@ impl Stack {
     pub fn new() -> Self {
         Stack {
             inner: inner::Stack::new(),
+            wrapped: 0,
         }
     }
 
@@ -166,6 +301,7 @@ impl Stack {
     #[inline]
     pub fn clear(&mut self) {
         self.inner.clear();
+        self.wrapped = 0;
     }
 
     /**
@@ -180,7 +316,7 @@ impl Stack {
         // It doesn't matter if the slot is
         // marked as done or not
 
-        if self.inner.depth() == 0 {
+        if self.is_empty() {
             // Clear the `DONE` bit so the stack
             // can be re-used
             self.inner.current_mut().0 = Slot::ROOT;
@@ -202,7 +338,9 @@ impl Stack {
     - `f64`
     - `bool`
     - `char`, `&str`
-    - `Option<T>`.
+    - `&[u8]`
+    - `()`, `Option::None`
+    - unit enum variants.
     */
     #[inline]
     pub fn primitive(&mut self) -> Result<Pos, Error> {
@@ -215,7 +353,7 @@ impl Stack {
             Slot::EMPTY => {
                 curr.0 |= Slot::DONE;
 
-                Ok(curr.pos(self.inner.depth()))
+                Ok(curr.pos(self.depth()))
             }
             _ => Err(Error::msg("invalid attempt to write primitive")),
         }
@@ -230,21 +368,13 @@ impl Stack {
     pub fn map_begin(&mut self) -> Result<Pos, Error> {
         let curr = self.inner.current();
 
-        // The current slot must:
-        // - not be done and
-        // - be the root or
-        // - be a map key or
-        // - be a map value or
-        // - be a seq element
-
-        match curr.0 {
-            Slot::ROOT | Slot::MAP_KEY | Slot::MAP_VAL | Slot::SEQ_ELEM => {
-                self.inner.push_depth()?;
-                self.inner.current_mut().0 = Slot::MAP_DONE;
+        if curr.can_begin() {
+            self.inner.push_depth()?;
+            self.inner.current_mut().0 = Slot::MAP_DONE;
 
-                Ok(curr.pos(self.inner.depth()))
-            }
-            _ => Err(Error::msg("invalid attempt to begin map")),
+            Ok(curr.pos(self.depth()))
+        } else {
+            Err(Error::msg("invalid attempt to begin map"))
         }
     }
 
@@ -266,7 +396,7 @@ impl Stack {
             Slot::MAP_DONE | Slot::MAP_VAL_DONE => {
                 curr.0 = Slot::MAP_KEY;
 
-                Ok(curr.pos(self.inner.depth()))
+                Ok(curr.pos(self.depth()))
             }
             _ => Err(Error::msg("invalid attempt to begin key")),
         }
@@ -289,7 +419,7 @@ impl Stack {
             Slot::MAP_KEY_DONE => {
                 curr.0 = Slot::MAP_VAL;
 
-                Ok(curr.pos(self.inner.depth()))
+                Ok(curr.pos(self.depth()))
             }
             _ => Err(Error::msg("invalid attempt to begin value")),
         }
@@ -318,7 +448,7 @@ impl Stack {
                 let mut curr = self.inner.current_mut();
                 curr.0 |= Slot::DONE;
 
-                Ok(curr.pos(self.inner.depth() + 1))
+                Ok(curr.pos(self.depth() + 1))
             }
             _ => Err(Error::msg("invalid attempt to end map")),
         }
@@ -333,21 +463,13 @@ impl Stack {
     pub fn seq_begin(&mut self) -> Result<Pos, Error> {
         let curr = self.inner.current();
 
-        // The current slot must:
-        // - not be done and
-        // - be the root or
-        // - be a map key or
-        // - be a map value or
-        // - be a seq element
-
-        match curr.0 {
-            Slot::ROOT | Slot::MAP_KEY | Slot::MAP_VAL | Slot::SEQ_ELEM => {
-                self.inner.push_depth()?;
-                self.inner.current_mut().0 = Slot::SEQ_DONE;
+        if curr.can_begin() {
+            self.inner.push_depth()?;
+            self.inner.current_mut().0 = Slot::SEQ_DONE;
 
-                Ok(curr.pos(self.inner.depth()))
-            }
-            _ => Err(Error::msg("invalid attempt to begin sequence")),
+            Ok(curr.pos(self.depth()))
+        } else {
+            Err(Error::msg("invalid attempt to begin sequence"))
         }
     }
 
@@ -369,7 +491,7 @@ impl Stack {
             Slot::SEQ_DONE | Slot::SEQ_ELEM_DONE => {
                 curr.0 = Slot::SEQ_ELEM;
 
-                Ok(curr.pos(self.inner.depth()))
+                Ok(curr.pos(self.depth()))
             }
             _ => Err(Error::msg("invalid attempt to begin element")),
         }
@@ -398,353 +520,1228 @@ impl Stack {
                 let mut curr = self.inner.current_mut();
                 curr.0 |= Slot::DONE;
 
-                Ok(curr.pos(self.inner.depth() + 1))
+                Ok(curr.pos(self.depth() + 1))
             }
             _ => Err(Error::msg("invalid attempt to end sequence")),
         }
     }
 
     /**
-    Whether or not the stack has seen a complete and valid stream.
+    Begin a new struct.
+
+    The struct must be completed by calling `struct_end`.
     */
     #[inline]
-    pub fn can_end(&self) -> bool {
-        self.inner.depth() == 0
+    pub fn struct_begin(&mut self) -> Result<Pos, Error> {
+        let curr = self.inner.current();
+
+        if curr.can_begin() {
+            self.inner.push_depth()?;
+            self.inner.current_mut().0 = Slot::STRUCT_DONE;
+
+            Ok(curr.pos(self.depth()))
+        } else {
+            Err(Error::msg("invalid attempt to begin struct"))
+        }
     }
 
     /**
-    Complete the stack.
+    Begin a struct field.
 
-    This stack may be re-used after being completed
-    by calling `begin`.
+    The field will be implicitly completed by the value
+    that follows it.
     */
     #[inline]
-    pub fn end(&mut self) -> Result<(), Error> {
-        // The stack must be on the root slot
-        // It doesn't matter if the slot is
-        // marked as done or not
+    pub fn struct_field(&mut self) -> Result<Pos, Error> {
+        let curr = self.inner.current_mut();
 
-        if self.inner.depth() == 0 {
-            // Set the slot to done so it
-            // can't be re-used without calling begin
-            self.inner.current_mut().0 |= Slot::DONE;
+        // The current slot must:
+        // - be a fresh struct (with no field) or
+        // - be a struct with a done field
 
-            Ok(())
-        } else {
-            Err(Error::msg("stack is not empty"))
+        match curr.0 {
+            Slot::STRUCT_DONE | Slot::STRUCT_VAL_DONE => {
+                curr.0 = Slot::STRUCT_VAL;
+
+                Ok(curr.pos(self.depth()))
+            }
+            _ => Err(Error::msg("invalid attempt to begin struct field")),
         }
     }
-}
 
-#[cfg(not(feature = "arbitrary-depth"))]
-mod inner {
-    use super::{
-        Error,
-        Slot,
-    };
+    /**
+    Complete the current struct.
+    */
+    #[inline]
+    pub fn struct_end(&mut self) -> Result<Pos, Error> {
+        let curr = self.inner.current();
 
-    #[derive(Clone)]
-    pub(super) struct Stack {
-        slots: [Slot; Stack::SLOTS],
-        depth: usize,
-    }
+        // The current slot must:
+        // - be a fresh struct or
+        // - be a struct with a done field
 
-    impl Stack {
-        const SLOTS: usize = 16;
-        const MAX_DEPTH: usize = Self::SLOTS - 1;
+        match curr.0 {
+            Slot::STRUCT_DONE | Slot::STRUCT_VAL_DONE => {
+                // The fact that the slot is not `Slot::ROOT`
+                // guarantees that `depth > 0` and so this
+                // will not overflow
+                unsafe {
+                    self.inner.pop_depth();
+                }
 
-        #[inline]
-        pub(super) fn new() -> Self {
-            Stack {
-                slots: [Slot::root(); Self::SLOTS],
-                depth: 0,
-            }
-        }
+                let curr = self.inner.current_mut();
+                curr.0 |= Slot::DONE;
 
-        #[inline]
-        pub(super) fn clear(&mut self) {
-            *self = Stack {
-                slots: [Slot::root(); Self::SLOTS],
-                depth: 0,
+                Ok(curr.pos(self.depth() + 1))
             }
+            _ => Err(Error::msg("invalid attempt to end struct")),
         }
+    }
 
-        #[inline]
-        pub(super) fn depth(&self) -> usize {
-            self.depth
-        }
+    /**
+    Begin a new tuple.
 
-        #[inline]
-        pub(super) fn push_depth(&mut self) -> Result<(), Error> {
-            if self.depth >= Self::MAX_DEPTH {
-                return Err(Error::msg("nesting limit reached"));
-            }
+    The tuple must be completed by calling `tuple_end`.
+    */
+    #[inline]
+    pub fn tuple_begin(&mut self) -> Result<Pos, Error> {
+        let curr = self.inner.current();
 
-            self.depth += 1;
+        if curr.can_begin() {
+            self.inner.push_depth()?;
+            self.inner.current_mut().0 = Slot::TUPLE_DONE;
 
-            Ok(())
+            Ok(curr.pos(self.depth()))
+        } else {
+            Err(Error::msg("invalid attempt to begin tuple"))
         }
+    }
 
-        // Callers must ensure `self.depth() > 0`
-        #[inline]
-        pub(super) unsafe fn pop_depth(&mut self) {
-            self.depth -= 1;
-        }
+    /**
+    Begin a tuple element.
 
-        #[inline]
-        pub(super) fn current_mut(&mut self) -> &mut Slot {
-            #[cfg(debug_assertions)]
-            {
-                self.slots.get_mut(self.depth).expect("depth out of bounds")
-            }
-            #[cfg(not(debug_assertions))]
-            {
-                // The depth is guaranteed to be in-bounds
-                // and pointing to initialized memory
-                unsafe { self.slots.get_unchecked_mut(self.depth) }
-            }
-        }
+    The element will be implicitly completed by the value
+    that follows it.
+    */
+    #[inline]
+    pub fn tuple_elem(&mut self) -> Result<Pos, Error> {
+        let curr = self.inner.current_mut();
 
-        #[inline]
-        pub(super) fn current(&self) -> Slot {
-            #[cfg(debug_assertions)]
-            {
-                *self.slots.get(self.depth).expect("depth out of bounds")
-            }
-            #[cfg(not(debug_assertions))]
-            {
-                // The depth is guaranteed to be in-bounds
-                // and pointing to initialized memory
-                unsafe { *self.slots.get_unchecked(self.depth) }
+        // The current slot must:
+        // - be a fresh tuple (with no element) or
+        // - be a tuple with a done element
+
+        match curr.0 {
+            Slot::TUPLE_DONE | Slot::TUPLE_ELEM_DONE => {
+                curr.0 = Slot::TUPLE_ELEM;
+
+                Ok(curr.pos(self.depth()))
             }
+            _ => Err(Error::msg("invalid attempt to begin tuple element")),
         }
     }
-}
 
-#[cfg(feature = "arbitrary-depth")]
-mod inner {
-    use smallvec::SmallVec;
+    /**
+    Complete the current tuple.
+    */
+    #[inline]
+    pub fn tuple_end(&mut self) -> Result<Pos, Error> {
+        let curr = self.inner.current();
 
-    use super::{
-        Error,
-        Slot,
-    };
+        // The current slot must:
+        // - be a fresh tuple or
+        // - be a tuple with a done element
 
-    #[derive(Clone)]
-    pub(super) struct Stack(SmallVec<[Slot; 16]>);
+        match curr.0 {
+            Slot::TUPLE_DONE | Slot::TUPLE_ELEM_DONE => {
+                // The fact that the slot is not `Slot::ROOT`
+                // guarantees that `depth > 0` and so this
+                // will not overflow
+                unsafe {
+                    self.inner.pop_depth();
+                }
 
-    impl Stack {
-        #[inline]
-        pub(super) fn new() -> Self {
-            let mut slots = SmallVec::new();
-            slots.push(Slot::root());
+                let curr = self.inner.current_mut();
+                curr.0 |= Slot::DONE;
 
-            Stack(slots)
+                Ok(curr.pos(self.depth() + 1))
+            }
+            _ => Err(Error::msg("invalid attempt to end tuple")),
         }
+    }
 
-        #[inline]
-        pub(super) fn clear(&mut self) {
-            self.0.clear();
-            self.0.push(Slot::root());
-        }
+    /**
+    Begin a new optional value that's present.
 
-        #[inline]
-        pub(super) fn depth(&self) -> usize {
-            self.0.len() - 1
-        }
+    The optional value must be given exactly one value and
+    completed by calling `some_end`.
+    */
+    #[inline]
+    pub fn some_begin(&mut self) -> Result<Pos, Error> {
+        let curr = self.inner.current_mut();
 
-        #[inline]
-        pub(super) fn push_depth(&mut self) -> Result<(), Error> {
-            self.0.push(Slot::root());
+        if curr.can_begin() {
+            curr.push_wrap(Slot::WRAP_SOME)?;
+            self.wrapped += 1;
 
-            Ok(())
+            Ok(curr.pos(self.depth()))
+        } else {
+            Err(Error::msg("invalid attempt to begin optional value"))
         }
+    }
 
-        #[inline]
-        pub(super) unsafe fn pop_depth(&mut self) {
-            self.0.pop();
-        }
+    /**
+    Complete the current optional value.
+    */
+    #[inline]
+    pub fn some_end(&mut self) -> Result<Pos, Error> {
+        let curr = self.inner.current_mut();
 
-        #[inline]
-        pub(super) fn current_mut(&mut self) -> &mut Slot {
-            self.0.last_mut().expect("missing stack slot")
-        }
+        // The current slot must:
+        // - have an optional value as the last value opened in it and
+        // - be done
 
-        #[inline]
-        pub(super) fn current(&self) -> Slot {
-            *self.0.last().expect("missing stack slot")
+        if curr.pop_wrap(Slot::WRAP_SOME) {
+            let pos = curr.pos(self.depth());
+            self.wrapped -= 1;
+
+            Ok(pos)
+        } else {
+            Err(Error::msg("invalid attempt to end optional value"))
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /**
+    Begin a new tagged value.
 
-    #[cfg(feature = "std")]
-    mod prop_test {
-        use super::*;
+    The tagged value must be given exactly one value and
+    completed by calling `tagged_end`.
+    */
+    #[inline]
+    pub fn tagged_begin(&mut self) -> Result<Pos, Error> {
+        let curr = self.inner.current_mut();
 
-        use crate::std::vec::Vec;
+        if curr.can_begin() {
+            curr.push_wrap(Slot::WRAP_TAGGED)?;
+            self.wrapped += 1;
 
-        use quickcheck::{
-            quickcheck,
-            Arbitrary,
-            Gen,
-        };
-        
-        // FIXME: This test isn't very clever about how a
-        // sequence of commands is generated. It's more likely
-        // to come up with a set that fails early than one
-        // that manages to push and pop the stack depth a lot.
-        // We could instead weight commands so we're more likely
-        // to generate something valid with a degree of randomness.
+            Ok(curr.pos(self.depth()))
+        } else {
+            Err(Error::msg("invalid attempt to begin tagged value"))
+        }
+    }
 
-        #[derive(Clone, Copy, Debug)]
-        enum Command {
-            Begin,
-            Primitive,
-            MapBegin,
-            MapKey,
-            MapValue,
-            MapEnd,
-            SeqBegin,
-            SeqElem,
-            SeqEnd,
-            End,
+    /**
+    Complete the current tagged value.
+    */
+    #[inline]
+    pub fn tagged_end(&mut self) -> Result<Pos, Error> {
+        let curr = self.inner.current_mut();
+
+        // The current slot must:
+        // - have a tagged value as the last value opened in it and
+        // - be done
+
+        if curr.pop_wrap(Slot::WRAP_TAGGED) {
+            let pos = curr.pos(self.depth());
+            self.wrapped -= 1;
+
+            Ok(pos)
+        } else {
+            Err(Error::msg("invalid attempt to end tagged value"))
         }
+    }
 
-        impl Arbitrary for Command {
-            fn arbitrary<G: Gen>(g: &mut G) -> Command {
-                match g.next_u32() % 10 {
-                    0 => Command::Primitive,
-                    1 => Command::MapBegin,
-                    2 => Command::MapKey,
-                    3 => Command::MapValue,
-                    4 => Command::MapEnd,
-                    5 => Command::SeqBegin,
-                    6 => Command::SeqElem,
-                    7 => Command::SeqEnd,
-                    8 => Command::End,
-                    9 => Command::Begin,
-                    _ => unreachable!(),
+    /**
+    Begin a new newtype enum variant.
+
+    The variant must be given exactly one value and
+    completed by calling `newtype_variant_end`.
+    */
+    #[inline]
+    pub fn newtype_variant_begin(&mut self) -> Result<Pos, Error> {
+        let curr = self.inner.current();
+
+        if curr.can_begin() {
+            self.inner.push_depth()?;
+            self.inner.current_mut().0 = Slot::NEWTYPE_VAL;
+
+            Ok(curr.pos(self.depth()))
+        } else {
+            Err(Error::msg("invalid attempt to begin newtype variant"))
+        }
+    }
+
+    /**
+    Complete the current newtype enum variant.
+    */
+    #[inline]
+    pub fn newtype_variant_end(&mut self) -> Result<Pos, Error> {
+        let curr = self.inner.current();
+
+        // The current slot must:
+        // - be a newtype variant with a done value
+
+        match curr.0 {
+            Slot::NEWTYPE_VAL_DONE => {
+                // The fact that the slot is not `Slot::ROOT`
+                // guarantees that `depth > 0` and so this
+                // will not overflow
+                unsafe {
+                    self.inner.pop_depth();
                 }
+
+                let curr = self.inner.current_mut();
+                curr.0 |= Slot::DONE;
+
+                Ok(curr.pos(self.depth() + 1))
             }
+            _ => Err(Error::msg("invalid attempt to end newtype variant")),
         }
+    }
 
-        quickcheck! {
-            fn stack_does_not_panic(cmd: Vec<Command>) -> bool {
-                let mut stack = Stack::new();
+    /**
+    Begin a new tuple enum variant.
+
+    The variant must be completed by calling `tuple_variant_end`.
+    */
+    #[inline]
+    pub fn tuple_variant_begin(&mut self) -> Result<Pos, Error> {
+        let curr = self.inner.current();
+
+        if curr.can_begin() {
+            self.inner.push_depth()?;
+            self.inner.current_mut().0 = Slot::TUPLE_VARIANT_DONE;
+
+            Ok(curr.pos(self.depth()))
+        } else {
+            Err(Error::msg("invalid attempt to begin tuple variant"))
+        }
+    }
+
+    /**
+    Begin a tuple enum variant element.
+
+    The element will be implicitly completed by the value
+    that follows it.
+    */
+    #[inline]
+    pub fn tuple_variant_elem(&mut self) -> Result<Pos, Error> {
+        let curr = self.inner.current_mut();
+
+        // The current slot must:
+        // - be a fresh tuple variant (with no element) or
+        // - be a tuple variant with a done element
+
+        match curr.0 {
+            Slot::TUPLE_VARIANT_DONE | Slot::TUPLE_VARIANT_ELEM_DONE => {
+                curr.0 = Slot::TUPLE_VARIANT_ELEM;
+
+                Ok(curr.pos(self.depth()))
+            }
+            _ => Err(Error::msg("invalid attempt to begin tuple variant element")),
+        }
+    }
+
+    /**
+    Complete the current tuple enum variant.
+    */
+    #[inline]
+    pub fn tuple_variant_end(&mut self) -> Result<Pos, Error> {
+        let curr = self.inner.current();
+
+        // The current slot must:
+        // - be a fresh tuple variant or
+        // - be a tuple variant with a done element
+
+        match curr.0 {
+            Slot::TUPLE_VARIANT_DONE | Slot::TUPLE_VARIANT_ELEM_DONE => {
+                // The fact that the slot is not `Slot::ROOT`
+                // guarantees that `depth > 0` and so this
+                // will not overflow
+                unsafe {
+                    self.inner.pop_depth();
+                }
+
+                let curr = self.inner.current_mut();
+                curr.0 |= Slot::DONE;
+
+                Ok(curr.pos(self.depth() + 1))
+            }
+            _ => Err(Error::msg("invalid attempt to end tuple variant")),
+        }
+    }
+
+    /**
+    Begin a new struct enum variant.
+
+    The variant must be completed by calling `struct_variant_end`.
+    */
+    #[inline]
+    pub fn struct_variant_begin(&mut self) -> Result<Pos, Error> {
+        let curr = self.inner.current();
+
+        if curr.can_begin() {
+            self.inner.push_depth()?;
+            self.inner.current_mut().0 = Slot::STRUCT_VARIANT_DONE;
+
+            Ok(curr.pos(self.depth()))
+        } else {
+            Err(Error::msg("invalid attempt to begin struct variant"))
+        }
+    }
+
+    /**
+    Begin a struct enum variant field.
+
+    The field will be implicitly completed by the value
+    that follows it.
+    */
+    #[inline]
+    pub fn struct_variant_field(&mut self) -> Result<Pos, Error> {
+        let curr = self.inner.current_mut();
+
+        // The current slot must:
+        // - be a fresh struct variant (with no field) or
+        // - be a struct variant with a done field
+
+        match curr.0 {
+            Slot::STRUCT_VARIANT_DONE | Slot::STRUCT_VARIANT_VAL_DONE => {
+                curr.0 = Slot::STRUCT_VARIANT_VAL;
+
+                Ok(curr.pos(self.depth()))
+            }
+            _ => Err(Error::msg("invalid attempt to begin struct variant field")),
+        }
+    }
+
+    /**
+    Complete the current struct enum variant.
+    */
+    #[inline]
+    pub fn struct_variant_end(&mut self) -> Result<Pos, Error> {
+        let curr = self.inner.current();
+
+        // The current slot must:
+        // - be a fresh struct variant or
+        // - be a struct variant with a done field
+
+        match curr.0 {
+            Slot::STRUCT_VARIANT_DONE | Slot::STRUCT_VARIANT_VAL_DONE => {
+                // The fact that the slot is not `Slot::ROOT`
+                // guarantees that `depth > 0` and so this
+                // will not overflow
+                unsafe {
+                    self.inner.pop_depth();
+                }
+
+                let curr = self.inner.current_mut();
+                curr.0 |= Slot::DONE;
+
+                Ok(curr.pos(self.depth() + 1))
+            }
+            _ => Err(Error::msg("invalid attempt to end struct variant")),
+        }
+    }
+
+    /**
+    Whether or not the stack has seen a complete and valid stream.
+    */
+    #[inline]
+    pub fn can_end(&self) -> bool {
+        self.is_empty()
+    }
+
+    /**
+    Complete the stack.
+
+    This stack may be re-used after being completed
+    by calling `begin`.
+    */
+    #[inline]
+    pub fn end(&mut self) -> Result<(), Error> {
+        // The stack must be on the root slot
+        // It doesn't matter if the slot is
+        // marked as done or not
+
+        if self.is_empty() {
+            // Set the slot to done so it
+            // can't be re-used without calling begin
+            self.inner.current_mut().0 |= Slot::DONE;
+
+            Ok(())
+        } else {
+            Err(Error::msg("stack is not empty"))
+        }
+    }
+
+    /**
+    Whether the stack is on the root slot, with no optional or tagged values open in it.
+    */
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.inner.depth() == 0 && self.wrapped == 0
+    }
+
+    /**
+    The depth of the current position.
+
+    Optional and tagged values don't take a slot, but the values in them
+    are still nested one level deeper.
+    */
+    #[inline]
+    fn depth(&self) -> usize {
+        self.inner.depth() + self.wrapped
+    }
+}
+
+#[cfg(not(feature = "arbitrary-depth"))]
+mod inner {
+    use super::{
+        Error,
+        Slot,
+    };
+
+    #[derive(Clone)]
+    pub(super) struct Stack {
+        slots: [Slot; Stack::SLOTS],
+        depth: usize,
+    }
+
+    impl Stack {
+        const SLOTS: usize = 16;
+        const MAX_DEPTH: usize = Self::SLOTS - 1;
+
+        #[inline]
+        pub(super) fn new() -> Self {
+            Stack {
+                slots: [Slot::root(); Self::SLOTS],
+                depth: 0,
+            }
+        }
+
+        #[inline]
+        pub(super) fn clear(&mut self) {
+            *self = Stack {
+                slots: [Slot::root(); Self::SLOTS],
+                depth: 0,
+            }
+        }
+
+        #[inline]
+        pub(super) fn depth(&self) -> usize {
+            self.depth
+        }
+
+        #[inline]
+        pub(super) fn push_depth(&mut self) -> Result<(), Error> {
+            if self.depth >= Self::MAX_DEPTH {
+                return Err(Error::msg("nesting limit reached"));
+            }
+
+            self.depth += 1;
+
+            Ok(())
+        }
+
+        // Callers must ensure `self.depth() > 0`
+        #[inline]
+        pub(super) unsafe fn pop_depth(&mut self) {
+            self.depth -= 1;
+        }
+
+        #[inline]
+        pub(super) fn current_mut(&mut self) -> &mut Slot {
+            #[cfg(debug_assertions)]
+            {
+                self.slots.get_mut(self.depth).expect("depth out of bounds")
+            }
+            #[cfg(not(debug_assertions))]
+            {
+                // The depth is guaranteed to be in-bounds
+                // and pointing to initialized memory
+                unsafe { self.slots.get_unchecked_mut(self.depth) }
+            }
+        }
+
+        #[inline]
+        pub(super) fn current(&self) -> Slot {
+            #[cfg(debug_assertions)]
+            {
+                *self.slots.get(self.depth).expect("depth out of bounds")
+            }
+            #[cfg(not(debug_assertions))]
+            {
+                // The depth is guaranteed to be in-bounds
+                // and pointing to initialized memory
+                unsafe { *self.slots.get_unchecked(self.depth) }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary-depth")]
+mod inner {
+    use smallvec::SmallVec;
+
+    use super::{
+        Error,
+        Slot,
+    };
+
+    #[derive(Clone)]
+    pub(super) struct Stack(SmallVec<[Slot; 16]>);
+
+    impl Stack {
+        #[inline]
+        pub(super) fn new() -> Self {
+            let mut slots = SmallVec::new();
+            slots.push(Slot::root());
+
+            Stack(slots)
+        }
+
+        #[inline]
+        pub(super) fn clear(&mut self) {
+            self.0.clear();
+            self.0.push(Slot::root());
+        }
+
+        #[inline]
+        pub(super) fn depth(&self) -> usize {
+            self.0.len() - 1
+        }
+
+        #[inline]
+        pub(super) fn push_depth(&mut self) -> Result<(), Error> {
+            self.0.push(Slot::root());
+
+            Ok(())
+        }
+
+        #[inline]
+        pub(super) unsafe fn pop_depth(&mut self) {
+            self.0.pop();
+        }
+
+        #[inline]
+        pub(super) fn current_mut(&mut self) -> &mut Slot {
+            self.0.last_mut().expect("missing stack slot")
+        }
+
+        #[inline]
+        pub(super) fn current(&self) -> Slot {
+            *self.0.last().expect("missing stack slot")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "std")]
+    mod prop_test {
+        use super::*;
+
+        use crate::std::vec::Vec;
+
+        use quickcheck::{
+            quickcheck,
+            Arbitrary,
+            Gen,
+        };
+        
+        // FIXME: This test isn't very clever about how a
+        // sequence of commands is generated. It's more likely
+        // to come up with a set that fails early than one
+        // that manages to push and pop the stack depth a lot.
+        // We could instead weight commands so we're more likely
+        // to generate something valid with a degree of randomness.
+
+        #[derive(Clone, Copy, Debug)]
+        enum Command {
+            Begin,
+            Primitive,
+            MapBegin,
+            MapKey,
+            MapValue,
+            MapEnd,
+            SeqBegin,
+            SeqElem,
+            SeqEnd,
+            StructBegin,
+            StructField,
+            StructEnd,
+            TupleBegin,
+            TupleElem,
+            TupleEnd,
+            SomeBegin,
+            SomeEnd,
+            TaggedBegin,
+            TaggedEnd,
+            NewtypeVariantBegin,
+            NewtypeVariantEnd,
+            TupleVariantBegin,
+            TupleVariantElem,
+            TupleVariantEnd,
+            StructVariantBegin,
+            StructVariantField,
+            StructVariantEnd,
+            End,
+        }
+
+        impl Arbitrary for Command {
+            fn arbitrary<G: Gen>(g: &mut G) -> Command {
+                match g.next_u32() % 28 {
+                    0 => Command::Primitive,
+                    1 => Command::MapBegin,
+                    2 => Command::MapKey,
+                    3 => Command::MapValue,
+                    4 => Command::MapEnd,
+                    5 => Command::SeqBegin,
+                    6 => Command::SeqElem,
+                    7 => Command::SeqEnd,
+                    8 => Command::End,
+                    9 => Command::Begin,
+                    10 => Command::NewtypeVariantBegin,
+                    11 => Command::NewtypeVariantEnd,
+                    12 => Command::TupleVariantBegin,
+                    13 => Command::TupleVariantElem,
+                    14 => Command::TupleVariantEnd,
+                    15 => Command::StructVariantBegin,
+                    16 => Command::StructVariantField,
+                    17 => Command::StructVariantEnd,
+                    18 => Command::StructBegin,
+                    19 => Command::StructField,
+                    20 => Command::StructEnd,
+                    21 => Command::TupleBegin,
+                    22 => Command::TupleElem,
+                    23 => Command::TupleEnd,
+                    24 => Command::SomeBegin,
+                    25 => Command::SomeEnd,
+                    26 => Command::TaggedBegin,
+                    27 => Command::TaggedEnd,
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        quickcheck! {
+            fn stack_does_not_panic(cmd: Vec<Command>) -> bool {
+                let mut stack = Stack::new();
+
+                for cmd in cmd {
+                    match cmd {
+                        Command::Begin => {
+                            let _ = stack.begin();
+                        },
+                        Command::Primitive => {
+                            let _ = stack.primitive();
+                        },
+                        Command::MapBegin => {
+                            let _ = stack.map_begin();
+                        },
+                        Command::MapKey => {
+                            let _ = stack.map_key();
+                        },
+                        Command::MapValue => {
+                            let _ = stack.map_value();
+                        },
+                        Command::MapEnd => {
+                            let _ = stack.map_end();
+                        },
+                        Command::SeqBegin => {
+                            let _ = stack.seq_begin();
+                        },
+                        Command::SeqElem => {
+                            let _ = stack.seq_elem();
+                        },
+                        Command::SeqEnd => {
+                            let _ = stack.seq_end();
+                        },
+                        Command::StructBegin => {
+                            let _ = stack.struct_begin();
+                        },
+                        Command::StructField => {
+                            let _ = stack.struct_field();
+                        },
+                        Command::StructEnd => {
+                            let _ = stack.struct_end();
+                        },
+                        Command::TupleBegin => {
+                            let _ = stack.tuple_begin();
+                        },
+                        Command::TupleElem => {
+                            let _ = stack.tuple_elem();
+                        },
+                        Command::TupleEnd => {
+                            let _ = stack.tuple_end();
+                        },
+                        Command::SomeBegin => {
+                            let _ = stack.some_begin();
+                        },
+                        Command::SomeEnd => {
+                            let _ = stack.some_end();
+                        },
+                        Command::TaggedBegin => {
+                            let _ = stack.tagged_begin();
+                        },
+                        Command::TaggedEnd => {
+                            let _ = stack.tagged_end();
+                        },
+                        Command::NewtypeVariantBegin => {
+                            let _ = stack.newtype_variant_begin();
+                        },
+                        Command::NewtypeVariantEnd => {
+                            let _ = stack.newtype_variant_end();
+                        },
+                        Command::TupleVariantBegin => {
+                            let _ = stack.tuple_variant_begin();
+                        },
+                        Command::TupleVariantElem => {
+                            let _ = stack.tuple_variant_elem();
+                        },
+                        Command::TupleVariantEnd => {
+                            let _ = stack.tuple_variant_end();
+                        },
+                        Command::StructVariantBegin => {
+                            let _ = stack.struct_variant_begin();
+                        },
+                        Command::StructVariantField => {
+                            let _ = stack.struct_variant_field();
+                        },
+                        Command::StructVariantEnd => {
+                            let _ = stack.struct_variant_end();
+                        },
+                        Command::End => {
+                            let _ = stack.end();
+                        },
+                    }
+                }
+
+                // So long as the stack doesn't panic we're happy
+                true
+            }
+        }
+    }
+
+    #[cfg(feature = "arbitrary-depth")]
+    mod arbitrary_depth {
+        use super::*;
+
+        #[test]
+        fn stack_spills_to_heap_on_overflow() {
+            let mut stack = Stack::new();
+
+            for _ in 0..64 {
+                stack.map_begin().unwrap();
+                stack.map_key().unwrap();
+            }
+        }
+    }
+
+    #[cfg(not(feature = "arbitrary-depth"))]
+    mod fixed_depth {
+        use super::*;
+
+        #[test]
+        fn error_overflow_stack() {
+            let mut stack = Stack::new();
+
+            for _ in 0..15 {
+                stack.map_begin().unwrap();
+                stack.map_key().unwrap();
+            }
+
+            // The 16th attempt to begin a map should fail
+            assert!(stack.map_begin().is_err());
+        }
+
+        #[test]
+        fn some_does_not_overflow_stack() {
+            let mut stack = Stack::new();
+
+            for _ in 0..15 {
+                stack.map_begin().unwrap();
+                stack.map_key().unwrap();
+                stack.primitive().unwrap();
+                stack.map_value().unwrap();
+                stack.some_begin().unwrap();
+            }
+
+            stack.primitive().unwrap();
+
+            for _ in 0..15 {
+                stack.some_end().unwrap();
+                stack.map_end().unwrap();
+            }
+
+            stack.end().unwrap();
+        }
+
+        #[test]
+        fn tagged_does_not_overflow_stack() {
+            let mut stack = Stack::new();
+
+            for _ in 0..15 {
+                stack.tagged_begin().unwrap();
+                stack.some_begin().unwrap();
+                stack.map_begin().unwrap();
+                stack.map_key().unwrap();
+                stack.primitive().unwrap();
+                stack.map_value().unwrap();
+            }
+
+            stack.primitive().unwrap();
+
+            for _ in 0..15 {
+                stack.map_end().unwrap();
+                stack.some_end().unwrap();
+                stack.tagged_end().unwrap();
+            }
+
+            stack.end().unwrap();
+        }
+    }
+
+    #[test]
+    fn primitive() {
+        let mut stack = Stack::new();
+
+        stack.primitive().unwrap();
+        stack.end().unwrap();
+    }
+
+    #[test]
+    fn end_empty_stack() {
+        let mut stack = Stack::new();
+
+        stack.end().unwrap();
+    }
+
+    #[test]
+    fn error_double_primitive() {
+        let mut stack = Stack::new();
+
+        stack.primitive().unwrap();
+
+        assert!(stack.primitive().is_err());
+    }
+
+    #[test]
+    fn simple_map() {
+        let mut stack = Stack::new();
+
+        stack.map_begin().unwrap();
+
+        stack.map_key().unwrap();
+        stack.primitive().unwrap();
+
+        stack.map_value().unwrap();
+        stack.primitive().unwrap();
+
+        stack.map_end().unwrap();
+
+        stack.end().unwrap();
+    }
+
+    #[test]
+    fn empty_map() {
+        let mut stack = Stack::new();
+
+        stack.map_begin().unwrap();
+        stack.map_end().unwrap();
+
+        stack.end().unwrap();
+    }
+
+    #[test]
+    fn error_end_map_as_seq() {
+        let mut stack = Stack::new();
+
+        stack.map_begin().unwrap();
+
+        assert!(stack.seq_end().is_err());
+    }
+
+    #[test]
+    fn error_end_seq_as_map() {
+        let mut stack = Stack::new();
+
+        stack.seq_begin().unwrap();
+
+        assert!(stack.map_end().is_err());
+    }
+
+    #[test]
+    fn error_end_map_at_root_depth() {
+        let mut stack = Stack::new();
+
+        assert!(stack.map_end().is_err());
+    }
+
+    #[test]
+    fn error_end_seq_at_root_depth() {
+        let mut stack = Stack::new();
+
+        assert!(stack.seq_end().is_err());
+    }
+
+    #[test]
+    fn error_primitive_without_map_key() {
+        let mut stack = Stack::new();
+
+        stack.map_begin().unwrap();
+
+        assert!(stack.primitive().is_err());
+    }
+
+    #[test]
+    fn error_primitive_without_map_value() {
+        let mut stack = Stack::new();
+
+        stack.map_begin().unwrap();
+
+        stack.map_key().unwrap();
+        stack.primitive().unwrap();
+
+        assert!(stack.primitive().is_err());
+    }
+
+    #[test]
+    fn error_map_value_without_map_key() {
+        let mut stack = Stack::new();
+
+        stack.map_begin().unwrap();
+
+        assert!(stack.map_value().is_err());
+    }
+
+    #[test]
+    fn error_end_incomplete_map() {
+        let mut stack = Stack::new();
+
+        stack.map_begin().unwrap();
+        stack.map_key().unwrap();
+
+        assert!(stack.map_end().is_err());
+    }
+
+    #[test]
+    fn error_map_key_outside_map() {
+        let mut stack = Stack::new();
+
+        assert!(stack.map_key().is_err());
+    }
+
+    #[test]
+    fn error_map_value_outside_map() {
+        let mut stack = Stack::new();
+
+        assert!(stack.map_value().is_err());
+    }
+
+    #[test]
+    fn simple_seq() {
+        let mut stack = Stack::new();
+
+        stack.seq_begin().unwrap();
+
+        stack.seq_elem().unwrap();
+        stack.primitive().unwrap();
+
+        stack.seq_end().unwrap();
+
+        stack.end().unwrap();
+    }
+
+    #[test]
+    fn empty_seq() {
+        let mut stack = Stack::new();
+
+        stack.seq_begin().unwrap();
+        stack.seq_end().unwrap();
+
+        stack.end().unwrap();
+    }
+
+    #[test]
+    fn error_primitive_without_seq_elem() {
+        let mut stack = Stack::new();
+
+        stack.seq_begin().unwrap();
+
+        assert!(stack.primitive().is_err());
+    }
+
+    #[test]
+    fn error_end_incomplete_seq() {
+        let mut stack = Stack::new();
+
+        stack.seq_begin().unwrap();
+
+        stack.seq_elem().unwrap();
+
+        assert!(stack.seq_end().is_err());
+    }
+
+    #[test]
+    fn error_seq_elem_outside_seq() {
+        let mut stack = Stack::new();
+
+        assert!(stack.seq_elem().is_err());
+    }
+
+    #[test]
+    fn simple_struct() {
+        let mut stack = Stack::new();
+
+        stack.struct_begin().unwrap();
+
+        stack.struct_field().unwrap();
+        assert!(stack.primitive().unwrap().is_value());
+
+        stack.struct_field().unwrap();
+        stack.seq_begin().unwrap();
+        stack.seq_end().unwrap();
+
+        stack.struct_end().unwrap();
+
+        stack.end().unwrap();
+    }
 
-                for cmd in cmd {
-                    match cmd {
-                        Command::Begin => {
-                            let _ = stack.begin();
-                        },
-                        Command::Primitive => {
-                            let _ = stack.primitive();
-                        },
-                        Command::MapBegin => {
-                            let _ = stack.map_begin();
-                        },
-                        Command::MapKey => {
-                            let _ = stack.map_key();
-                        },
-                        Command::MapValue => {
-                            let _ = stack.map_value();
-                        },
-                        Command::MapEnd => {
-                            let _ = stack.map_end();
-                        },
-                        Command::SeqBegin => {
-                            let _ = stack.seq_begin();
-                        },
-                        Command::SeqElem => {
-                            let _ = stack.seq_elem();
-                        },
-                        Command::SeqEnd => {
-                            let _ = stack.seq_end();
-                        },
-                        Command::End => {
-                            let _ = stack.end();
-                        },
-                    }
-                }
+    #[test]
+    fn error_end_struct_as_map() {
+        let mut stack = Stack::new();
 
-                // So long as the stack doesn't panic we're happy
-                true
-            }
-        }
+        stack.struct_begin().unwrap();
+
+        assert!(stack.map_end().is_err());
     }
 
-    #[cfg(feature = "arbitrary-depth")]
-    mod arbitrary_depth {
-        use super::*;
+    #[test]
+    fn error_end_struct_as_struct_variant() {
+        let mut stack = Stack::new();
 
-        #[test]
-        fn stack_spills_to_heap_on_overflow() {
-            let mut stack = Stack::new();
+        stack.struct_begin().unwrap();
 
-            for _ in 0..64 {
-                stack.map_begin().unwrap();
-                stack.map_key().unwrap();
-            }
-        }
+        assert!(stack.struct_variant_end().is_err());
     }
 
-    #[cfg(not(feature = "arbitrary-depth"))]
-    mod fixed_depth {
-        use super::*;
+    #[test]
+    fn error_map_key_in_struct() {
+        let mut stack = Stack::new();
 
-        #[test]
-        fn error_overflow_stack() {
-            let mut stack = Stack::new();
+        stack.struct_begin().unwrap();
 
-            for _ in 0..15 {
-                stack.map_begin().unwrap();
-                stack.map_key().unwrap();
-            }
+        assert!(stack.map_key().is_err());
+    }
 
-            // The 16th attempt to begin a map should fail
-            assert!(stack.map_begin().is_err());
-        }
+    #[test]
+    fn error_end_incomplete_struct() {
+        let mut stack = Stack::new();
+
+        stack.struct_begin().unwrap();
+        stack.struct_field().unwrap();
+
+        assert!(stack.struct_end().is_err());
     }
 
     #[test]
-    fn primitive() {
+    fn simple_tuple() {
         let mut stack = Stack::new();
 
+        stack.tuple_begin().unwrap();
+
+        stack.tuple_elem().unwrap();
+        assert!(stack.primitive().unwrap().is_elem());
+
+        stack.tuple_elem().unwrap();
         stack.primitive().unwrap();
+
+        stack.tuple_end().unwrap();
+
         stack.end().unwrap();
     }
 
     #[test]
-    fn end_empty_stack() {
+    fn error_end_tuple_as_seq() {
+        let mut stack = Stack::new();
+
+        stack.tuple_begin().unwrap();
+
+        assert!(stack.seq_end().is_err());
+    }
+
+    #[test]
+    fn error_seq_elem_in_tuple() {
+        let mut stack = Stack::new();
+
+        stack.tuple_begin().unwrap();
+
+        assert!(stack.seq_elem().is_err());
+    }
+
+    #[test]
+    fn error_tuple_variant_elem_in_tuple() {
         let mut stack = Stack::new();
 
+        stack.tuple_begin().unwrap();
+
+        assert!(stack.tuple_variant_elem().is_err());
+    }
+
+    #[test]
+    fn simple_some() {
+        let mut stack = Stack::new();
+
+        stack.some_begin().unwrap();
+        stack.primitive().unwrap();
+        stack.some_end().unwrap();
+
         stack.end().unwrap();
     }
 
     #[test]
-    fn error_double_primitive() {
+    fn nested_some() {
         let mut stack = Stack::new();
 
+        stack.seq_begin().unwrap();
+        stack.seq_elem().unwrap();
+
+        stack.some_begin().unwrap();
+        stack.some_begin().unwrap();
         stack.primitive().unwrap();
+        stack.some_end().unwrap();
+        stack.some_end().unwrap();
 
-        assert!(stack.primitive().is_err());
+        stack.seq_end().unwrap();
+
+        stack.end().unwrap();
     }
 
     #[test]
-    fn simple_map() {
+    fn some_keeps_pos() {
         let mut stack = Stack::new();
 
         stack.map_begin().unwrap();
-
         stack.map_key().unwrap();
-        stack.primitive().unwrap();
+
+        let begin = stack.some_begin().unwrap();
+        let key = stack.primitive().unwrap();
+        let end = stack.some_end().unwrap();
+
+        assert!(begin.is_key());
+        assert!(key.is_key());
+        assert!(end.is_key());
+        assert!(begin.depth() == key.depth());
 
         stack.map_value().unwrap();
-        stack.primitive().unwrap();
+
+        stack.some_begin().unwrap();
+        assert!(stack.primitive().unwrap().is_value());
+        stack.some_end().unwrap();
 
         stack.map_end().unwrap();
 
@@ -752,149 +1749,288 @@ mod tests {
     }
 
     #[test]
-    fn empty_map() {
+    fn tagged_keeps_pos() {
         let mut stack = Stack::new();
 
         stack.map_begin().unwrap();
+        stack.map_key().unwrap();
+
+        let begin = stack.tagged_begin().unwrap();
+        let key = stack.primitive().unwrap();
+        let end = stack.tagged_end().unwrap();
+
+        assert!(begin.is_key());
+        assert!(key.is_key());
+        assert!(end.is_key());
+        assert!(begin.depth() == key.depth());
+
+        stack.map_value().unwrap();
+        stack.primitive().unwrap();
         stack.map_end().unwrap();
 
         stack.end().unwrap();
     }
 
     #[test]
-    fn error_end_map_as_seq() {
+    fn error_overflow_wrapped_values() {
         let mut stack = Stack::new();
 
-        stack.map_begin().unwrap();
+        for _ in 0..15 {
+            stack.some_begin().unwrap();
+        }
 
-        assert!(stack.seq_end().is_err());
+        // The 16th optional value in the same slot should fail
+        assert!(stack.tagged_begin().is_err());
     }
 
     #[test]
-    fn error_end_seq_as_map() {
+    fn error_end_with_open_some() {
         let mut stack = Stack::new();
 
-        stack.seq_begin().unwrap();
+        stack.some_begin().unwrap();
+        stack.primitive().unwrap();
 
-        assert!(stack.map_end().is_err());
+        assert!(!stack.can_end());
+        assert!(stack.end().is_err());
     }
 
     #[test]
-    fn error_end_map_at_root_depth() {
+    fn error_end_map_with_open_some() {
         let mut stack = Stack::new();
 
+        stack.map_begin().unwrap();
+        stack.map_key().unwrap();
+        stack.primitive().unwrap();
+        stack.map_value().unwrap();
+
+        stack.some_begin().unwrap();
+        stack.primitive().unwrap();
+
         assert!(stack.map_end().is_err());
     }
 
     #[test]
-    fn error_end_seq_at_root_depth() {
+    fn error_end_empty_some() {
         let mut stack = Stack::new();
 
-        assert!(stack.seq_end().is_err());
+        stack.some_begin().unwrap();
+
+        assert!(stack.some_end().is_err());
     }
 
     #[test]
-    fn error_primitive_without_map_key() {
+    fn error_end_some_as_newtype_variant() {
         let mut stack = Stack::new();
 
-        stack.map_begin().unwrap();
+        stack.some_begin().unwrap();
+        stack.primitive().unwrap();
 
-        assert!(stack.primitive().is_err());
+        assert!(stack.newtype_variant_end().is_err());
     }
 
     #[test]
-    fn error_primitive_without_map_value() {
+    fn simple_tagged() {
         let mut stack = Stack::new();
 
-        stack.map_begin().unwrap();
+        stack.tagged_begin().unwrap();
+        stack.primitive().unwrap();
+        stack.tagged_end().unwrap();
 
+        stack.end().unwrap();
+    }
+
+    #[test]
+    fn nested_tagged() {
+        let mut stack = Stack::new();
+
+        stack.map_begin().unwrap();
         stack.map_key().unwrap();
+
+        stack.tagged_begin().unwrap();
         stack.primitive().unwrap();
+        stack.tagged_end().unwrap();
 
-        assert!(stack.primitive().is_err());
+        stack.map_value().unwrap();
+
+        stack.some_begin().unwrap();
+        stack.tagged_begin().unwrap();
+        stack.primitive().unwrap();
+        stack.tagged_end().unwrap();
+        stack.some_end().unwrap();
+
+        stack.map_end().unwrap();
+
+        stack.end().unwrap();
     }
 
     #[test]
-    fn error_map_value_without_map_key() {
+    fn error_end_empty_tagged() {
         let mut stack = Stack::new();
 
-        stack.map_begin().unwrap();
+        stack.tagged_begin().unwrap();
 
-        assert!(stack.map_value().is_err());
+        assert!(stack.tagged_end().is_err());
     }
 
     #[test]
-    fn error_end_incomplete_map() {
+    fn error_end_some_as_tagged() {
         let mut stack = Stack::new();
 
-        stack.map_begin().unwrap();
-        stack.map_key().unwrap();
+        stack.tagged_begin().unwrap();
+        stack.some_begin().unwrap();
+        stack.primitive().unwrap();
 
-        assert!(stack.map_end().is_err());
+        assert!(stack.tagged_end().is_err());
+
+        stack.some_end().unwrap();
+        stack.tagged_end().unwrap();
+
+        stack.end().unwrap();
     }
 
     #[test]
-    fn error_map_key_outside_map() {
+    fn error_end_tagged_as_some() {
         let mut stack = Stack::new();
 
-        assert!(stack.map_key().is_err());
+        stack.tagged_begin().unwrap();
+        stack.primitive().unwrap();
+
+        assert!(stack.some_end().is_err());
     }
 
     #[test]
-    fn error_map_value_outside_map() {
+    fn simple_newtype_variant() {
         let mut stack = Stack::new();
 
-        assert!(stack.map_value().is_err());
+        stack.newtype_variant_begin().unwrap();
+        stack.primitive().unwrap();
+        stack.newtype_variant_end().unwrap();
+
+        stack.end().unwrap();
     }
 
     #[test]
-    fn simple_seq() {
+    fn nested_newtype_variant() {
         let mut stack = Stack::new();
 
-        stack.seq_begin().unwrap();
+        stack.newtype_variant_begin().unwrap();
 
+        stack.seq_begin().unwrap();
         stack.seq_elem().unwrap();
         stack.primitive().unwrap();
-
         stack.seq_end().unwrap();
 
+        stack.newtype_variant_end().unwrap();
+
         stack.end().unwrap();
     }
 
     #[test]
-    fn empty_seq() {
+    fn error_end_empty_newtype_variant() {
         let mut stack = Stack::new();
 
-        stack.seq_begin().unwrap();
-        stack.seq_end().unwrap();
+        stack.newtype_variant_begin().unwrap();
 
-        stack.end().unwrap();
+        assert!(stack.newtype_variant_end().is_err());
     }
 
     #[test]
-    fn error_primitive_without_seq_elem() {
+    fn error_double_primitive_in_newtype_variant() {
         let mut stack = Stack::new();
 
-        stack.seq_begin().unwrap();
+        stack.newtype_variant_begin().unwrap();
+        stack.primitive().unwrap();
 
         assert!(stack.primitive().is_err());
     }
 
     #[test]
-    fn error_end_incomplete_seq() {
+    fn simple_tuple_variant() {
         let mut stack = Stack::new();
 
-        stack.seq_begin().unwrap();
+        stack.tuple_variant_begin().unwrap();
 
-        stack.seq_elem().unwrap();
+        stack.tuple_variant_elem().unwrap();
+        stack.primitive().unwrap();
+
+        stack.tuple_variant_elem().unwrap();
+        stack.primitive().unwrap();
+
+        stack.tuple_variant_end().unwrap();
+
+        stack.end().unwrap();
+    }
+
+    #[test]
+    fn error_end_tuple_variant_as_seq() {
+        let mut stack = Stack::new();
+
+        stack.tuple_variant_begin().unwrap();
 
         assert!(stack.seq_end().is_err());
     }
 
     #[test]
-    fn error_seq_elem_outside_seq() {
+    fn error_seq_elem_in_tuple_variant() {
         let mut stack = Stack::new();
 
+        stack.tuple_variant_begin().unwrap();
+
         assert!(stack.seq_elem().is_err());
     }
+
+    #[test]
+    fn error_end_incomplete_tuple_variant() {
+        let mut stack = Stack::new();
+
+        stack.tuple_variant_begin().unwrap();
+        stack.tuple_variant_elem().unwrap();
+
+        assert!(stack.tuple_variant_end().is_err());
+    }
+
+    #[test]
+    fn simple_struct_variant() {
+        let mut stack = Stack::new();
+
+        stack.struct_variant_begin().unwrap();
+
+        stack.struct_variant_field().unwrap();
+        stack.primitive().unwrap();
+
+        stack.struct_variant_field().unwrap();
+        stack.map_begin().unwrap();
+        stack.map_end().unwrap();
+
+        stack.struct_variant_end().unwrap();
+
+        stack.end().unwrap();
+    }
+
+    #[test]
+    fn error_end_struct_variant_as_map() {
+        let mut stack = Stack::new();
+
+        stack.struct_variant_begin().unwrap();
+
+        assert!(stack.map_end().is_err());
+    }
+
+    #[test]
+    fn error_map_key_in_struct_variant() {
+        let mut stack = Stack::new();
+
+        stack.struct_variant_begin().unwrap();
+
+        assert!(stack.map_key().is_err());
+    }
+
+    #[test]
+    fn error_struct_variant_field_outside_struct_variant() {
+        let mut stack = Stack::new();
+
+        stack.map_begin().unwrap();
+
+        assert!(stack.struct_variant_field().is_err());
+    }
 }
@@ -0,0 +1,35 @@
+/**
+A tag that gives a value a well-known or user-defined meaning.
+
+Tags don't change the structure of the value they're attached to.
+A tagged RFC 3339 timestamp is still streamed as a string, but formats
+that understand tags, like CBOR, can use them to write a native tagged value.
+Formats that don't understand tags can ignore them.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tag {
+    /**
+    An RFC 3339 timestamp, like `1985-04-12T23:20:50.52Z`.
+
+    The tagged value is expected to be a string.
+    */
+    Rfc3339,
+    /**
+    A UUID, like `a0a2a0a2-a0a2-a0a2-a0a2-a0a2a0a2a0a2`.
+
+    The tagged value is expected to be a string or 16 bytes.
+    */
+    Uuid,
+    /**
+    A URI, like `https://docs.rs/sval`.
+
+    The tagged value is expected to be a string.
+    */
+    Uri,
+    /**
+    A user-defined tag.
+
+    Streams that don't recognize the name may ignore the tag.
+    */
+    Custom(&'static str),
+}
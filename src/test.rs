@@ -20,6 +20,7 @@ mod std_support {
             string::String,
             vec::Vec,
         },
+        stream::Tag,
         value::{
             owned::Kind,
             OwnedValue,
@@ -36,15 +37,42 @@ mod std_support {
         MapEnd,
         SeqBegin(Option<usize>),
         SeqEnd,
+        StructBegin(&'static str, usize),
+        StructField(u32, &'static str),
+        StructEnd,
+        TupleBegin(Option<&'static str>, usize),
+        TupleEnd,
+        UnitVariant(&'static str, u32, &'static str),
+        NewtypeVariantBegin(&'static str, u32, &'static str),
+        NewtypeVariantEnd,
+        TupleVariantBegin(&'static str, u32, &'static str, usize),
+        TupleVariantEnd,
+        StructVariantBegin(&'static str, u32, &'static str, usize),
+        StructVariantField(u32, &'static str),
+        StructVariantEnd,
+        Signed8(i8),
+        Signed16(i16),
+        Signed32(i32),
         Signed(i64),
+        Unsigned8(u8),
+        Unsigned16(u16),
+        Unsigned32(u32),
         Unsigned(u64),
+        Float32(f32),
         Float(f64),
         BigSigned(i128),
         BigUnsigned(u128),
+        Number(String),
         Bool(bool),
         Str(String),
         Char(char),
+        Bytes(Vec<u8>),
         None,
+        Unit,
+        SomeBegin,
+        SomeEnd,
+        TaggedBegin(Tag),
+        TaggedEnd,
     }
 
     /**
@@ -60,15 +88,52 @@ mod std_support {
                 Kind::MapEnd => Some(Token::MapEnd),
                 Kind::SeqBegin(len) => Some(Token::SeqBegin(len)),
                 Kind::SeqEnd => Some(Token::SeqEnd),
+                Kind::StructBegin(ty, len) => Some(Token::StructBegin(ty, len)),
+                Kind::StructField(index, field) => Some(Token::StructField(index, field)),
+                Kind::StructEnd => Some(Token::StructEnd),
+                Kind::TupleBegin(ty, len) => Some(Token::TupleBegin(ty, len)),
+                Kind::TupleEnd => Some(Token::TupleEnd),
+                Kind::UnitVariant(ty, index, variant) => {
+                    Some(Token::UnitVariant(ty, index, variant))
+                }
+                Kind::NewtypeVariantBegin(ty, index, variant) => {
+                    Some(Token::NewtypeVariantBegin(ty, index, variant))
+                }
+                Kind::NewtypeVariantEnd => Some(Token::NewtypeVariantEnd),
+                Kind::TupleVariantBegin(ty, index, variant, len) => {
+                    Some(Token::TupleVariantBegin(ty, index, variant, len))
+                }
+                Kind::TupleVariantEnd => Some(Token::TupleVariantEnd),
+                Kind::StructVariantBegin(ty, index, variant, len) => {
+                    Some(Token::StructVariantBegin(ty, index, variant, len))
+                }
+                Kind::StructVariantField(index, field) => {
+                    Some(Token::StructVariantField(index, field))
+                }
+                Kind::StructVariantEnd => Some(Token::StructVariantEnd),
+                Kind::Signed8(v) => Some(Token::Signed8(v)),
+                Kind::Signed16(v) => Some(Token::Signed16(v)),
+                Kind::Signed32(v) => Some(Token::Signed32(v)),
                 Kind::Signed(v) => Some(Token::Signed(v)),
+                Kind::Unsigned8(v) => Some(Token::Unsigned8(v)),
+                Kind::Unsigned16(v) => Some(Token::Unsigned16(v)),
+                Kind::Unsigned32(v) => Some(Token::Unsigned32(v)),
                 Kind::Unsigned(v) => Some(Token::Unsigned(v)),
                 Kind::BigSigned(v) => Some(Token::BigSigned(v)),
                 Kind::BigUnsigned(v) => Some(Token::BigUnsigned(v)),
+                Kind::Float32(v) => Some(Token::Float32(v)),
                 Kind::Float(v) => Some(Token::Float(v)),
+                Kind::Number(ref v) => Some(Token::Number((*v).clone())),
                 Kind::Bool(v) => Some(Token::Bool(v)),
                 Kind::Char(v) => Some(Token::Char(v)),
                 Kind::Str(ref v) => Some(Token::Str((*v).clone())),
+                Kind::Bytes(ref v) => Some(Token::Bytes((*v).clone())),
                 Kind::None => Some(Token::None),
+                Kind::Unit => Some(Token::Unit),
+                Kind::SomeBegin => Some(Token::SomeBegin),
+                Kind::SomeEnd => Some(Token::SomeEnd),
+                Kind::TaggedBegin(tag) => Some(Token::TaggedBegin(tag)),
+                Kind::TaggedEnd => Some(Token::TaggedEnd),
                 _ => None,
             })
             .collect()
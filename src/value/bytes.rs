@@ -0,0 +1,81 @@
+/*!
+Byte buffers.
+*/
+
+use crate::value::{
+    Error,
+    Stream,
+    Value,
+};
+
+/**
+A buffer of bytes.
+
+Slices and vectors of `u8` are streamed as sequences of integers.
+Wrap them in `Bytes` to stream them as a single buffer instead:
+
+```
+use sval::value::Bytes;
+
+let bytes = Bytes(&[0xf0, 0x9f, 0x98, 0x8e][..]);
+# let _ = bytes;
+```
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bytes<T: ?Sized>(pub T);
+
+impl<T: ?Sized> Value for Bytes<T>
+where
+    T: AsRef<[u8]>,
+{
+    #[inline]
+    fn stream(&self, stream: &mut Stream) -> Result<(), Error> {
+        stream.bytes(self.0.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "std")]
+    mod std_support {
+        use crate::{
+            std::vec::Vec,
+            test::{
+                self,
+                Token,
+            },
+            value::Bytes,
+        };
+
+        #[test]
+        fn stream_bytes() {
+            assert_eq!(
+                vec![Token::Bytes(vec![1, 2, 3])],
+                test::tokens(Bytes(&[1u8, 2, 3][..]))
+            );
+
+            assert_eq!(
+                vec![Token::Bytes(vec![1, 2, 3])],
+                test::tokens(Bytes(vec![1u8, 2, 3]))
+            );
+
+            assert_eq!(
+                vec![Token::Bytes(Vec::new())],
+                test::tokens(Bytes(Vec::<u8>::new()))
+            );
+        }
+
+        #[test]
+        fn stream_byte_slice_as_seq() {
+            assert_eq!(
+                vec![
+                    Token::SeqBegin(Some(2)),
+                    Token::Unsigned8(1),
+                    Token::Unsigned8(2),
+                    Token::SeqEnd,
+                ],
+                test::tokens(&[1u8, 2][..])
+            );
+        }
+    }
+}
@@ -23,202 +23,565 @@ use crate::{
 
 /**
 An extension to `stream::Stream` for items that are known upfront.
+
+By default, items are streamed using the regular `stream::Stream` methods.
 */
-pub(crate) trait Stream: stream::Stream {
-    fn map_key_collect(&mut self, k: Value) -> Result<(), stream::Error>;
+pub(crate) trait Stream<'v>: stream::Stream {
+    /**
+    Stream a string that's borrowed for the lifetime of the value.
+    */
+    #[inline]
+    fn str_borrowed(&mut self, v: &'v str) -> Result<(), stream::Error> {
+        self.str(v)
+    }
 
-    fn map_value_collect(&mut self, v: Value) -> Result<(), stream::Error>;
+    /**
+    Whether the stream accepts borrowed strings.
 
-    fn seq_elem_collect(&mut self, v: Value) -> Result<(), stream::Error>;
-}
+    Streams that don't can receive borrowed values through
+    the `*_collect` methods instead.
+    */
+    #[inline]
+    fn is_borrowed(&self) -> bool {
+        false
+    }
 
-impl<'a, S: ?Sized> Stream for &'a mut S
-where
-    S: Stream,
-{
     #[inline]
     fn map_key_collect(&mut self, k: Value) -> Result<(), stream::Error> {
-        (**self).map_key_collect(k)
+        stream::Stream::map_key(self)?;
+        k.stream(self)?;
+
+        Ok(())
     }
 
     #[inline]
     fn map_value_collect(&mut self, v: Value) -> Result<(), stream::Error> {
-        (**self).map_value_collect(v)
+        stream::Stream::map_value(self)?;
+        v.stream(self)?;
+
+        Ok(())
     }
 
     #[inline]
     fn seq_elem_collect(&mut self, v: Value) -> Result<(), stream::Error> {
-        (**self).seq_elem_collect(v)
-    }
-}
+        stream::Stream::seq_elem(self)?;
+        v.stream(self)?;
 
-/**
-A value that's known upfront.
-*/
-pub(crate) struct Value<'a> {
-    stack: DebugStack<'a>,
-    value: &'a dyn value::Value,
-}
+        Ok(())
+    }
 
-impl<'a> Value<'a> {
     #[inline]
-    pub(super) fn new(stack: value::stream::DebugStack<'a>, value: &'a impl value::Value) -> Self {
-        Value {
-            stack: DebugStack::new(stack),
-            value,
-        }
-    }
+    fn struct_field_collect(
+        &mut self,
+        index: u32,
+        field: &'static str,
+        v: Value,
+    ) -> Result<(), stream::Error> {
+        stream::Stream::struct_field(self, index, field)?;
+        v.stream(self)?;
 
-    /**
-    Stream this value.
+        Ok(())
+    }
 
-    The value may only be streamed once.
-    Subsequent calls to `stream` may fail.
-    */
     #[inline]
-    pub(crate) fn stream(&self, mut stream: impl Stream) -> Result<(), Error> {
-        let mut stream = value::Stream::new(self.stack.take()?, &mut stream);
+    fn tuple_elem_collect(&mut self, index: u32, v: Value) -> Result<(), stream::Error> {
+        stream::Stream::tuple_elem(self, index)?;
+        v.stream(self)?;
 
-        stream.any(&self.value)?;
+        Ok(())
+    }
+
+    #[inline]
+    fn newtype_variant_collect(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+        v: Value,
+    ) -> Result<(), stream::Error> {
+        stream::Stream::newtype_variant_begin(self, ty, index, variant)?;
+        v.stream(&mut *self)?;
+        stream::Stream::newtype_variant_end(self)?;
 
         Ok(())
     }
-}
 
-/**
-Default implementations for stream extensions.
-*/
-pub(crate) struct Default<S>(pub(crate) S);
+    #[inline]
+    fn some_collect(&mut self, v: Value) -> Result<(), stream::Error> {
+        stream::Stream::some_begin(self)?;
+        v.stream(&mut *self)?;
+        stream::Stream::some_end(self)?;
+
+        Ok(())
+    }
 
-impl<S> Stream for Default<S>
-where
-    S: stream::Stream,
-{
     #[inline]
-    fn map_key_collect(&mut self, k: Value) -> Result<(), stream::Error> {
-        stream::Stream::map_key(self)?;
-        k.stream(self)?;
+    fn tagged_collect(&mut self, tag: stream::Tag, v: Value) -> Result<(), stream::Error> {
+        stream::Stream::tagged_begin(self, tag)?;
+        v.stream(&mut *self)?;
+        stream::Stream::tagged_end(self)?;
 
         Ok(())
     }
 
     #[inline]
-    fn map_value_collect(&mut self, v: Value) -> Result<(), stream::Error> {
-        stream::Stream::map_value(self)?;
+    fn tuple_variant_elem_collect(&mut self, index: u32, v: Value) -> Result<(), stream::Error> {
+        stream::Stream::tuple_variant_elem(self, index)?;
         v.stream(self)?;
 
         Ok(())
     }
 
     #[inline]
-    fn seq_elem_collect(&mut self, v: Value) -> Result<(), stream::Error> {
-        stream::Stream::seq_elem(self)?;
+    fn struct_variant_field_collect(
+        &mut self,
+        index: u32,
+        field: &'static str,
+        v: Value,
+    ) -> Result<(), stream::Error> {
+        stream::Stream::struct_variant_field(self, index, field)?;
         v.stream(self)?;
 
         Ok(())
     }
 }
 
-impl<S> stream::Stream for Default<S>
+impl<'a, 'v, S: ?Sized> Stream<'v> for &'a mut S
 where
-    S: stream::Stream,
+    S: Stream<'v>,
 {
     #[inline]
-    fn begin(&mut self) -> Result<(), stream::Error> {
-        self.0.begin()
+    fn str_borrowed(&mut self, v: &'v str) -> Result<(), stream::Error> {
+        (**self).str_borrowed(v)
     }
 
     #[inline]
-    fn fmt(&mut self, args: stream::Arguments) -> Result<(), stream::Error> {
-        self.0.fmt(args)
+    fn is_borrowed(&self) -> bool {
+        (**self).is_borrowed()
     }
 
     #[inline]
-    fn i64(&mut self, v: i64) -> Result<(), stream::Error> {
-        self.0.i64(v)
+    fn map_key_collect(&mut self, k: Value) -> Result<(), stream::Error> {
+        (**self).map_key_collect(k)
     }
 
     #[inline]
-    fn u64(&mut self, v: u64) -> Result<(), stream::Error> {
-        self.0.u64(v)
+    fn map_value_collect(&mut self, v: Value) -> Result<(), stream::Error> {
+        (**self).map_value_collect(v)
     }
 
     #[inline]
-    fn i128(&mut self, v: i128) -> Result<(), stream::Error> {
-        self.0.i128(v)
+    fn seq_elem_collect(&mut self, v: Value) -> Result<(), stream::Error> {
+        (**self).seq_elem_collect(v)
     }
 
     #[inline]
-    fn u128(&mut self, v: u128) -> Result<(), stream::Error> {
-        self.0.u128(v)
+    fn some_collect(&mut self, v: Value) -> Result<(), stream::Error> {
+        (**self).some_collect(v)
     }
 
     #[inline]
-    fn f64(&mut self, v: f64) -> Result<(), stream::Error> {
-        self.0.f64(v)
+    fn tagged_collect(&mut self, tag: stream::Tag, v: Value) -> Result<(), stream::Error> {
+        (**self).tagged_collect(tag, v)
     }
 
     #[inline]
-    fn bool(&mut self, v: bool) -> Result<(), stream::Error> {
-        self.0.bool(v)
+    fn struct_field_collect(
+        &mut self,
+        index: u32,
+        field: &'static str,
+        v: Value,
+    ) -> Result<(), stream::Error> {
+        (**self).struct_field_collect(index, field, v)
     }
 
     #[inline]
-    fn char(&mut self, v: char) -> Result<(), stream::Error> {
-        self.0.char(v)
+    fn tuple_elem_collect(&mut self, index: u32, v: Value) -> Result<(), stream::Error> {
+        (**self).tuple_elem_collect(index, v)
     }
 
     #[inline]
-    fn str(&mut self, v: &str) -> Result<(), stream::Error> {
-        self.0.str(v)
+    fn newtype_variant_collect(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+        v: Value,
+    ) -> Result<(), stream::Error> {
+        (**self).newtype_variant_collect(ty, index, variant, v)
     }
 
     #[inline]
-    fn none(&mut self) -> Result<(), stream::Error> {
-        self.0.none()
+    fn tuple_variant_elem_collect(&mut self, index: u32, v: Value) -> Result<(), stream::Error> {
+        (**self).tuple_variant_elem_collect(index, v)
     }
 
     #[inline]
-    fn map_begin(&mut self, len: Option<usize>) -> Result<(), stream::Error> {
-        self.0.map_begin(len)
+    fn struct_variant_field_collect(
+        &mut self,
+        index: u32,
+        field: &'static str,
+        v: Value,
+    ) -> Result<(), stream::Error> {
+        (**self).struct_variant_field_collect(index, field, v)
     }
+}
 
-    #[inline]
-    fn map_key(&mut self) -> Result<(), stream::Error> {
-        self.0.map_key()
-    }
+/**
+A value that's known upfront.
+*/
+pub(crate) struct Value<'a> {
+    stack: DebugStack<'a>,
+    value: &'a dyn value::Value,
+}
 
+impl<'a> Value<'a> {
     #[inline]
-    fn map_value(&mut self) -> Result<(), stream::Error> {
-        self.0.map_value()
+    pub(super) fn new(stack: value::stream::DebugStack<'a>, value: &'a impl value::Value) -> Self {
+        Value {
+            stack: DebugStack::new(stack),
+            value,
+        }
     }
 
-    #[inline]
-    fn map_end(&mut self) -> Result<(), stream::Error> {
-        self.0.map_end()
-    }
+    /**
+    Stream this value.
 
+    The value may only be streamed once.
+    Subsequent calls to `stream` may fail.
+    */
     #[inline]
-    fn seq_begin(&mut self, len: Option<usize>) -> Result<(), stream::Error> {
-        self.0.seq_begin(len)
-    }
+    pub(crate) fn stream<'v>(&self, mut stream: impl Stream<'v>) -> Result<(), Error> {
+        let mut stream = value::Stream::new(self.stack.take()?, &mut stream);
 
-    #[inline]
-    fn seq_elem(&mut self) -> Result<(), stream::Error> {
-        self.0.seq_elem()
+        self.value.stream(&mut stream)?;
+
+        Ok(())
     }
+}
+
+/**
+Forward the methods of `stream::Stream` to the stream in the first field.
+*/
+macro_rules! forward_stream {
+    () => {
+        #[inline]
+        fn begin(&mut self) -> Result<(), stream::Error> {
+            self.0.begin()
+        }
+
+        #[inline]
+        fn fmt(&mut self, args: stream::Arguments) -> Result<(), stream::Error> {
+            self.0.fmt(args)
+        }
 
+        #[inline]
+        fn i8(&mut self, v: i8) -> Result<(), stream::Error> {
+            self.0.i8(v)
+        }
+
+        #[inline]
+        fn i16(&mut self, v: i16) -> Result<(), stream::Error> {
+            self.0.i16(v)
+        }
+
+        #[inline]
+        fn i32(&mut self, v: i32) -> Result<(), stream::Error> {
+            self.0.i32(v)
+        }
+
+        #[inline]
+        fn u8(&mut self, v: u8) -> Result<(), stream::Error> {
+            self.0.u8(v)
+        }
+
+        #[inline]
+        fn u16(&mut self, v: u16) -> Result<(), stream::Error> {
+            self.0.u16(v)
+        }
+
+        #[inline]
+        fn u32(&mut self, v: u32) -> Result<(), stream::Error> {
+            self.0.u32(v)
+        }
+
+        #[inline]
+        fn f32(&mut self, v: f32) -> Result<(), stream::Error> {
+            self.0.f32(v)
+        }
+
+        #[inline]
+        fn i64(&mut self, v: i64) -> Result<(), stream::Error> {
+            self.0.i64(v)
+        }
+
+        #[inline]
+        fn u64(&mut self, v: u64) -> Result<(), stream::Error> {
+            self.0.u64(v)
+        }
+
+        #[inline]
+        fn i128(&mut self, v: i128) -> Result<(), stream::Error> {
+            self.0.i128(v)
+        }
+
+        #[inline]
+        fn u128(&mut self, v: u128) -> Result<(), stream::Error> {
+            self.0.u128(v)
+        }
+
+        #[inline]
+        fn f64(&mut self, v: f64) -> Result<(), stream::Error> {
+            self.0.f64(v)
+        }
+
+        #[inline]
+        fn number(&mut self, v: &str) -> Result<(), stream::Error> {
+            self.0.number(v)
+        }
+
+        #[inline]
+        fn bool(&mut self, v: bool) -> Result<(), stream::Error> {
+            self.0.bool(v)
+        }
+
+        #[inline]
+        fn char(&mut self, v: char) -> Result<(), stream::Error> {
+            self.0.char(v)
+        }
+
+        #[inline]
+        fn str(&mut self, v: &str) -> Result<(), stream::Error> {
+            self.0.str(v)
+        }
+
+        #[inline]
+        fn bytes(&mut self, v: &[u8]) -> Result<(), stream::Error> {
+            self.0.bytes(v)
+        }
+
+        #[inline]
+        fn none(&mut self) -> Result<(), stream::Error> {
+            self.0.none()
+        }
+
+        #[inline]
+        fn unit(&mut self) -> Result<(), stream::Error> {
+            self.0.unit()
+        }
+
+        #[inline]
+        fn some_begin(&mut self) -> Result<(), stream::Error> {
+            self.0.some_begin()
+        }
+
+        #[inline]
+        fn some_end(&mut self) -> Result<(), stream::Error> {
+            self.0.some_end()
+        }
+
+        #[inline]
+        fn tagged_begin(&mut self, tag: stream::Tag) -> Result<(), stream::Error> {
+            self.0.tagged_begin(tag)
+        }
+
+        #[inline]
+        fn tagged_end(&mut self) -> Result<(), stream::Error> {
+            self.0.tagged_end()
+        }
+
+        #[inline]
+        fn map_begin(&mut self, len: Option<usize>) -> Result<(), stream::Error> {
+            self.0.map_begin(len)
+        }
+
+        #[inline]
+        fn map_key(&mut self) -> Result<(), stream::Error> {
+            self.0.map_key()
+        }
+
+        #[inline]
+        fn map_value(&mut self) -> Result<(), stream::Error> {
+            self.0.map_value()
+        }
+
+        #[inline]
+        fn map_end(&mut self) -> Result<(), stream::Error> {
+            self.0.map_end()
+        }
+
+        #[inline]
+        fn seq_begin(&mut self, len: Option<usize>) -> Result<(), stream::Error> {
+            self.0.seq_begin(len)
+        }
+
+        #[inline]
+        fn seq_elem(&mut self) -> Result<(), stream::Error> {
+            self.0.seq_elem()
+        }
+
+        #[inline]
+        fn seq_end(&mut self) -> Result<(), stream::Error> {
+            self.0.seq_end()
+        }
+
+        #[inline]
+        fn struct_begin(&mut self, ty: &'static str, len: usize) -> Result<(), stream::Error> {
+            self.0.struct_begin(ty, len)
+        }
+
+        #[inline]
+        fn struct_field(&mut self, index: u32, field: &'static str) -> Result<(), stream::Error> {
+            self.0.struct_field(index, field)
+        }
+
+        #[inline]
+        fn struct_end(&mut self) -> Result<(), stream::Error> {
+            self.0.struct_end()
+        }
+
+        #[inline]
+        fn tuple_begin(
+            &mut self,
+            ty: Option<&'static str>,
+            len: usize,
+        ) -> Result<(), stream::Error> {
+            self.0.tuple_begin(ty, len)
+        }
+
+        #[inline]
+        fn tuple_elem(&mut self, index: u32) -> Result<(), stream::Error> {
+            self.0.tuple_elem(index)
+        }
+
+        #[inline]
+        fn tuple_end(&mut self) -> Result<(), stream::Error> {
+            self.0.tuple_end()
+        }
+
+        #[inline]
+        fn unit_variant(
+            &mut self,
+            ty: &'static str,
+            index: u32,
+            variant: &'static str,
+        ) -> Result<(), stream::Error> {
+            self.0.unit_variant(ty, index, variant)
+        }
+
+        #[inline]
+        fn newtype_variant_begin(
+            &mut self,
+            ty: &'static str,
+            index: u32,
+            variant: &'static str,
+        ) -> Result<(), stream::Error> {
+            self.0.newtype_variant_begin(ty, index, variant)
+        }
+
+        #[inline]
+        fn newtype_variant_end(&mut self) -> Result<(), stream::Error> {
+            self.0.newtype_variant_end()
+        }
+
+        #[inline]
+        fn tuple_variant_begin(
+            &mut self,
+            ty: &'static str,
+            index: u32,
+            variant: &'static str,
+            len: usize,
+        ) -> Result<(), stream::Error> {
+            self.0.tuple_variant_begin(ty, index, variant, len)
+        }
+
+        #[inline]
+        fn tuple_variant_elem(&mut self, index: u32) -> Result<(), stream::Error> {
+            self.0.tuple_variant_elem(index)
+        }
+
+        #[inline]
+        fn tuple_variant_end(&mut self) -> Result<(), stream::Error> {
+            self.0.tuple_variant_end()
+        }
+
+        #[inline]
+        fn struct_variant_begin(
+            &mut self,
+            ty: &'static str,
+            index: u32,
+            variant: &'static str,
+            len: usize,
+        ) -> Result<(), stream::Error> {
+            self.0.struct_variant_begin(ty, index, variant, len)
+        }
+
+        #[inline]
+        fn struct_variant_field(
+            &mut self,
+            index: u32,
+            field: &'static str,
+        ) -> Result<(), stream::Error> {
+            self.0.struct_variant_field(index, field)
+        }
+
+        #[inline]
+        fn struct_variant_end(&mut self) -> Result<(), stream::Error> {
+            self.0.struct_variant_end()
+        }
+
+        #[inline]
+        fn end(&mut self) -> Result<(), stream::Error> {
+            self.0.end()
+        }
+    };
+}
+
+/**
+Default implementations for stream extensions.
+*/
+pub(crate) struct Default<S>(pub(crate) S);
+
+impl<'v, S> Stream<'v> for Default<S>
+where
+    S: stream::Stream,
+{
+}
+
+impl<S> stream::Stream for Default<S>
+where
+    S: stream::Stream,
+{
+    forward_stream!();
+}
+
+/**
+Implementations for streams that accept borrowed strings.
+*/
+pub(crate) struct Borrowed<S>(pub(crate) S);
+
+impl<'v, S> Stream<'v> for Borrowed<S>
+where
+    S: stream::BorrowedStream<'v>,
+{
     #[inline]
-    fn seq_end(&mut self) -> Result<(), stream::Error> {
-        self.0.seq_end()
+    fn str_borrowed(&mut self, v: &'v str) -> Result<(), stream::Error> {
+        self.0.str_borrowed(v)
     }
 
     #[inline]
-    fn end(&mut self) -> Result<(), stream::Error> {
-        self.0.end()
+    fn is_borrowed(&self) -> bool {
+        true
     }
 }
 
+impl<S> stream::Stream for Borrowed<S>
+where
+    S: stream::Stream,
+{
+    forward_stream!();
+}
+
 struct DebugStack<'a> {
     #[cfg(debug_assertions)]
     stack: crate::std::cell::Cell<Option<value::stream::DebugStack<'a>>>,
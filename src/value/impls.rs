@@ -10,7 +10,7 @@ use crate::{
 impl Value for () {
     #[inline]
     fn stream(&self, stream: &mut Stream) -> Result<(), Error> {
-        stream.none()
+        stream.unit()
     }
 }
 
@@ -21,7 +21,15 @@ where
     #[inline]
     fn stream(&self, stream: &mut Stream) -> Result<(), Error> {
         match self {
-            Some(v) => v.stream(stream),
+            Some(v) => stream.some(v),
+            None => stream.none(),
+        }
+    }
+
+    #[inline]
+    fn stream_borrowed<'v>(&'v self, stream: &mut Stream<'_, 'v>) -> Result<(), Error> {
+        match self {
+            Some(v) => stream.some_borrowed(v),
             None => stream.none(),
         }
     }
@@ -41,6 +49,17 @@ where
 
         stream.seq_end()
     }
+
+    #[inline]
+    fn stream_borrowed<'v>(&'v self, stream: &mut Stream<'_, 'v>) -> Result<(), Error> {
+        stream.seq_begin(Some(self.len()))?;
+
+        for v in self {
+            stream.seq_elem_borrowed(v)?;
+        }
+
+        stream.seq_end()
+    }
 }
 
 impl<T, U> Value for (T, U)
@@ -50,33 +69,43 @@ where
 {
     #[inline]
     fn stream(&self, stream: &mut Stream) -> Result<(), Error> {
-        stream.seq_begin(Some(2))?;
+        stream.tuple_begin(None, 2)?;
 
-        stream.seq_elem(&self.0)?;
-        stream.seq_elem(&self.1)?;
+        stream.tuple_elem(0, &self.0)?;
+        stream.tuple_elem(1, &self.1)?;
 
-        stream.seq_end()
+        stream.tuple_end()
+    }
+
+    #[inline]
+    fn stream_borrowed<'v>(&'v self, stream: &mut Stream<'_, 'v>) -> Result<(), Error> {
+        stream.tuple_begin(None, 2)?;
+
+        stream.tuple_elem_borrowed(0, &self.0)?;
+        stream.tuple_elem_borrowed(1, &self.1)?;
+
+        stream.tuple_end()
     }
 }
 
 impl Value for u8 {
     #[inline]
     fn stream(&self, stream: &mut Stream) -> Result<(), Error> {
-        stream.u64(u64::from(*self))
+        stream.u8(*self)
     }
 }
 
 impl Value for u16 {
     #[inline]
     fn stream(&self, stream: &mut Stream) -> Result<(), Error> {
-        stream.u64(u64::from(*self))
+        stream.u16(*self)
     }
 }
 
 impl Value for u32 {
     #[inline]
     fn stream(&self, stream: &mut Stream) -> Result<(), Error> {
-        stream.u64(u64::from(*self))
+        stream.u32(*self)
     }
 }
 
@@ -90,21 +119,21 @@ impl Value for u64 {
 impl Value for i8 {
     #[inline]
     fn stream(&self, stream: &mut Stream) -> Result<(), Error> {
-        stream.i64(i64::from(*self))
+        stream.i8(*self)
     }
 }
 
 impl Value for i16 {
     #[inline]
     fn stream(&self, stream: &mut Stream) -> Result<(), Error> {
-        stream.i64(i64::from(*self))
+        stream.i16(*self)
     }
 }
 
 impl Value for i32 {
     #[inline]
     fn stream(&self, stream: &mut Stream) -> Result<(), Error> {
-        stream.i64(i64::from(*self))
+        stream.i32(*self)
     }
 }
 
@@ -132,7 +161,7 @@ impl Value for i128 {
 impl Value for f32 {
     #[inline]
     fn stream(&self, stream: &mut Stream) -> Result<(), Error> {
-        stream.f64(f64::from(*self))
+        stream.f32(*self)
     }
 }
 
@@ -162,6 +191,11 @@ impl Value for str {
     fn stream(&self, stream: &mut Stream) -> Result<(), Error> {
         stream.str(self)
     }
+
+    #[inline]
+    fn stream_borrowed<'v>(&'v self, stream: &mut Stream<'_, 'v>) -> Result<(), Error> {
+        stream.str_borrowed(self)
+    }
 }
 
 impl<'a> Value for fmt::Arguments<'a> {
@@ -199,6 +233,11 @@ mod std_support {
         fn stream(&self, stream: &mut Stream) -> Result<(), Error> {
             (**self).stream(stream)
         }
+
+        #[inline]
+        fn stream_borrowed<'v>(&'v self, stream: &mut Stream<'_, 'v>) -> Result<(), Error> {
+            (**self).stream_borrowed(stream)
+        }
     }
 
     // FIXME: It'd be a shame not to optimize `Value::to_owned` for `Arc`
@@ -210,6 +249,11 @@ mod std_support {
         fn stream(&self, stream: &mut Stream) -> Result<(), Error> {
             (**self).stream(stream)
         }
+
+        #[inline]
+        fn stream_borrowed<'v>(&'v self, stream: &mut Stream<'_, 'v>) -> Result<(), Error> {
+            (**self).stream_borrowed(stream)
+        }
     }
 
     impl<T: ?Sized> Value for Rc<T>
@@ -220,12 +264,22 @@ mod std_support {
         fn stream(&self, stream: &mut Stream) -> Result<(), Error> {
             (**self).stream(stream)
         }
+
+        #[inline]
+        fn stream_borrowed<'v>(&'v self, stream: &mut Stream<'_, 'v>) -> Result<(), Error> {
+            (**self).stream_borrowed(stream)
+        }
     }
 
     impl Value for String {
         #[inline]
         fn stream(&self, stream: &mut Stream) -> Result<(), Error> {
-            stream.str(&*self)
+            stream.str(self)
+        }
+
+        #[inline]
+        fn stream_borrowed<'v>(&'v self, stream: &mut Stream<'_, 'v>) -> Result<(), Error> {
+            stream.str_borrowed(self)
         }
     }
 
@@ -237,6 +291,11 @@ mod std_support {
         fn stream(&self, stream: &mut Stream) -> Result<(), Error> {
             self.as_slice().stream(stream)
         }
+
+        #[inline]
+        fn stream_borrowed<'v>(&'v self, stream: &mut Stream<'_, 'v>) -> Result<(), Error> {
+            self.as_slice().stream_borrowed(stream)
+        }
     }
 
     impl<K, V> Value for BTreeMap<K, V>
@@ -255,6 +314,18 @@ mod std_support {
 
             stream.map_end()
         }
+
+        #[inline]
+        fn stream_borrowed<'v>(&'v self, stream: &mut Stream<'_, 'v>) -> Result<(), Error> {
+            stream.map_begin(Some(self.len()))?;
+
+            for (k, v) in self {
+                stream.map_key_borrowed(k)?;
+                stream.map_value_borrowed(v)?;
+            }
+
+            stream.map_end()
+        }
     }
 
     impl<K, V, H> Value for HashMap<K, V, H>
@@ -274,6 +345,60 @@ mod std_support {
 
             stream.map_end()
         }
+
+        #[inline]
+        fn stream_borrowed<'v>(&'v self, stream: &mut Stream<'_, 'v>) -> Result<(), Error> {
+            stream.map_begin(Some(self.len()))?;
+
+            for (k, v) in self {
+                stream.map_key_borrowed(k)?;
+                stream.map_value_borrowed(v)?;
+            }
+
+            stream.map_end()
+        }
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+mod rust_decimal_support {
+    use super::*;
+
+    use crate::std::string::ToString;
+
+    use rust_decimal_lib::Decimal;
+
+    impl Value for Decimal {
+        #[inline]
+        fn stream(&self, stream: &mut Stream) -> Result<(), Error> {
+            stream.number(&self.to_string())
+        }
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+mod num_bigint_support {
+    use super::*;
+
+    use crate::std::string::ToString;
+
+    use num_bigint_lib::{
+        BigInt,
+        BigUint,
+    };
+
+    impl Value for BigInt {
+        #[inline]
+        fn stream(&self, stream: &mut Stream) -> Result<(), Error> {
+            stream.number(&self.to_string())
+        }
+    }
+
+    impl Value for BigUint {
+        #[inline]
+        fn stream(&self, stream: &mut Stream) -> Result<(), Error> {
+            stream.number(&self.to_string())
+        }
     }
 }
 
@@ -301,11 +426,11 @@ mod tests {
 
         #[test]
         fn stream_unsigned() {
-            assert_eq!(vec![Token::Unsigned(1)], test::tokens(1u8));
+            assert_eq!(vec![Token::Unsigned8(1)], test::tokens(1u8));
 
-            assert_eq!(vec![Token::Unsigned(1)], test::tokens(1u16));
+            assert_eq!(vec![Token::Unsigned16(1)], test::tokens(1u16));
 
-            assert_eq!(vec![Token::Unsigned(1)], test::tokens(1u32));
+            assert_eq!(vec![Token::Unsigned32(1)], test::tokens(1u32));
 
             assert_eq!(vec![Token::Unsigned(1)], test::tokens(1u64));
 
@@ -314,11 +439,11 @@ mod tests {
 
         #[test]
         fn stream_signed() {
-            assert_eq!(vec![Token::Signed(1)], test::tokens(1i8));
+            assert_eq!(vec![Token::Signed8(1)], test::tokens(1i8));
 
-            assert_eq!(vec![Token::Signed(1)], test::tokens(1i16));
+            assert_eq!(vec![Token::Signed16(1)], test::tokens(1i16));
 
-            assert_eq!(vec![Token::Signed(1)], test::tokens(1i32));
+            assert_eq!(vec![Token::Signed32(1)], test::tokens(1i32));
 
             assert_eq!(vec![Token::Signed(1)], test::tokens(1i64));
 
@@ -327,7 +452,7 @@ mod tests {
 
         #[test]
         fn stream_float() {
-            assert_eq!(vec![Token::Float(1.0)], test::tokens(1f32));
+            assert_eq!(vec![Token::Float32(1.0)], test::tokens(1f32));
 
             assert_eq!(vec![Token::Float(1.0)], test::tokens(1f64));
         }
@@ -361,7 +486,15 @@ mod tests {
         fn stream_option() {
             assert_eq!(vec![Token::None], test::tokens(Option::None::<i32>));
 
-            assert_eq!(vec![Token::Signed(1)], test::tokens(Some(1)));
+            assert_eq!(
+                vec![Token::SomeBegin, Token::Signed32(1), Token::SomeEnd],
+                test::tokens(Some(1))
+            );
+        }
+
+        #[test]
+        fn stream_unit() {
+            assert_eq!(vec![Token::Unit], test::tokens(()));
         }
 
         #[test]
@@ -373,9 +506,9 @@ mod tests {
             assert_eq!(
                 vec![
                     Token::SeqBegin(Some(3)),
-                    Token::Signed(1),
-                    Token::Signed(2),
-                    Token::Signed(3),
+                    Token::Signed32(1),
+                    Token::Signed32(2),
+                    Token::Signed32(3),
                     Token::SeqEnd,
                 ],
                 v
@@ -388,9 +521,9 @@ mod tests {
             assert_eq!(
                 vec![
                     Token::SeqBegin(Some(3)),
-                    Token::Signed(1),
-                    Token::Signed(2),
-                    Token::Signed(3),
+                    Token::Signed32(1),
+                    Token::Signed32(2),
+                    Token::Signed32(3),
                     Token::SeqEnd,
                 ],
                 v
@@ -414,10 +547,10 @@ mod tests {
             assert_eq!(
                 vec![
                     Token::MapBegin(Some(2)),
-                    Token::Signed(1),
-                    Token::Signed(11),
-                    Token::Signed(2),
-                    Token::Signed(22),
+                    Token::Signed32(1),
+                    Token::Signed32(11),
+                    Token::Signed32(2),
+                    Token::Signed32(22),
                     Token::MapEnd,
                 ],
                 v
@@ -426,14 +559,59 @@ mod tests {
 
         #[test]
         fn stream_box() {
-            assert_eq!(vec![Token::Signed(1)], test::tokens(Box::new(1i8)));
+            assert_eq!(vec![Token::Signed8(1)], test::tokens(Box::new(1i8)));
         }
 
         #[test]
         fn stream_rc() {
-            assert_eq!(vec![Token::Signed(1)], test::tokens(Rc::new(1i8)));
+            assert_eq!(vec![Token::Signed8(1)], test::tokens(Rc::new(1i8)));
+
+            assert_eq!(vec![Token::Signed8(1)], test::tokens(Arc::new(1i8)));
+        }
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    mod rust_decimal_support {
+        use crate::test::{
+            self,
+            Token,
+        };
+
+        use rust_decimal_lib::Decimal;
 
-            assert_eq!(vec![Token::Signed(1)], test::tokens(Arc::new(1i8)));
+        #[test]
+        fn stream_decimal() {
+            assert_eq!(
+                vec![Token::Number("-12345678901234567890.123".into())],
+                test::tokens("-12345678901234567890.123".parse::<Decimal>().unwrap())
+            );
+        }
+    }
+
+    #[cfg(feature = "num-bigint")]
+    mod num_bigint_support {
+        use crate::test::{
+            self,
+            Token,
+        };
+
+        use num_bigint_lib::{
+            BigInt,
+            BigUint,
+        };
+
+        #[test]
+        fn stream_bigint() {
+            assert_eq!(
+                vec![Token::Number("-1".into())],
+                test::tokens(BigInt::from(-1))
+            );
+
+            let v = "340282366920938463463374607431768211456";
+            assert_eq!(
+                vec![Token::Number(v.into())],
+                test::tokens(v.parse::<BigUint>().unwrap())
+            );
         }
     }
 }
@@ -4,6 +4,7 @@ A streamable value.
 
 #[macro_use]
 mod macros;
+mod bytes;
 mod impls;
 mod stream;
 
@@ -12,9 +13,15 @@ pub(crate) mod collect;
 #[cfg(feature = "std")]
 pub(crate) mod owned;
 
-pub(crate) use self::stream::stream;
+pub(crate) use self::stream::{
+    stream,
+    stream_borrowed,
+};
 
-pub use self::stream::Stream;
+pub use self::{
+    bytes::Bytes,
+    stream::Stream,
+};
 
 #[cfg(feature = "std")]
 pub use self::owned::OwnedValue;
@@ -242,10 +249,52 @@ impl Value for MyValue {
     }
 }
 ```
+
+# Borrowing strings
+
+Streams that accept borrowed strings can keep them without copying.
+A value can lend its strings to those streams by also implementing
+`stream_borrowed`. The `'v` lifetime on the stream it's given is
+the lifetime of the value, so strings that live for `'v` can be
+streamed using `str_borrowed`:
+
+```
+# use sval::value::{self, Value};
+struct MyValue {
+    id: String,
+}
+
+impl Value for MyValue {
+    fn stream(&self, stream: &mut value::Stream) -> Result<(), value::Error> {
+        stream.str(&self.id)
+    }
+
+    fn stream_borrowed<'v>(
+        &'v self,
+        stream: &mut value::Stream<'_, 'v>,
+    ) -> Result<(), value::Error> {
+        stream.str_borrowed(&self.id)
+    }
+}
+```
+
+Nested values that are borrowed from `self` can be lent in the same way
+using `any_borrowed`, `map_key_borrowed`, `map_value_borrowed` and `seq_elem_borrowed`.
 */
 pub trait Value {
     /** Stream this value. */
     fn stream(&self, stream: &mut Stream) -> Result<(), Error>;
+
+    /**
+    Stream this value, lending any strings that live as long as it does.
+
+    Both methods should stream the same structure.
+    By default, this calls `stream`, so no strings are lent.
+    */
+    #[inline]
+    fn stream_borrowed<'v>(&'v self, stream: &mut Stream<'_, 'v>) -> Result<(), Error> {
+        self.stream(stream)
+    }
 }
 
 impl<'a, T: ?Sized> Value for &'a T
@@ -256,6 +305,11 @@ where
     fn stream(&self, stream: &mut Stream) -> Result<(), Error> {
         (**self).stream(stream)
     }
+
+    #[inline]
+    fn stream_borrowed<'v>(&'v self, stream: &mut Stream<'_, 'v>) -> Result<(), Error> {
+        (**self).stream_borrowed(stream)
+    }
 }
 
 #[cfg(test)]
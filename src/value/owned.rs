@@ -82,15 +82,33 @@ impl Value for OwnedValue {
             ValueInner::Stream(ref v) => {
                 for token in v.iter() {
                     match token.kind {
+                        Signed8(v) => stream.i8(v)?,
+                        Signed16(v) => stream.i16(v)?,
+                        Signed32(v) => stream.i32(v)?,
                         Signed(v) => stream.i64(v)?,
+                        Unsigned8(v) => stream.u8(v)?,
+                        Unsigned16(v) => stream.u16(v)?,
+                        Unsigned32(v) => stream.u32(v)?,
                         Unsigned(v) => stream.u64(v)?,
+                        Float32(v) => stream.f32(v)?,
                         Float(v) => stream.f64(v)?,
                         BigSigned(v) => stream.i128(v)?,
                         BigUnsigned(v) => stream.u128(v)?,
+                        Number(ref v) => stream.number(v)?,
                         Bool(v) => stream.bool(v)?,
                         Str(ref v) => stream.str(&*v)?,
                         Char(v) => stream.char(v)?,
+                        Bytes(ref v) => stream.bytes(v)?,
                         None => stream.none()?,
+                        Unit => stream.unit()?,
+                        SomeBegin => {
+                            stream.some_begin()?;
+                        }
+                        SomeEnd => stream.some_end()?,
+                        TaggedBegin(tag) => {
+                            stream.tagged_begin(tag)?;
+                        }
+                        TaggedEnd => stream.tagged_end()?,
                         MapBegin(len) => stream.map_begin(len)?,
                         MapKey => {
                             stream.map_key_begin()?;
@@ -104,6 +122,37 @@ impl Value for OwnedValue {
                             stream.seq_elem_begin()?;
                         }
                         SeqEnd => stream.seq_end()?,
+                        StructBegin(ty, len) => stream.struct_begin(ty, len)?,
+                        StructField(index, field) => {
+                            stream.struct_field_begin(index, field)?;
+                        }
+                        StructEnd => stream.struct_end()?,
+                        TupleBegin(ty, len) => stream.tuple_begin(ty, len)?,
+                        TupleElem(index) => {
+                            stream.tuple_elem_begin(index)?;
+                        }
+                        TupleEnd => stream.tuple_end()?,
+                        UnitVariant(ty, index, variant) => {
+                            stream.unit_variant(ty, index, variant)?
+                        }
+                        NewtypeVariantBegin(ty, index, variant) => {
+                            stream.newtype_variant_begin(ty, index, variant)?;
+                        }
+                        NewtypeVariantEnd => stream.newtype_variant_end()?,
+                        TupleVariantBegin(ty, index, variant, len) => {
+                            stream.tuple_variant_begin(ty, index, variant, len)?
+                        }
+                        TupleVariantElem(index) => {
+                            stream.tuple_variant_elem_begin(index)?;
+                        }
+                        TupleVariantEnd => stream.tuple_variant_end()?,
+                        StructVariantBegin(ty, index, variant, len) => {
+                            stream.struct_variant_begin(ty, index, variant, len)?
+                        }
+                        StructVariantField(index, field) => {
+                            stream.struct_variant_field_begin(index, field)?;
+                        }
+                        StructVariantEnd => stream.struct_variant_end()?,
                     }
                 }
 
@@ -121,15 +170,44 @@ pub(crate) enum Kind {
     SeqBegin(Option<usize>),
     SeqElem,
     SeqEnd,
+    StructBegin(&'static str, usize),
+    StructField(u32, &'static str),
+    StructEnd,
+    TupleBegin(Option<&'static str>, usize),
+    TupleElem(u32),
+    TupleEnd,
+    UnitVariant(&'static str, u32, &'static str),
+    NewtypeVariantBegin(&'static str, u32, &'static str),
+    NewtypeVariantEnd,
+    TupleVariantBegin(&'static str, u32, &'static str, usize),
+    TupleVariantElem(u32),
+    TupleVariantEnd,
+    StructVariantBegin(&'static str, u32, &'static str, usize),
+    StructVariantField(u32, &'static str),
+    StructVariantEnd,
+    Signed8(i8),
+    Signed16(i16),
+    Signed32(i32),
     Signed(i64),
+    Unsigned8(u8),
+    Unsigned16(u16),
+    Unsigned32(u32),
     Unsigned(u64),
+    Float32(f32),
     Float(f64),
     BigSigned(i128),
     BigUnsigned(u128),
+    Number(String),
     Bool(bool),
     Str(String),
     Char(char),
+    Bytes(Vec<u8>),
     None,
+    Unit,
+    SomeBegin,
+    SomeEnd,
+    TaggedBegin(stream::Tag),
+    TaggedEnd,
 }
 
 pub(crate) struct Buf {
@@ -153,10 +231,26 @@ impl Buf {
 
     fn push(&mut self, kind: Kind, depth: stack::Depth) {
         match kind {
-            Kind::MapBegin(_) | Kind::SeqBegin(_) => {
+            Kind::MapBegin(_)
+            | Kind::SeqBegin(_)
+            | Kind::StructBegin(..)
+            | Kind::TupleBegin(..)
+            | Kind::SomeBegin
+            | Kind::TaggedBegin(_)
+            | Kind::NewtypeVariantBegin(..)
+            | Kind::TupleVariantBegin(..)
+            | Kind::StructVariantBegin(..) => {
                 self.tokens.push(Token { depth: depth, kind });
             }
-            Kind::MapEnd | Kind::SeqEnd => {
+            Kind::MapEnd
+            | Kind::SeqEnd
+            | Kind::StructEnd
+            | Kind::TupleEnd
+            | Kind::SomeEnd
+            | Kind::TaggedEnd
+            | Kind::NewtypeVariantEnd
+            | Kind::TupleVariantEnd
+            | Kind::StructVariantEnd => {
                 self.tokens.push(Token { depth: depth, kind });
             }
             kind => {
@@ -175,6 +269,62 @@ impl Stream for Buf {
         Ok(())
     }
 
+    fn i8(&mut self, v: i8) -> Result<(), stream::Error> {
+        let depth = self.stack.primitive()?.depth();
+
+        self.push(Kind::Signed8(v), depth);
+
+        Ok(())
+    }
+
+    fn i16(&mut self, v: i16) -> Result<(), stream::Error> {
+        let depth = self.stack.primitive()?.depth();
+
+        self.push(Kind::Signed16(v), depth);
+
+        Ok(())
+    }
+
+    fn i32(&mut self, v: i32) -> Result<(), stream::Error> {
+        let depth = self.stack.primitive()?.depth();
+
+        self.push(Kind::Signed32(v), depth);
+
+        Ok(())
+    }
+
+    fn u8(&mut self, v: u8) -> Result<(), stream::Error> {
+        let depth = self.stack.primitive()?.depth();
+
+        self.push(Kind::Unsigned8(v), depth);
+
+        Ok(())
+    }
+
+    fn u16(&mut self, v: u16) -> Result<(), stream::Error> {
+        let depth = self.stack.primitive()?.depth();
+
+        self.push(Kind::Unsigned16(v), depth);
+
+        Ok(())
+    }
+
+    fn u32(&mut self, v: u32) -> Result<(), stream::Error> {
+        let depth = self.stack.primitive()?.depth();
+
+        self.push(Kind::Unsigned32(v), depth);
+
+        Ok(())
+    }
+
+    fn f32(&mut self, v: f32) -> Result<(), stream::Error> {
+        let depth = self.stack.primitive()?.depth();
+
+        self.push(Kind::Float32(v), depth);
+
+        Ok(())
+    }
+
     fn i64(&mut self, v: i64) -> Result<(), stream::Error> {
         let depth = self.stack.primitive()?.depth();
 
@@ -215,6 +365,14 @@ impl Stream for Buf {
         Ok(())
     }
 
+    fn number(&mut self, v: &str) -> Result<(), stream::Error> {
+        let depth = self.stack.primitive()?.depth();
+
+        self.push(Kind::Number(v.to_string()), depth);
+
+        Ok(())
+    }
+
     fn bool(&mut self, v: bool) -> Result<(), stream::Error> {
         let depth = self.stack.primitive()?.depth();
 
@@ -239,6 +397,14 @@ impl Stream for Buf {
         Ok(())
     }
 
+    fn bytes(&mut self, v: &[u8]) -> Result<(), stream::Error> {
+        let depth = self.stack.primitive()?.depth();
+
+        self.push(Kind::Bytes(v.to_vec()), depth);
+
+        Ok(())
+    }
+
     fn none(&mut self) -> Result<(), stream::Error> {
         let depth = self.stack.primitive()?.depth();
 
@@ -247,6 +413,46 @@ impl Stream for Buf {
         Ok(())
     }
 
+    fn unit(&mut self) -> Result<(), stream::Error> {
+        let depth = self.stack.primitive()?.depth();
+
+        self.push(Kind::Unit, depth);
+
+        Ok(())
+    }
+
+    fn some_begin(&mut self) -> Result<(), stream::Error> {
+        let depth = self.stack.some_begin()?.depth();
+
+        self.push(Kind::SomeBegin, depth);
+
+        Ok(())
+    }
+
+    fn some_end(&mut self) -> Result<(), stream::Error> {
+        let depth = self.stack.some_end()?.depth();
+
+        self.push(Kind::SomeEnd, depth);
+
+        Ok(())
+    }
+
+    fn tagged_begin(&mut self, tag: stream::Tag) -> Result<(), stream::Error> {
+        let depth = self.stack.tagged_begin()?.depth();
+
+        self.push(Kind::TaggedBegin(tag), depth);
+
+        Ok(())
+    }
+
+    fn tagged_end(&mut self) -> Result<(), stream::Error> {
+        let depth = self.stack.tagged_end()?.depth();
+
+        self.push(Kind::TaggedEnd, depth);
+
+        Ok(())
+    }
+
     fn map_begin(&mut self, len: Option<usize>) -> Result<(), stream::Error> {
         let depth = self.stack.map_begin()?.depth();
 
@@ -302,6 +508,151 @@ impl Stream for Buf {
 
         Ok(())
     }
+
+    fn struct_begin(&mut self, ty: &'static str, len: usize) -> Result<(), stream::Error> {
+        let depth = self.stack.struct_begin()?.depth();
+
+        self.push(Kind::StructBegin(ty, len), depth);
+
+        Ok(())
+    }
+
+    fn struct_field(&mut self, index: u32, field: &'static str) -> Result<(), stream::Error> {
+        let depth = self.stack.struct_field()?.depth();
+
+        self.push(Kind::StructField(index, field), depth);
+
+        Ok(())
+    }
+
+    fn struct_end(&mut self) -> Result<(), stream::Error> {
+        let depth = self.stack.struct_end()?.depth();
+
+        self.push(Kind::StructEnd, depth);
+
+        Ok(())
+    }
+
+    fn tuple_begin(&mut self, ty: Option<&'static str>, len: usize) -> Result<(), stream::Error> {
+        let depth = self.stack.tuple_begin()?.depth();
+
+        self.push(Kind::TupleBegin(ty, len), depth);
+
+        Ok(())
+    }
+
+    fn tuple_elem(&mut self, index: u32) -> Result<(), stream::Error> {
+        let depth = self.stack.tuple_elem()?.depth();
+
+        self.push(Kind::TupleElem(index), depth);
+
+        Ok(())
+    }
+
+    fn tuple_end(&mut self) -> Result<(), stream::Error> {
+        let depth = self.stack.tuple_end()?.depth();
+
+        self.push(Kind::TupleEnd, depth);
+
+        Ok(())
+    }
+    fn unit_variant(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<(), stream::Error> {
+        let depth = self.stack.primitive()?.depth();
+
+        self.push(Kind::UnitVariant(ty, index, variant), depth);
+
+        Ok(())
+    }
+
+    fn newtype_variant_begin(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<(), stream::Error> {
+        let depth = self.stack.newtype_variant_begin()?.depth();
+
+        self.push(Kind::NewtypeVariantBegin(ty, index, variant), depth);
+
+        Ok(())
+    }
+
+    fn newtype_variant_end(&mut self) -> Result<(), stream::Error> {
+        let depth = self.stack.newtype_variant_end()?.depth();
+
+        self.push(Kind::NewtypeVariantEnd, depth);
+
+        Ok(())
+    }
+
+    fn tuple_variant_begin(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<(), stream::Error> {
+        let depth = self.stack.tuple_variant_begin()?.depth();
+
+        self.push(Kind::TupleVariantBegin(ty, index, variant, len), depth);
+
+        Ok(())
+    }
+
+    fn tuple_variant_elem(&mut self, index: u32) -> Result<(), stream::Error> {
+        let depth = self.stack.tuple_variant_elem()?.depth();
+
+        self.push(Kind::TupleVariantElem(index), depth);
+
+        Ok(())
+    }
+
+    fn tuple_variant_end(&mut self) -> Result<(), stream::Error> {
+        let depth = self.stack.tuple_variant_end()?.depth();
+
+        self.push(Kind::TupleVariantEnd, depth);
+
+        Ok(())
+    }
+
+    fn struct_variant_begin(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<(), stream::Error> {
+        let depth = self.stack.struct_variant_begin()?.depth();
+
+        self.push(Kind::StructVariantBegin(ty, index, variant, len), depth);
+
+        Ok(())
+    }
+
+    fn struct_variant_field(
+        &mut self,
+        index: u32,
+        field: &'static str,
+    ) -> Result<(), stream::Error> {
+        let depth = self.stack.struct_variant_field()?.depth();
+
+        self.push(Kind::StructVariantField(index, field), depth);
+
+        Ok(())
+    }
+
+    fn struct_variant_end(&mut self) -> Result<(), stream::Error> {
+        let depth = self.stack.struct_variant_end()?.depth();
+
+        self.push(Kind::StructVariantEnd, depth);
+
+        Ok(())
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -369,6 +720,75 @@ mod tests {
         }
     }
 
+    struct Struct;
+
+    impl Value for Struct {
+        fn stream(&self, stream: &mut value::Stream) -> Result<(), value::Error> {
+            stream.struct_begin("Struct", 2)?;
+
+            stream.struct_field(0, "a", 1)?;
+
+            stream.struct_field_begin(1, "b")?.tuple_begin(None, 2)?;
+            stream.tuple_elem(0, 2)?;
+            stream.tuple_elem(1, 3)?;
+            stream.tuple_end()?;
+
+            stream.struct_end()
+        }
+    }
+
+    struct Tagged;
+
+    impl Value for Tagged {
+        fn stream(&self, stream: &mut value::Stream) -> Result<(), value::Error> {
+            stream.map_begin(Some(2))?;
+
+            stream.map_key("at")?;
+            stream.map_value_begin()?.tagged(
+                stream::Tag::Rfc3339,
+                "1985-04-12T23:20:50.52Z",
+            )?;
+
+            stream.map_key("id")?;
+            stream
+                .map_value_begin()?
+                .tagged_begin(stream::Tag::Custom("id"))?
+                .seq_begin(Some(1))?;
+            stream.seq_elem(1)?;
+            stream.seq_end()?;
+            stream.tagged_end()?;
+
+            stream.map_end()
+        }
+    }
+
+    struct Variants;
+
+    impl Value for Variants {
+        fn stream(&self, stream: &mut value::Stream) -> Result<(), value::Error> {
+            stream.seq_begin(Some(4))?;
+
+            stream.seq_elem_begin()?.unit_variant("Variants", 0, "Unit")?;
+
+            stream.seq_elem_begin()?.newtype_variant("Variants", 1, "Newtype", 1)?;
+
+            stream
+                .seq_elem_begin()?
+                .tuple_variant_begin("Variants", 2, "Tuple", 2)?;
+            stream.tuple_variant_elem(0, 1)?;
+            stream.tuple_variant_elem(1, 2)?;
+            stream.tuple_variant_end()?;
+
+            stream
+                .seq_elem_begin()?
+                .struct_variant_begin("Variants", 3, "Struct", 1)?;
+            stream.struct_variant_field(0, "a", 1)?;
+            stream.struct_variant_end()?;
+
+            stream.seq_end()
+        }
+    }
+
     #[test]
     fn owned_value_is_send_sync() {
         fn is_send_sync<T: Send + Sync>() {}
@@ -398,11 +818,34 @@ mod tests {
 
         assert_eq!(vec![Token::Float(42f64)], test::tokens(42f64));
 
+        assert_eq!(vec![Token::Unsigned8(42u8)], test::tokens(42u8));
+
+        assert_eq!(vec![Token::Signed16(42i16)], test::tokens(42i16));
+
+        assert_eq!(vec![Token::Float32(42f32)], test::tokens(42f32));
+
+        assert_eq!(
+            vec![Token::Signed32(42i32)],
+            test::tokens(OwnedValue::from_value(42i32))
+        );
+
         assert_eq!(vec![Token::Bool(true)], test::tokens(true));
 
         assert_eq!(vec![Token::Char('a')], test::tokens('a'));
 
+        assert_eq!(
+            vec![Token::Bytes(vec![1, 2, 3])],
+            test::tokens(value::Bytes(&[1u8, 2, 3][..]))
+        );
+
         assert_eq!(vec![Token::None], test::tokens(Option::None::<()>));
+
+        assert_eq!(vec![Token::Unit], test::tokens(()));
+
+        assert_eq!(
+            vec![Token::SomeBegin, Token::Signed(42i64), Token::SomeEnd],
+            test::tokens(Some(42i64))
+        );
     }
 
     #[test]
@@ -412,10 +855,10 @@ mod tests {
         assert_eq!(
             vec![
                 Token::MapBegin(Some(2)),
-                Token::Signed(1),
-                Token::Signed(11),
-                Token::Signed(2),
-                Token::Signed(22),
+                Token::Signed32(1),
+                Token::Signed32(11),
+                Token::Signed32(2),
+                Token::Signed32(22),
                 Token::MapEnd,
             ],
             v
@@ -429,11 +872,100 @@ mod tests {
         assert_eq!(
             vec![
                 Token::SeqBegin(Some(2)),
-                Token::Signed(1),
-                Token::Signed(2),
+                Token::Signed32(1),
+                Token::Signed32(2),
+                Token::SeqEnd,
+            ],
+            v
+        );
+    }
+
+    #[test]
+    fn owned_struct() {
+        let v = test::tokens(Struct);
+
+        assert_eq!(
+            vec![
+                Token::StructBegin("Struct", 2),
+                Token::StructField(0, "a"),
+                Token::Signed32(1),
+                Token::StructField(1, "b"),
+                Token::TupleBegin(None, 2),
+                Token::Signed32(2),
+                Token::Signed32(3),
+                Token::TupleEnd,
+                Token::StructEnd,
+            ],
+            v
+        );
+    }
+
+    #[test]
+    fn owned_struct_replay() {
+        let v = test::tokens(OwnedValue::from_value(Struct));
+
+        assert_eq!(test::tokens(Struct), v);
+    }
+
+    #[test]
+    fn owned_tagged() {
+        let v = test::tokens(Tagged);
+
+        assert_eq!(
+            vec![
+                Token::MapBegin(Some(2)),
+                Token::Str("at".into()),
+                Token::TaggedBegin(stream::Tag::Rfc3339),
+                Token::Str("1985-04-12T23:20:50.52Z".into()),
+                Token::TaggedEnd,
+                Token::Str("id".into()),
+                Token::TaggedBegin(stream::Tag::Custom("id")),
+                Token::SeqBegin(Some(1)),
+                Token::Signed32(1),
                 Token::SeqEnd,
+                Token::TaggedEnd,
+                Token::MapEnd,
             ],
             v
         );
     }
+
+    #[test]
+    fn owned_tagged_replay() {
+        let v = test::tokens(OwnedValue::from_value(Tagged));
+
+        assert_eq!(test::tokens(Tagged), v);
+    }
+
+    #[test]
+    fn owned_variants() {
+        let v = test::tokens(Variants);
+
+        assert_eq!(
+            vec![
+                Token::SeqBegin(Some(4)),
+                Token::UnitVariant("Variants", 0, "Unit"),
+                Token::NewtypeVariantBegin("Variants", 1, "Newtype"),
+                Token::Signed32(1),
+                Token::NewtypeVariantEnd,
+                Token::TupleVariantBegin("Variants", 2, "Tuple", 2),
+                Token::Signed32(1),
+                Token::Signed32(2),
+                Token::TupleVariantEnd,
+                Token::StructVariantBegin("Variants", 3, "Struct", 1),
+                Token::StructVariantField(0, "a"),
+                Token::Signed32(1),
+                Token::StructVariantEnd,
+                Token::SeqEnd,
+            ],
+            v
+        );
+    }
+
+    #[test]
+    fn owned_variants_replay() {
+        let v = test::tokens(OwnedValue::from_value(Variants));
+
+        assert_eq!(test::tokens(Variants), v);
+    }
 }
@@ -3,6 +3,7 @@ use crate::{
     stream::{
         Arguments,
         Stack,
+        Tag,
     },
     value::{
         collect,
@@ -18,14 +19,14 @@ This type is a wrapper for a [`stream::Stream`] with a more ergonomic interface.
 
 [`stream::Stream`]: ../stream/trait.Stream.html
 */
-pub struct Stream<'a> {
-    stack: DebugStack<'a>,
-    stream: &'a mut dyn collect::Stream,
+pub struct Stream<'s, 'v> {
+    stack: DebugStack<'s>,
+    stream: &'s mut dyn collect::Stream<'v>,
 }
 
-impl<'a> Stream<'a> {
+impl<'s, 'v> Stream<'s, 'v> {
     #[inline]
-    pub(super) fn new(stack: DebugStack<'a>, stream: &'a mut dyn collect::Stream) -> Self {
+    pub(super) fn new(stack: DebugStack<'s>, stream: &'s mut dyn collect::Stream<'v>) -> Self {
         Stream { stack, stream }
     }
 
@@ -42,6 +43,19 @@ impl<'a> Stream<'a> {
         Ok(())
     }
 
+    #[inline]
+    fn stream_borrowed(mut self, value: &'v (impl Value + ?Sized)) -> Result<(), Error> {
+        self.stack.begin()?;
+        self.stream.begin()?;
+
+        value.stream_borrowed(&mut self)?;
+
+        self.stack.end()?;
+        self.stream.end()?;
+
+        Ok(())
+    }
+
     /**
     Stream a value.
     */
@@ -50,6 +64,16 @@ impl<'a> Stream<'a> {
         v.stream(self)
     }
 
+    /**
+    Stream a value that's borrowed for the lifetime of the value being streamed.
+
+    Any strings in the value may be kept by the underlying stream.
+    */
+    #[inline]
+    pub fn any_borrowed(&mut self, v: &'v (impl Value + ?Sized)) -> Result<(), Error> {
+        v.stream_borrowed(self)
+    }
+
     /**
     Stream a format.
     */
@@ -62,6 +86,90 @@ impl<'a> Stream<'a> {
         Ok(())
     }
 
+    /**
+    Stream an 8bit signed integer.
+    */
+    #[inline]
+    pub fn i8(&mut self, v: i8) -> Result<(), Error> {
+        self.stack.primitive()?;
+
+        self.stream.i8(v)?;
+
+        Ok(())
+    }
+
+    /**
+    Stream a 16bit signed integer.
+    */
+    #[inline]
+    pub fn i16(&mut self, v: i16) -> Result<(), Error> {
+        self.stack.primitive()?;
+
+        self.stream.i16(v)?;
+
+        Ok(())
+    }
+
+    /**
+    Stream a 32bit signed integer.
+    */
+    #[inline]
+    pub fn i32(&mut self, v: i32) -> Result<(), Error> {
+        self.stack.primitive()?;
+
+        self.stream.i32(v)?;
+
+        Ok(())
+    }
+
+    /**
+    Stream an 8bit unsigned integer.
+    */
+    #[inline]
+    pub fn u8(&mut self, v: u8) -> Result<(), Error> {
+        self.stack.primitive()?;
+
+        self.stream.u8(v)?;
+
+        Ok(())
+    }
+
+    /**
+    Stream a 16bit unsigned integer.
+    */
+    #[inline]
+    pub fn u16(&mut self, v: u16) -> Result<(), Error> {
+        self.stack.primitive()?;
+
+        self.stream.u16(v)?;
+
+        Ok(())
+    }
+
+    /**
+    Stream a 32bit unsigned integer.
+    */
+    #[inline]
+    pub fn u32(&mut self, v: u32) -> Result<(), Error> {
+        self.stack.primitive()?;
+
+        self.stream.u32(v)?;
+
+        Ok(())
+    }
+
+    /**
+    Stream a 32bit floating point value.
+    */
+    #[inline]
+    pub fn f32(&mut self, v: f32) -> Result<(), Error> {
+        self.stack.primitive()?;
+
+        self.stream.f32(v)?;
+
+        Ok(())
+    }
+
     /**
     Stream a signed integer.
     */
@@ -122,6 +230,25 @@ impl<'a> Stream<'a> {
         Ok(())
     }
 
+    /**
+    Stream an arbitrary-precision number.
+
+    The number must be a decimal literal, like `-1.05e10`.
+    An error is returned if it isn't.
+    */
+    #[inline]
+    pub fn number(&mut self, v: &str) -> Result<(), Error> {
+        if !is_number(v) {
+            return Err(Error::msg("the number is not a valid decimal literal"));
+        }
+
+        self.stack.primitive()?;
+
+        self.stream.number(v)?;
+
+        Ok(())
+    }
+
     /**
     Stream a boolean.
     */
@@ -158,8 +285,37 @@ impl<'a> Stream<'a> {
         Ok(())
     }
 
+    /**
+    Stream a UTF8 string that's borrowed for the lifetime of the value being streamed.
+
+    Streams that accept borrowed strings may keep this one
+    without copying it. Other streams will receive it as a regular string.
+    */
+    #[inline]
+    pub fn str_borrowed(&mut self, v: &'v str) -> Result<(), Error> {
+        self.stack.primitive()?;
+
+        self.stream.str_borrowed(v)?;
+
+        Ok(())
+    }
+
+    /**
+    Stream a buffer of bytes.
+    */
+    #[inline]
+    pub fn bytes(&mut self, v: &[u8]) -> Result<(), Error> {
+        self.stack.primitive()?;
+
+        self.stream.bytes(v)?;
+
+        Ok(())
+    }
+
     /**
     Stream an empty value.
+
+    This is used for optional values that aren't present, like `Option::None`.
     */
     #[inline]
     pub fn none(&mut self) -> Result<(), Error> {
@@ -170,6 +326,78 @@ impl<'a> Stream<'a> {
         Ok(())
     }
 
+    /**
+    Stream a unit value.
+    */
+    #[inline]
+    pub fn unit(&mut self) -> Result<(), Error> {
+        self.stack.primitive()?;
+
+        self.stream.unit()?;
+
+        Ok(())
+    }
+
+    /**
+    Stream an optional value that's present, like `Option::Some`.
+    */
+    #[inline]
+    pub fn some(&mut self, v: impl Value) -> Result<(), Error> {
+        self.stack.some_begin()?;
+
+        self.stream
+            .some_collect(collect::Value::new(self.stack.borrow_mut(), &v))?;
+
+        self.stack.some_end()?;
+
+        Ok(())
+    }
+
+    /**
+    Stream an optional value that's present and borrowed for the lifetime
+    of the value being streamed.
+    */
+    #[inline]
+    pub fn some_borrowed(&mut self, v: &'v (impl Value + ?Sized)) -> Result<(), Error> {
+        if self.stream.is_borrowed() {
+            self.some_begin()?.any_borrowed(v)?;
+            self.some_end()
+        } else {
+            self.some(v)
+        }
+    }
+
+    /**
+    Stream a tagged value.
+
+    The tag gives the value a well-known or user-defined meaning,
+    like an RFC 3339 timestamp or a UUID.
+    */
+    #[inline]
+    pub fn tagged(&mut self, tag: Tag, v: impl Value) -> Result<(), Error> {
+        self.stack.tagged_begin()?;
+
+        self.stream
+            .tagged_collect(tag, collect::Value::new(self.stack.borrow_mut(), &v))?;
+
+        self.stack.tagged_end()?;
+
+        Ok(())
+    }
+
+    /**
+    Stream a tagged value that's borrowed for the lifetime of the value being streamed.
+    */
+    #[inline]
+    pub fn tagged_borrowed(&mut self, tag: Tag, v: &'v (impl Value + ?Sized)) -> Result<(), Error> {
+        if self.stream.is_borrowed() {
+            self.tagged_begin(tag)?.any_borrowed(v)?;
+            self.tagged_end()
+        } else {
+            self.tagged(tag, v)
+        }
+    }
+
     /**
     Begin a map.
     */
@@ -195,6 +423,18 @@ impl<'a> Stream<'a> {
         Ok(())
     }
 
+    /**
+    Stream a map key that's borrowed for the lifetime of the value being streamed.
+    */
+    #[inline]
+    pub fn map_key_borrowed(&mut self, k: &'v (impl Value + ?Sized)) -> Result<(), Error> {
+        if self.stream.is_borrowed() {
+            self.map_key_begin()?.any_borrowed(k)
+        } else {
+            self.map_key(k)
+        }
+    }
+
     /**
     Stream a map value.
     */
@@ -208,6 +448,18 @@ impl<'a> Stream<'a> {
         Ok(())
     }
 
+    /**
+    Stream a map value that's borrowed for the lifetime of the value being streamed.
+    */
+    #[inline]
+    pub fn map_value_borrowed(&mut self, v: &'v (impl Value + ?Sized)) -> Result<(), Error> {
+        if self.stream.is_borrowed() {
+            self.map_value_begin()?.any_borrowed(v)
+        } else {
+            self.map_value(v)
+        }
+    }
+
     /**
     End a map.
     */
@@ -220,6 +472,18 @@ impl<'a> Stream<'a> {
         Ok(())
     }
 
+    /**
+    Stream the entries of a map into the current map.
+
+    The value is streamed without its outer map, so its entries
+    appear alongside the other entries in the current map.
+    */
+    #[cfg(feature = "derive")]
+    #[inline]
+    pub(crate) fn map_flatten(&mut self, v: impl Value) -> Result<(), Error> {
+        stream(v, crate::derive::Flatten::new(&mut *self.stream))
+    }
+
     /**
     Begin a sequence.
     */
@@ -245,6 +509,18 @@ impl<'a> Stream<'a> {
         Ok(())
     }
 
+    /**
+    Stream a sequence element that's borrowed for the lifetime of the value being streamed.
+    */
+    #[inline]
+    pub fn seq_elem_borrowed(&mut self, v: &'v (impl Value + ?Sized)) -> Result<(), Error> {
+        if self.stream.is_borrowed() {
+            self.seq_elem_begin()?.any_borrowed(v)
+        } else {
+            self.seq_elem(v)
+        }
+    }
+
     /**
     End a sequence.
     */
@@ -256,96 +532,569 @@ impl<'a> Stream<'a> {
 
         Ok(())
     }
-}
 
-impl<'a> Stream<'a> {
     /**
-    Begin a map key.
+    Begin a struct.
     */
     #[inline]
-    pub fn map_key_begin(&mut self) -> Result<&mut Stream<'a>, Error> {
-        self.stack.map_key()?;
+    pub fn struct_begin(&mut self, ty: &'static str, len: usize) -> Result<(), Error> {
+        self.stack.struct_begin()?;
 
-        self.stream.map_key()?;
+        self.stream.struct_begin(ty, len)?;
 
-        Ok(self)
+        Ok(())
     }
 
     /**
-    Begin a map value.
+    Stream a struct field.
     */
     #[inline]
-    pub fn map_value_begin(&mut self) -> Result<&mut Stream<'a>, Error> {
-        self.stack.map_value()?;
+    pub fn struct_field(
+        &mut self,
+        index: u32,
+        field: &'static str,
+        v: impl Value,
+    ) -> Result<(), Error> {
+        self.stack.struct_field()?;
+
+        self.stream.struct_field_collect(
+            index,
+            field,
+            collect::Value::new(self.stack.borrow_mut(), &v),
+        )?;
 
-        self.stream.map_value()?;
+        Ok(())
+    }
 
-        Ok(self)
+    /**
+    Stream a struct field that's borrowed for the lifetime of the value being streamed.
+    */
+    #[inline]
+    pub fn struct_field_borrowed(
+        &mut self,
+        index: u32,
+        field: &'static str,
+        v: &'v (impl Value + ?Sized),
+    ) -> Result<(), Error> {
+        if self.stream.is_borrowed() {
+            self.struct_field_begin(index, field)?.any_borrowed(v)
+        } else {
+            self.struct_field(index, field, v)
+        }
     }
 
     /**
-    Begin a sequence element.
+    End a struct.
     */
     #[inline]
-    pub fn seq_elem_begin(&mut self) -> Result<&mut Stream<'a>, Error> {
-        self.stack.seq_elem()?;
+    pub fn struct_end(&mut self) -> Result<(), Error> {
+        self.stack.struct_end()?;
 
-        self.stream.seq_elem()?;
+        self.stream.struct_end()?;
 
-        Ok(self)
+        Ok(())
     }
-}
 
-pub(crate) fn stream(value: impl Value, mut stream: impl collect::Stream) -> Result<(), Error> {
-    cfg_debug_stack! {
-        if #[debug_assertions] {
-            let mut stack = Stack::default();
-
-            let stream = Stream::new(
-                DebugStack { stack: &mut stack, _m: PhantomData },
-                &mut stream);
+    /**
+    Begin a tuple.
+    */
+    #[inline]
+    pub fn tuple_begin(&mut self, ty: Option<&'static str>, len: usize) -> Result<(), Error> {
+        self.stack.tuple_begin()?;
 
-            stream.stream(value)
-        }
-        else {
-            let stream = Stream::new(DebugStack { _m: PhantomData }, &mut stream);
+        self.stream.tuple_begin(ty, len)?;
 
-            stream.stream(value)
-        }
+        Ok(())
     }
-}
 
-pub(super) struct DebugStack<'a> {
-    #[cfg(debug_assertions)]
-    stack: &'a mut Stack,
-    pub(super) _m: PhantomData<&'a mut Stack>,
-}
+    /**
+    Stream a tuple element.
+    */
+    #[inline]
+    pub fn tuple_elem(&mut self, index: u32, v: impl Value) -> Result<(), Error> {
+        self.stack.tuple_elem()?;
 
-impl<'a> DebugStack<'a> {
+        self.stream
+            .tuple_elem_collect(index, collect::Value::new(self.stack.borrow_mut(), &v))?;
+
+        Ok(())
+    }
+
+    /**
+    Stream a tuple element that's borrowed for the lifetime of the value being streamed.
+    */
     #[inline]
-    fn borrow_mut(&mut self) -> DebugStack {
-        cfg_debug_stack! {
-            if #[debug_assertions] {
-                DebugStack {
-                    stack: self.stack,
-                    _m: PhantomData,
-                }
-            }
-            else {
-                DebugStack {
-                    _m: PhantomData,
-                }
-            }
+    pub fn tuple_elem_borrowed(
+        &mut self,
+        index: u32,
+        v: &'v (impl Value + ?Sized),
+    ) -> Result<(), Error> {
+        if self.stream.is_borrowed() {
+            self.tuple_elem_begin(index)?.any_borrowed(v)
+        } else {
+            self.tuple_elem(index, v)
         }
     }
 
+    /**
+    End a tuple.
+    */
     #[inline]
-    pub fn begin(&mut self) -> Result<(), Error> {
-        cfg_debug_stack! {
-            if #[debug_assertions] {
-                self.stack.begin()?;
-            }
-        }
+    pub fn tuple_end(&mut self) -> Result<(), Error> {
+        self.stack.tuple_end()?;
+
+        self.stream.tuple_end()?;
+
+        Ok(())
+    }
+
+    /**
+    Stream a unit enum variant.
+    */
+    #[inline]
+    pub fn unit_variant(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        self.stack.primitive()?;
+
+        self.stream.unit_variant(ty, index, variant)?;
+
+        Ok(())
+    }
+
+    /**
+    Stream a newtype enum variant.
+    */
+    #[inline]
+    pub fn newtype_variant(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+        v: impl Value,
+    ) -> Result<(), Error> {
+        self.stack.newtype_variant_begin()?;
+
+        self.stream.newtype_variant_collect(
+            ty,
+            index,
+            variant,
+            collect::Value::new(self.stack.borrow_mut(), &v),
+        )?;
+
+        self.stack.newtype_variant_end()?;
+
+        Ok(())
+    }
+
+    /**
+    Begin a tuple enum variant.
+    */
+    #[inline]
+    pub fn tuple_variant_begin(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<(), Error> {
+        self.stack.tuple_variant_begin()?;
+
+        self.stream.tuple_variant_begin(ty, index, variant, len)?;
+
+        Ok(())
+    }
+
+    /**
+    Stream a tuple enum variant element.
+    */
+    #[inline]
+    pub fn tuple_variant_elem(&mut self, index: u32, v: impl Value) -> Result<(), Error> {
+        self.stack.tuple_variant_elem()?;
+
+        self.stream
+            .tuple_variant_elem_collect(index, collect::Value::new(self.stack.borrow_mut(), &v))?;
+
+        Ok(())
+    }
+
+    /**
+    End a tuple enum variant.
+    */
+    #[inline]
+    pub fn tuple_variant_end(&mut self) -> Result<(), Error> {
+        self.stack.tuple_variant_end()?;
+
+        self.stream.tuple_variant_end()?;
+
+        Ok(())
+    }
+
+    /**
+    Begin a struct enum variant.
+    */
+    #[inline]
+    pub fn struct_variant_begin(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<(), Error> {
+        self.stack.struct_variant_begin()?;
+
+        self.stream.struct_variant_begin(ty, index, variant, len)?;
+
+        Ok(())
+    }
+
+    /**
+    Stream a struct enum variant field.
+    */
+    #[inline]
+    pub fn struct_variant_field(
+        &mut self,
+        index: u32,
+        field: &'static str,
+        v: impl Value,
+    ) -> Result<(), Error> {
+        self.stack.struct_variant_field()?;
+
+        self.stream.struct_variant_field_collect(
+            index,
+            field,
+            collect::Value::new(self.stack.borrow_mut(), &v),
+        )?;
+
+        Ok(())
+    }
+
+    /**
+    Stream a struct enum variant field that's borrowed for the lifetime of the value being streamed.
+    */
+    #[inline]
+    pub fn struct_variant_field_borrowed(
+        &mut self,
+        index: u32,
+        field: &'static str,
+        v: &'v (impl Value + ?Sized),
+    ) -> Result<(), Error> {
+        if self.stream.is_borrowed() {
+            self.struct_variant_field_begin(index, field)?.any_borrowed(v)
+        } else {
+            self.struct_variant_field(index, field, v)
+        }
+    }
+
+    /**
+    End a struct enum variant.
+    */
+    #[inline]
+    pub fn struct_variant_end(&mut self) -> Result<(), Error> {
+        self.stack.struct_variant_end()?;
+
+        self.stream.struct_variant_end()?;
+
+        Ok(())
+    }
+}
+
+impl<'s, 'v> Stream<'s, 'v> {
+    /**
+    Begin a map key.
+    */
+    #[inline]
+    pub fn map_key_begin(&mut self) -> Result<&mut Stream<'s, 'v>, Error> {
+        self.stack.map_key()?;
+
+        self.stream.map_key()?;
+
+        Ok(self)
+    }
+
+    /**
+    Begin a map value.
+    */
+    #[inline]
+    pub fn map_value_begin(&mut self) -> Result<&mut Stream<'s, 'v>, Error> {
+        self.stack.map_value()?;
+
+        self.stream.map_value()?;
+
+        Ok(self)
+    }
+
+    /**
+    Begin a sequence element.
+    */
+    #[inline]
+    pub fn seq_elem_begin(&mut self) -> Result<&mut Stream<'s, 'v>, Error> {
+        self.stack.seq_elem()?;
+
+        self.stream.seq_elem()?;
+
+        Ok(self)
+    }
+
+    /**
+    Begin a struct field.
+    */
+    #[inline]
+    pub fn struct_field_begin(
+        &mut self,
+        index: u32,
+        field: &'static str,
+    ) -> Result<&mut Stream<'s, 'v>, Error> {
+        self.stack.struct_field()?;
+
+        self.stream.struct_field(index, field)?;
+
+        Ok(self)
+    }
+
+    /**
+    Begin a tuple element.
+    */
+    #[inline]
+    pub fn tuple_elem_begin(&mut self, index: u32) -> Result<&mut Stream<'s, 'v>, Error> {
+        self.stack.tuple_elem()?;
+
+        self.stream.tuple_elem(index)?;
+
+        Ok(self)
+    }
+
+    /**
+    Begin an optional value that's present.
+
+    The optional value must be completed by calling `some_end`.
+    */
+    #[inline]
+    pub fn some_begin(&mut self) -> Result<&mut Stream<'s, 'v>, Error> {
+        self.stack.some_begin()?;
+
+        self.stream.some_begin()?;
+
+        Ok(self)
+    }
+
+    /**
+    End an optional value that's present.
+    */
+    #[inline]
+    pub fn some_end(&mut self) -> Result<(), Error> {
+        self.stack.some_end()?;
+
+        self.stream.some_end()?;
+
+        Ok(())
+    }
+
+    /**
+    Begin a tagged value.
+
+    The tagged value must be completed by calling `tagged_end`.
+    */
+    #[inline]
+    pub fn tagged_begin(&mut self, tag: Tag) -> Result<&mut Stream<'s, 'v>, Error> {
+        self.stack.tagged_begin()?;
+
+        self.stream.tagged_begin(tag)?;
+
+        Ok(self)
+    }
+
+    /**
+    End a tagged value.
+    */
+    #[inline]
+    pub fn tagged_end(&mut self) -> Result<(), Error> {
+        self.stack.tagged_end()?;
+
+        self.stream.tagged_end()?;
+
+        Ok(())
+    }
+
+    /**
+    Begin a newtype enum variant.
+
+    The variant must be completed by calling `newtype_variant_end`.
+    */
+    #[inline]
+    pub fn newtype_variant_begin(
+        &mut self,
+        ty: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<&mut Stream<'s, 'v>, Error> {
+        self.stack.newtype_variant_begin()?;
+
+        self.stream.newtype_variant_begin(ty, index, variant)?;
+
+        Ok(self)
+    }
+
+    /**
+    End a newtype enum variant.
+    */
+    #[inline]
+    pub fn newtype_variant_end(&mut self) -> Result<(), Error> {
+        self.stack.newtype_variant_end()?;
+
+        self.stream.newtype_variant_end()?;
+
+        Ok(())
+    }
+
+    /**
+    Begin a tuple enum variant element.
+    */
+    #[inline]
+    pub fn tuple_variant_elem_begin(&mut self, index: u32) -> Result<&mut Stream<'s, 'v>, Error> {
+        self.stack.tuple_variant_elem()?;
+
+        self.stream.tuple_variant_elem(index)?;
+
+        Ok(self)
+    }
+
+    /**
+    Begin a struct enum variant field.
+    */
+    #[inline]
+    pub fn struct_variant_field_begin(
+        &mut self,
+        index: u32,
+        field: &'static str,
+    ) -> Result<&mut Stream<'s, 'v>, Error> {
+        self.stack.struct_variant_field()?;
+
+        self.stream.struct_variant_field(index, field)?;
+
+        Ok(self)
+    }
+}
+
+pub(crate) fn stream<'v>(value: impl Value, stream: impl collect::Stream<'v>) -> Result<(), Error> {
+    with_stream(stream, |stream| stream.stream(value))
+}
+
+pub(crate) fn stream_borrowed<'v>(
+    value: &'v (impl Value + ?Sized),
+    stream: impl collect::Stream<'v>,
+) -> Result<(), Error> {
+    with_stream(stream, |stream| stream.stream_borrowed(value))
+}
+
+#[inline]
+fn with_stream<'v>(
+    mut stream: impl collect::Stream<'v>,
+    f: impl FnOnce(Stream<'_, 'v>) -> Result<(), Error>,
+) -> Result<(), Error> {
+    cfg_debug_stack! {
+        if #[debug_assertions] {
+            let mut stack = Stack::default();
+
+            let stream = Stream::new(
+                DebugStack { stack: &mut stack, _m: PhantomData },
+                &mut stream);
+
+            f(stream)
+        }
+        else {
+            let stream = Stream::new(DebugStack { _m: PhantomData }, &mut stream);
+
+            f(stream)
+        }
+    }
+}
+
+/**
+Whether a string is a decimal literal.
+
+Decimal literals follow the same grammar as json numbers: an optional `-` sign,
+an integer without leading zeros, an optional fraction, and an optional exponent.
+*/
+fn is_number(v: &str) -> bool {
+    fn digits(v: &[u8]) -> usize {
+        v.iter().take_while(|b| b.is_ascii_digit()).count()
+    }
+
+    let mut v = v.as_bytes();
+
+    if let Some(b'-') = v.first() {
+        v = &v[1..];
+    }
+
+    // The integer
+    match digits(v) {
+        0 => return false,
+        n if n > 1 && v[0] == b'0' => return false,
+        n => v = &v[n..],
+    }
+
+    // The fraction
+    if let Some(b'.') = v.first() {
+        v = &v[1..];
+
+        match digits(v) {
+            0 => return false,
+            n => v = &v[n..],
+        }
+    }
+
+    // The exponent
+    if v.first() == Some(&b'e') || v.first() == Some(&b'E') {
+        v = &v[1..];
+
+        if v.first() == Some(&b'+') || v.first() == Some(&b'-') {
+            v = &v[1..];
+        }
+
+        match digits(v) {
+            0 => return false,
+            n => v = &v[n..],
+        }
+    }
+
+    v.is_empty()
+}
+
+pub(super) struct DebugStack<'a> {
+    #[cfg(debug_assertions)]
+    stack: &'a mut Stack,
+    pub(super) _m: PhantomData<&'a mut Stack>,
+}
+
+impl<'a> DebugStack<'a> {
+    #[inline]
+    fn borrow_mut(&mut self) -> DebugStack {
+        cfg_debug_stack! {
+            if #[debug_assertions] {
+                DebugStack {
+                    stack: self.stack,
+                    _m: PhantomData,
+                }
+            }
+            else {
+                DebugStack {
+                    _m: PhantomData,
+                }
+            }
+        }
+    }
+
+    #[inline]
+    pub fn begin(&mut self) -> Result<(), Error> {
+        cfg_debug_stack! {
+            if #[debug_assertions] {
+                self.stack.begin()?;
+            }
+        }
 
         Ok(())
     }
@@ -438,6 +1187,204 @@ impl<'a> DebugStack<'a> {
         Ok(())
     }
 
+    #[inline]
+    pub fn struct_begin(&mut self) -> Result<(), Error> {
+        cfg_debug_stack! {
+            if #[debug_assertions] {
+                self.stack.struct_begin()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn struct_field(&mut self) -> Result<(), Error> {
+        cfg_debug_stack! {
+            if #[debug_assertions] {
+                self.stack.struct_field()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn struct_end(&mut self) -> Result<(), Error> {
+        cfg_debug_stack! {
+            if #[debug_assertions] {
+                self.stack.struct_end()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn tuple_begin(&mut self) -> Result<(), Error> {
+        cfg_debug_stack! {
+            if #[debug_assertions] {
+                self.stack.tuple_begin()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn tuple_elem(&mut self) -> Result<(), Error> {
+        cfg_debug_stack! {
+            if #[debug_assertions] {
+                self.stack.tuple_elem()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn tuple_end(&mut self) -> Result<(), Error> {
+        cfg_debug_stack! {
+            if #[debug_assertions] {
+                self.stack.tuple_end()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn some_begin(&mut self) -> Result<(), Error> {
+        cfg_debug_stack! {
+            if #[debug_assertions] {
+                self.stack.some_begin()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn some_end(&mut self) -> Result<(), Error> {
+        cfg_debug_stack! {
+            if #[debug_assertions] {
+                self.stack.some_end()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn tagged_begin(&mut self) -> Result<(), Error> {
+        cfg_debug_stack! {
+            if #[debug_assertions] {
+                self.stack.tagged_begin()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn tagged_end(&mut self) -> Result<(), Error> {
+        cfg_debug_stack! {
+            if #[debug_assertions] {
+                self.stack.tagged_end()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn newtype_variant_begin(&mut self) -> Result<(), Error> {
+        cfg_debug_stack! {
+            if #[debug_assertions] {
+                self.stack.newtype_variant_begin()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn newtype_variant_end(&mut self) -> Result<(), Error> {
+        cfg_debug_stack! {
+            if #[debug_assertions] {
+                self.stack.newtype_variant_end()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn tuple_variant_begin(&mut self) -> Result<(), Error> {
+        cfg_debug_stack! {
+            if #[debug_assertions] {
+                self.stack.tuple_variant_begin()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn tuple_variant_elem(&mut self) -> Result<(), Error> {
+        cfg_debug_stack! {
+            if #[debug_assertions] {
+                self.stack.tuple_variant_elem()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn tuple_variant_end(&mut self) -> Result<(), Error> {
+        cfg_debug_stack! {
+            if #[debug_assertions] {
+                self.stack.tuple_variant_end()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn struct_variant_begin(&mut self) -> Result<(), Error> {
+        cfg_debug_stack! {
+            if #[debug_assertions] {
+                self.stack.struct_variant_begin()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn struct_variant_field(&mut self) -> Result<(), Error> {
+        cfg_debug_stack! {
+            if #[debug_assertions] {
+                self.stack.struct_variant_field()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn struct_variant_end(&mut self) -> Result<(), Error> {
+        cfg_debug_stack! {
+            if #[debug_assertions] {
+                self.stack.struct_variant_end()?;
+            }
+        }
+
+        Ok(())
+    }
+
     #[inline]
     pub fn end(&mut self) -> Result<(), Error> {
         cfg_debug_stack! {
@@ -450,14 +1397,26 @@ impl<'a> DebugStack<'a> {
     }
 }
 
-#[cfg(all(test, not(debug_assertions)))]
+#[cfg(test)]
 mod tests {
     use super::*;
 
-    use crate::std::mem;
-
     #[test]
+    #[cfg(not(debug_assertions))]
     fn debug_stack_is_zero_sized() {
+        use crate::std::mem;
+
         assert_eq!(0, mem::size_of::<DebugStack>());
     }
+
+    #[test]
+    fn number_validation() {
+        for valid in &["0", "-0", "1", "-42", "0.5", "1.05", "1e10", "-1.5E-3", "2e+8"] {
+            assert!(is_number(valid), "{} should be valid", valid);
+        }
+
+        for invalid in &["", "-", "01", "1.", ".5", "+1", "1e", "1e+", "1.5.5", "NaN", "1 "] {
+            assert!(!is_number(invalid), "{} should be invalid", invalid);
+        }
+    }
 }
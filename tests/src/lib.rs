@@ -42,6 +42,106 @@ struct Nested<'a> {
     b: &'a str,
 }
 
+#[derive(Value, Serialize)]
+struct NewType(i32);
+
+#[derive(Value, Serialize)]
+struct Tuple<'a>(i32, &'a str, NewType);
+
+#[derive(Value, Serialize)]
+struct Unit;
+
+#[derive(Value, Serialize)]
+enum Enum<'a> {
+    Unit,
+    NewType(Nested<'a>),
+    Tuple(i32, &'a str),
+    #[sval(rename = "renamed")]
+    #[serde(rename = "renamed")]
+    Struct {
+        a: i32,
+        #[sval(rename = "renamed")]
+        #[serde(rename = "renamed")]
+        stream: i32,
+    },
+}
+
+#[derive(Value)]
+struct Attributes<'a> {
+    #[sval(skip)]
+    #[allow(dead_code)]
+    skipped: i32,
+    #[sval(skip_if = "Option::is_none")]
+    optional: Option<i32>,
+    #[sval(flatten)]
+    flattened: Nested<'a>,
+    #[sval(with = "stream_as_str")]
+    with: i32,
+}
+
+#[derive(Value)]
+enum AttributesEnum {
+    Tuple(#[sval(with = "stream_as_str")] i32, i32),
+    Struct {
+        #[sval(skip)]
+        #[allow(dead_code)]
+        skipped: i32,
+        #[sval(skip_if = "Option::is_none")]
+        optional: Option<i32>,
+        #[sval(with = "stream_as_str", rename = "renamed")]
+        with: i32,
+    },
+}
+
+#[derive(Value)]
+#[sval(rename_all = "camelCase")]
+struct RenameAll {
+    field_a: i32,
+    #[sval(rename = "b")]
+    field_b: i32,
+}
+
+#[derive(Value)]
+#[sval(rename_all = "snake_case", tag = "type")]
+enum Internal {
+    EmptyUnit,
+    NewType(Nested<'static>),
+    StructFields { a: i32 },
+}
+
+#[derive(Value)]
+#[sval(tag = "t", content = "c")]
+enum Adjacent {
+    Unit,
+    NewType(i32),
+    Tuple(i32, i32),
+    Struct { a: i32 },
+}
+
+#[derive(Value)]
+#[sval(untagged)]
+enum Untagged {
+    Unit,
+    NewType(i32),
+    Tuple(i32, i32),
+    Struct { a: i32 },
+}
+
+fn stream_as_str(v: &i32, stream: &mut value::Stream) -> Result<(), value::Error> {
+    stream.fmt(format_args!("{}", v))
+}
+
+struct SerdeBytes<'a>(&'a [u8]);
+
+impl<'a> serde::Serialize for SerdeBytes<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
 struct Anonymous;
 
 impl Value for Anonymous {
@@ -81,18 +181,248 @@ fn sval_derive() {
     });
     assert_eq!(
         vec![
-            Token::MapBegin(Some(3)),
+            Token::StructBegin("Struct", 3),
+            Token::StructField(0, "a"),
+            Token::Signed32(1),
+            Token::StructField(1, "b"),
+            Token::Signed32(2),
+            Token::StructField(2, "renamed"),
+            Token::StructBegin("Nested", 2),
+            Token::StructField(0, "a"),
+            Token::Signed32(3),
+            Token::StructField(1, "b"),
+            Token::Str(String::from("Hello!")),
+            Token::StructEnd,
+            Token::StructEnd,
+        ],
+        v
+    );
+}
+
+#[test]
+fn sval_derive_newtype() {
+    use self::SvalToken as Token;
+
+    let v = sval::test::tokens(NewType(1));
+    assert_eq!(vec![Token::Signed32(1)], v);
+}
+
+#[test]
+fn sval_derive_tuple() {
+    use self::SvalToken as Token;
+
+    let v = sval::test::tokens(Tuple(1, "Hello!", NewType(2)));
+    assert_eq!(
+        vec![
+            Token::TupleBegin(Some("Tuple"), 3),
+            Token::Signed32(1),
+            Token::Str(String::from("Hello!")),
+            Token::Signed32(2),
+            Token::TupleEnd,
+        ],
+        v
+    );
+}
+
+#[test]
+fn sval_derive_unit() {
+    use self::SvalToken as Token;
+
+    let v = sval::test::tokens(Unit);
+    assert_eq!(vec![Token::Unit], v);
+}
+
+#[test]
+fn sval_derive_enum() {
+    use self::SvalToken as Token;
+
+    let v = sval::test::tokens(Enum::Unit);
+    assert_eq!(vec![Token::UnitVariant("Enum", 0, "Unit")], v);
+
+    let v = sval::test::tokens(Enum::NewType(Nested { a: 1, b: "Hello!" }));
+    assert_eq!(
+        vec![
+            Token::NewtypeVariantBegin("Enum", 1, "NewType"),
+            Token::StructBegin("Nested", 2),
+            Token::StructField(0, "a"),
+            Token::Signed32(1),
+            Token::StructField(1, "b"),
+            Token::Str(String::from("Hello!")),
+            Token::StructEnd,
+            Token::NewtypeVariantEnd,
+        ],
+        v
+    );
+
+    let v = sval::test::tokens(Enum::Tuple(1, "Hello!"));
+    assert_eq!(
+        vec![
+            Token::TupleVariantBegin("Enum", 2, "Tuple", 2),
+            Token::Signed32(1),
+            Token::Str(String::from("Hello!")),
+            Token::TupleVariantEnd,
+        ],
+        v
+    );
+
+    let v = sval::test::tokens(Enum::Struct { a: 1, stream: 2 });
+    assert_eq!(
+        vec![
+            Token::StructVariantBegin("Enum", 3, "renamed", 2),
+            Token::StructVariantField(0, "a"),
+            Token::Signed32(1),
+            Token::StructVariantField(1, "renamed"),
+            Token::Signed32(2),
+            Token::StructVariantEnd,
+        ],
+        v
+    );
+}
+
+#[test]
+fn sval_derive_enum_matches_serde() {
+    use self::SerdeToken as Token;
+
+    assert_ser_tokens(
+        &sval::serde::to_serialize(Enum::Tuple(1, "Hello!")),
+        &[
+            Token::TupleVariant {
+                name: "Enum",
+                variant: "Tuple",
+                len: 2,
+            },
+            Token::I32(1),
+            Token::Str("Hello!"),
+            Token::TupleVariantEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &sval::serde::to_serialize(Enum::Struct { a: 1, stream: 2 }),
+        &[
+            Token::StructVariant {
+                name: "Enum",
+                variant: "renamed",
+                len: 2,
+            },
+            Token::Str("a"),
+            Token::I32(1),
+            Token::Str("renamed"),
+            Token::I32(2),
+            Token::StructVariantEnd,
+        ],
+    );
+}
+
+#[test]
+fn sval_derive_struct_matches_serde() {
+    use self::SerdeToken as Token;
+
+    let strct = [
+        Token::Struct {
+            name: "Struct",
+            len: 3,
+        },
+        Token::Str("a"),
+        Token::I32(1),
+        Token::Str("b"),
+        Token::I32(2),
+        Token::Str("renamed"),
+        Token::Struct {
+            name: "Nested",
+            len: 2,
+        },
+        Token::Str("a"),
+        Token::I32(3),
+        Token::Str("b"),
+        Token::Str("Hello!"),
+        Token::StructEnd,
+        Token::StructEnd,
+    ];
+
+    let tuple = [
+        Token::TupleStruct {
+            name: "Tuple",
+            len: 3,
+        },
+        Token::I32(1),
+        Token::Str("Hello!"),
+        Token::I32(2),
+        Token::TupleStructEnd,
+    ];
+
+    let v = Struct {
+        a: 1,
+        b: 2,
+        c: Nested { a: 3, b: "Hello!" },
+    };
+    assert_ser_tokens(&sval::serde::to_serialize(&v), &strct);
+    assert_ser_tokens(
+        &sval::serde::to_serialize(value::OwnedValue::from_value(&v)),
+        &strct,
+    );
+
+    let v = Tuple(1, "Hello!", NewType(2));
+    assert_ser_tokens(&sval::serde::to_serialize(&v), &tuple);
+    assert_ser_tokens(
+        &sval::serde::to_serialize(value::OwnedValue::from_value(&v)),
+        &tuple,
+    );
+
+    assert_ser_tokens(
+        &sval::serde::to_serialize((1, "Hello!")),
+        &[
+            Token::Tuple { len: 2 },
+            Token::I32(1),
+            Token::Str("Hello!"),
+            Token::TupleEnd,
+        ],
+    );
+}
+
+#[test]
+fn sval_derive_attributes() {
+    use self::SvalToken as Token;
+
+    let v = sval::test::tokens(Attributes {
+        skipped: 1,
+        optional: Some(2),
+        flattened: Nested { a: 3, b: "Hello!" },
+        with: 4,
+    });
+    assert_eq!(
+        vec![
+            Token::MapBegin(None),
+            Token::Str(String::from("optional")),
+            Token::SomeBegin,
+            Token::Signed32(2),
+            Token::SomeEnd,
             Token::Str(String::from("a")),
-            Token::Signed(1),
+            Token::Signed32(3),
             Token::Str(String::from("b")),
-            Token::Signed(2),
-            Token::Str(String::from("renamed")),
-            Token::MapBegin(Some(2)),
+            Token::Str(String::from("Hello!")),
+            Token::Str(String::from("with")),
+            Token::Str(String::from("4")),
+            Token::MapEnd,
+        ],
+        v
+    );
+
+    let v = sval::test::tokens(Attributes {
+        skipped: 1,
+        optional: None,
+        flattened: Nested { a: 3, b: "Hello!" },
+        with: 4,
+    });
+    assert_eq!(
+        vec![
+            Token::MapBegin(None),
             Token::Str(String::from("a")),
-            Token::Signed(3),
+            Token::Signed32(3),
             Token::Str(String::from("b")),
             Token::Str(String::from("Hello!")),
-            Token::MapEnd,
+            Token::Str(String::from("with")),
+            Token::Str(String::from("4")),
             Token::MapEnd,
         ],
         v
@@ -100,68 +430,147 @@ fn sval_derive() {
 }
 
 #[test]
-fn sval_derive_from_serde() {
+fn sval_derive_attributes_enum() {
     use self::SvalToken as Token;
 
-    let v = sval::test::tokens(Tagged::NewType(1));
+    let v = sval::test::tokens(AttributesEnum::Tuple(1, 2));
     assert_eq!(
         vec![
-            Token::MapBegin(Some(1)),
-            Token::Str(String::from("NewType")),
-            Token::SeqBegin(Some(1)),
-            Token::Signed(1),
-            Token::SeqEnd,
-            Token::MapEnd,
+            Token::TupleVariantBegin("AttributesEnum", 0, "Tuple", 2),
+            Token::Str(String::from("1")),
+            Token::Signed32(2),
+            Token::TupleVariantEnd,
+        ],
+        v
+    );
+
+    let v = sval::test::tokens(AttributesEnum::Struct {
+        skipped: 1,
+        optional: None,
+        with: 3,
+    });
+    assert_eq!(
+        vec![
+            Token::StructVariantBegin("AttributesEnum", 1, "Struct", 1),
+            Token::StructVariantField(2, "renamed"),
+            Token::Str(String::from("3")),
+            Token::StructVariantEnd,
+        ],
+        v
+    );
+
+    let v = sval::test::tokens(AttributesEnum::Struct {
+        skipped: 1,
+        optional: Some(2),
+        with: 3,
+    });
+    assert_eq!(
+        vec![
+            Token::StructVariantBegin("AttributesEnum", 1, "Struct", 2),
+            Token::StructVariantField(1, "optional"),
+            Token::SomeBegin,
+            Token::Signed32(2),
+            Token::SomeEnd,
+            Token::StructVariantField(2, "renamed"),
+            Token::Str(String::from("3")),
+            Token::StructVariantEnd,
         ],
         v
     );
 }
 
 #[test]
-fn serde_to_sval_tagged() {
+fn sval_derive_flatten_non_map() {
+    #[derive(Value)]
+    struct Flatten {
+        #[sval(flatten)]
+        a: i32,
+    }
+
+    struct Ignore;
+
+    impl sval::stream::Stream for Ignore {
+        fn fmt(&mut self, _: sval::stream::Arguments) -> Result<(), sval::stream::Error> {
+            Ok(())
+        }
+    }
+
+    assert!(sval::stream(Flatten { a: 1 }, Ignore).is_err());
+}
+
+#[test]
+fn sval_derive_flatten_optional() {
     use self::SvalToken as Token;
 
-    let v = sval::test::tokens(sval::serde::to_value(Tagged::Unit));
-    assert_eq!(vec![Token::Str(String::from("Unit"))], v);
+    #[derive(Value)]
+    struct Flatten {
+        a: i32,
+        #[sval(flatten)]
+        b: Option<Nested<'static>>,
+    }
 
-    let v = sval::test::tokens(sval::serde::to_value(Tagged::NewType(1)));
+    let v = sval::test::tokens(Flatten {
+        a: 1,
+        b: Some(Nested { a: 2, b: "Hello!" }),
+    });
     assert_eq!(
         vec![
-            Token::MapBegin(Some(1)),
-            Token::Str(String::from("NewType")),
-            Token::SeqBegin(Some(1)),
-            Token::Signed(1),
-            Token::SeqEnd,
+            Token::MapBegin(None),
+            Token::Str(String::from("a")),
+            Token::Signed32(1),
+            Token::Str(String::from("a")),
+            Token::Signed32(2),
+            Token::Str(String::from("b")),
+            Token::Str(String::from("Hello!")),
             Token::MapEnd,
         ],
         v
     );
 
-    let v = sval::test::tokens(sval::serde::to_value(Tagged::Tuple(1, 2)));
+    let v = sval::test::tokens(Flatten { a: 1, b: None });
     assert_eq!(
         vec![
-            Token::MapBegin(Some(1)),
-            Token::Str(String::from("Tuple")),
-            Token::SeqBegin(Some(2)),
-            Token::Signed(1),
-            Token::Signed(2),
-            Token::SeqEnd,
+            Token::MapBegin(None),
+            Token::Str(String::from("a")),
+            Token::Signed32(1),
             Token::MapEnd,
         ],
         v
     );
+}
 
-    let v = sval::test::tokens(sval::serde::to_value(Tagged::Struct { a: 1, b: 2 }));
+#[test]
+fn sval_derive_flatten_tagged() {
+    use self::SvalToken as Token;
+
+    struct Tagged;
+
+    impl Value for Tagged {
+        fn stream(&self, stream: &mut value::Stream) -> Result<(), value::Error> {
+            stream.tagged(
+                sval::stream::Tag::Custom("nested"),
+                Nested { a: 2, b: "Hello!" },
+            )
+        }
+    }
+
+    #[derive(Value)]
+    struct Flatten {
+        a: i32,
+        #[sval(flatten)]
+        b: Tagged,
+    }
+
+    let v = sval::test::tokens(Flatten { a: 1, b: Tagged });
     assert_eq!(
         vec![
-            Token::MapBegin(Some(1)),
-            Token::Str(String::from("Struct")),
-            Token::MapBegin(Some(2)),
+            Token::MapBegin(None),
             Token::Str(String::from("a")),
-            Token::Signed(1),
+            Token::Signed32(1),
+            Token::Str(String::from("a")),
+            Token::Signed32(2),
             Token::Str(String::from("b")),
-            Token::Signed(2),
-            Token::MapEnd,
+            Token::Str(String::from("Hello!")),
             Token::MapEnd,
         ],
         v
@@ -169,23 +578,827 @@ fn serde_to_sval_tagged() {
 }
 
 #[test]
-fn sval_to_serde_anonymous() {
+fn sval_derive_flatten_to_serde() {
     use self::SerdeToken as Token;
 
     assert_ser_tokens(
-        &sval::serde::to_serialize(Anonymous),
+        &sval::serde::to_serialize(Attributes {
+            skipped: 1,
+            optional: None,
+            flattened: Nested { a: 3, b: "Hello!" },
+            with: 4,
+        }),
         &[
             Token::Map { len: None },
-            Token::I64(1),
-            Token::Map { len: None },
-            Token::I64(2),
-            Token::Seq { len: None },
-            Token::I64(3),
-            Token::SeqEnd,
+            Token::Str("a"),
+            Token::I32(3),
+            Token::Str("b"),
+            Token::Str("Hello!"),
+            Token::Str("with"),
+            Token::Str("4"),
+            Token::MapEnd,
+        ],
+    );
+}
+
+#[test]
+fn sval_derive_rename_all() {
+    use self::SvalToken as Token;
+
+    let v = sval::test::tokens(RenameAll {
+        field_a: 1,
+        field_b: 2,
+    });
+    assert_eq!(
+        vec![
+            Token::StructBegin("RenameAll", 2),
+            Token::StructField(0, "fieldA"),
+            Token::Signed32(1),
+            Token::StructField(1, "b"),
+            Token::Signed32(2),
+            Token::StructEnd,
+        ],
+        v
+    );
+}
+
+#[test]
+fn sval_derive_internally_tagged() {
+    use self::SvalToken as Token;
+
+    let v = sval::test::tokens(Internal::EmptyUnit);
+    assert_eq!(
+        vec![
+            Token::MapBegin(Some(1)),
+            Token::Str(String::from("type")),
+            Token::Str(String::from("empty_unit")),
+            Token::MapEnd,
+        ],
+        v
+    );
+
+    let v = sval::test::tokens(Internal::NewType(Nested { a: 1, b: "Hello!" }));
+    assert_eq!(
+        vec![
+            Token::MapBegin(None),
+            Token::Str(String::from("type")),
+            Token::Str(String::from("new_type")),
+            Token::Str(String::from("a")),
+            Token::Signed32(1),
+            Token::Str(String::from("b")),
+            Token::Str(String::from("Hello!")),
             Token::MapEnd,
-            Token::I64(11),
-            Token::I64(111),
+        ],
+        v
+    );
+
+    let v = sval::test::tokens(Internal::StructFields { a: 1 });
+    assert_eq!(
+        vec![
+            Token::MapBegin(Some(2)),
+            Token::Str(String::from("type")),
+            Token::Str(String::from("struct_fields")),
+            Token::Str(String::from("a")),
+            Token::Signed32(1),
             Token::MapEnd,
         ],
+        v
     );
 }
+
+#[test]
+fn sval_derive_adjacently_tagged() {
+    use self::SvalToken as Token;
+
+    let v = sval::test::tokens(Adjacent::Unit);
+    assert_eq!(
+        vec![
+            Token::MapBegin(Some(1)),
+            Token::Str(String::from("t")),
+            Token::Str(String::from("Unit")),
+            Token::MapEnd,
+        ],
+        v
+    );
+
+    let v = sval::test::tokens(Adjacent::NewType(1));
+    assert_eq!(
+        vec![
+            Token::MapBegin(Some(2)),
+            Token::Str(String::from("t")),
+            Token::Str(String::from("NewType")),
+            Token::Str(String::from("c")),
+            Token::Signed32(1),
+            Token::MapEnd,
+        ],
+        v
+    );
+
+    let v = sval::test::tokens(Adjacent::Tuple(1, 2));
+    assert_eq!(
+        vec![
+            Token::MapBegin(Some(2)),
+            Token::Str(String::from("t")),
+            Token::Str(String::from("Tuple")),
+            Token::Str(String::from("c")),
+            Token::SeqBegin(Some(2)),
+            Token::Signed32(1),
+            Token::Signed32(2),
+            Token::SeqEnd,
+            Token::MapEnd,
+        ],
+        v
+    );
+
+    let v = sval::test::tokens(Adjacent::Struct { a: 1 });
+    assert_eq!(
+        vec![
+            Token::MapBegin(Some(2)),
+            Token::Str(String::from("t")),
+            Token::Str(String::from("Struct")),
+            Token::Str(String::from("c")),
+            Token::MapBegin(Some(1)),
+            Token::Str(String::from("a")),
+            Token::Signed32(1),
+            Token::MapEnd,
+            Token::MapEnd,
+        ],
+        v
+    );
+}
+
+#[test]
+fn sval_derive_untagged() {
+    use self::SvalToken as Token;
+
+    let v = sval::test::tokens(Untagged::Unit);
+    assert_eq!(vec![Token::Unit], v);
+
+    let v = sval::test::tokens(Untagged::NewType(1));
+    assert_eq!(vec![Token::Signed32(1)], v);
+
+    let v = sval::test::tokens(Untagged::Tuple(1, 2));
+    assert_eq!(
+        vec![
+            Token::SeqBegin(Some(2)),
+            Token::Signed32(1),
+            Token::Signed32(2),
+            Token::SeqEnd,
+        ],
+        v
+    );
+
+    let v = sval::test::tokens(Untagged::Struct { a: 1 });
+    assert_eq!(
+        vec![
+            Token::MapBegin(Some(1)),
+            Token::Str(String::from("a")),
+            Token::Signed32(1),
+            Token::MapEnd,
+        ],
+        v
+    );
+}
+
+#[test]
+fn sval_derive_from_serde() {
+    use self::SvalToken as Token;
+
+    let v = sval::test::tokens(Tagged::NewType(1));
+    assert_eq!(
+        vec![
+            Token::NewtypeVariantBegin("Tagged", 1, "NewType"),
+            Token::Signed32(1),
+            Token::NewtypeVariantEnd,
+        ],
+        v
+    );
+}
+
+#[test]
+fn serde_to_sval_tagged() {
+    use self::SvalToken as Token;
+
+    let v = sval::test::tokens(sval::serde::to_value(Tagged::Unit));
+    assert_eq!(vec![Token::UnitVariant("Tagged", 0, "Unit")], v);
+
+    let v = sval::test::tokens(sval::serde::to_value(Tagged::NewType(1)));
+    assert_eq!(
+        vec![
+            Token::NewtypeVariantBegin("Tagged", 1, "NewType"),
+            Token::Signed32(1),
+            Token::NewtypeVariantEnd,
+        ],
+        v
+    );
+
+    let v = sval::test::tokens(sval::serde::to_value(Tagged::Tuple(1, 2)));
+    assert_eq!(
+        vec![
+            Token::TupleVariantBegin("Tagged", 2, "Tuple", 2),
+            Token::Signed32(1),
+            Token::Signed32(2),
+            Token::TupleVariantEnd,
+        ],
+        v
+    );
+
+    let v = sval::test::tokens(sval::serde::to_value(Tagged::Struct { a: 1, b: 2 }));
+    assert_eq!(
+        vec![
+            Token::StructVariantBegin("Tagged", 3, "Struct", 2),
+            Token::StructVariantField(0, "a"),
+            Token::Signed32(1),
+            Token::StructVariantField(1, "b"),
+            Token::Signed32(2),
+            Token::StructVariantEnd,
+        ],
+        v
+    );
+
+    // Skipped fields still count towards the index of later ones
+    #[derive(Serialize)]
+    enum Skipped {
+        Struct {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            a: Option<i32>,
+            b: i32,
+        },
+    }
+
+    let v = sval::test::tokens(sval::serde::to_value(Skipped::Struct { a: None, b: 2 }));
+    assert_eq!(
+        vec![
+            Token::StructVariantBegin("Skipped", 0, "Struct", 1),
+            Token::StructVariantField(1, "b"),
+            Token::Signed32(2),
+            Token::StructVariantEnd,
+        ],
+        v
+    );
+}
+
+#[test]
+fn serde_to_sval_struct() {
+    use self::SvalToken as Token;
+
+    let v = sval::test::tokens(sval::serde::to_value(Nested { a: 1, b: "Hello!" }));
+    assert_eq!(
+        vec![
+            Token::StructBegin("Nested", 2),
+            Token::StructField(0, "a"),
+            Token::Signed32(1),
+            Token::StructField(1, "b"),
+            Token::Str(String::from("Hello!")),
+            Token::StructEnd,
+        ],
+        v
+    );
+
+    let v = sval::test::tokens(sval::serde::to_value(Tuple(1, "Hello!", NewType(2))));
+    assert_eq!(
+        vec![
+            Token::TupleBegin(Some("Tuple"), 3),
+            Token::Signed32(1),
+            Token::Str(String::from("Hello!")),
+            Token::Signed32(2),
+            Token::TupleEnd,
+        ],
+        v
+    );
+
+    let v = sval::test::tokens(sval::serde::to_value((1, 2)));
+    assert_eq!(
+        vec![
+            Token::TupleBegin(None, 2),
+            Token::Signed32(1),
+            Token::Signed32(2),
+            Token::TupleEnd,
+        ],
+        v
+    );
+}
+
+#[test]
+fn serde_to_sval_optional() {
+    use self::SvalToken as Token;
+
+    let v = sval::test::tokens(sval::serde::to_value(Some(1)));
+    assert_eq!(vec![Token::SomeBegin, Token::Signed32(1), Token::SomeEnd], v);
+
+    let v = sval::test::tokens(sval::serde::to_value(Option::None::<i32>));
+    assert_eq!(vec![Token::None], v);
+
+    let v = sval::test::tokens(sval::serde::to_value(()));
+    assert_eq!(vec![Token::Unit], v);
+}
+
+#[test]
+fn sval_to_serde_optional() {
+    use self::SerdeToken as Token;
+
+    let some = [Token::Some, Token::I32(1)];
+
+    assert_ser_tokens(&sval::serde::to_serialize(Some(1)), &some);
+    assert_ser_tokens(
+        &sval::serde::to_serialize(value::OwnedValue::from_value(Some(1))),
+        &some,
+    );
+
+    let nested = [
+        Token::Seq { len: Some(2) },
+        Token::Some,
+        Token::Some,
+        Token::I32(1),
+        Token::None,
+        Token::SeqEnd,
+    ];
+
+    assert_ser_tokens(
+        &sval::serde::to_serialize(vec![Some(Some(1)), None]),
+        &nested,
+    );
+    assert_ser_tokens(
+        &sval::serde::to_serialize(value::OwnedValue::from_value(vec![Some(Some(1)), None])),
+        &nested,
+    );
+
+    assert_ser_tokens(
+        &sval::serde::to_serialize(Option::None::<i32>),
+        &[Token::None],
+    );
+    assert_ser_tokens(&sval::serde::to_serialize(()), &[Token::Unit]);
+}
+
+#[test]
+fn stream_nested_optional() {
+    #[derive(Value)]
+    struct Node {
+        id: u32,
+        next: Option<Box<Node>>,
+    }
+
+    let mut node = Node { id: 0, next: None };
+    for id in 1..10 {
+        node = Node {
+            id,
+            next: Some(Box::new(node)),
+        };
+    }
+
+    // Optional values don't count towards the nesting limit
+    let mut stream = BorrowedStrs::default();
+    sval::stream(&node, &mut stream).unwrap();
+
+    assert_eq!(20, stream.fields.len());
+}
+
+#[test]
+fn stream_nested_tagged() {
+    struct Tagged<T>(T);
+
+    impl<T: Value> Value for Tagged<T> {
+        fn stream(&self, stream: &mut value::Stream) -> Result<(), value::Error> {
+            stream.tagged(sval::stream::Tag::Custom("node"), &self.0)
+        }
+    }
+
+    #[derive(Value)]
+    struct Node {
+        id: u32,
+        next: Tagged<Option<Box<Node>>>,
+    }
+
+    let mut node = Node {
+        id: 0,
+        next: Tagged(None),
+    };
+    for id in 1..10 {
+        node = Node {
+            id,
+            next: Tagged(Some(Box::new(node))),
+        };
+    }
+
+    // Tagged values don't count towards the nesting limit
+    let mut stream = BorrowedStrs::default();
+    sval::stream(&node, &mut stream).unwrap();
+
+    assert_eq!(20, stream.fields.len());
+}
+
+#[test]
+fn sval_to_serde_tags() {
+    use self::SerdeToken as Token;
+
+    struct Tags;
+
+    impl Value for Tags {
+        fn stream(&self, stream: &mut value::Stream) -> Result<(), value::Error> {
+            stream.seq_begin(Some(2))?;
+
+            stream
+                .seq_elem_begin()?
+                .tagged(sval::stream::Tag::Uuid, "a0a2a0a2-a0a2-a0a2-a0a2-a0a2a0a2a0a2")?;
+
+            // The optional value is buffered, so the tag is buffered too
+            stream
+                .seq_elem_begin()?
+                .some_begin()?
+                .tagged_begin(sval::stream::Tag::Custom("point"))?
+                .tuple_begin(None, 2)?;
+            stream.tuple_elem(0, 1)?;
+            stream.tuple_elem(1, 2)?;
+            stream.tuple_end()?;
+            stream.tagged_end()?;
+            stream.some_end()?;
+
+            stream.seq_end()
+        }
+    }
+
+    let tokens = [
+        Token::Seq { len: Some(2) },
+        Token::Str("a0a2a0a2-a0a2-a0a2-a0a2-a0a2a0a2a0a2"),
+        Token::Some,
+        Token::Tuple { len: 2 },
+        Token::I32(1),
+        Token::I32(2),
+        Token::TupleEnd,
+        Token::SeqEnd,
+    ];
+
+    assert_ser_tokens(&sval::serde::to_serialize(Tags), &tokens);
+    assert_ser_tokens(
+        &sval::serde::to_serialize(value::OwnedValue::from_value(Tags)),
+        &tokens,
+    );
+}
+
+#[test]
+fn serde_to_sval_to_serde_tagged() {
+    use self::SerdeToken as Token;
+
+    let unit = [Token::UnitVariant {
+        name: "Tagged",
+        variant: "Unit",
+    }];
+
+    let newtype = [
+        Token::NewtypeVariant {
+            name: "Tagged",
+            variant: "NewType",
+        },
+        Token::I32(1),
+    ];
+
+    let tuple = [
+        Token::TupleVariant {
+            name: "Tagged",
+            variant: "Tuple",
+            len: 2,
+        },
+        Token::I32(1),
+        Token::I32(2),
+        Token::TupleVariantEnd,
+    ];
+
+    let strct = [
+        Token::StructVariant {
+            name: "Tagged",
+            variant: "Struct",
+            len: 2,
+        },
+        Token::Str("a"),
+        Token::I32(1),
+        Token::Str("b"),
+        Token::I32(2),
+        Token::StructVariantEnd,
+    ];
+
+    assert_ser_tokens(&Tagged::Unit, &unit);
+    assert_ser_tokens(&Tagged::NewType(1), &newtype);
+    assert_ser_tokens(&Tagged::Tuple(1, 2), &tuple);
+    assert_ser_tokens(&Tagged::Struct { a: 1, b: 2 }, &strct);
+
+    for (value, tokens) in [
+        (Tagged::Unit, &unit[..]),
+        (Tagged::NewType(1), &newtype[..]),
+        (Tagged::Tuple(1, 2), &tuple[..]),
+        (Tagged::Struct { a: 1, b: 2 }, &strct[..]),
+    ] {
+        // Stream the variant directly
+        assert_ser_tokens(
+            &sval::serde::to_serialize(sval::serde::to_value(&value)),
+            tokens,
+        );
+
+        // Stream the variant through a buffer
+        assert_ser_tokens(
+            &sval::serde::to_serialize(value::OwnedValue::from_value(sval::serde::to_value(
+                &value,
+            ))),
+            tokens,
+        );
+    }
+}
+
+#[test]
+fn sval_to_serde_anonymous() {
+    use self::SerdeToken as Token;
+
+    assert_ser_tokens(
+        &sval::serde::to_serialize(Anonymous),
+        &[
+            Token::Map { len: None },
+            Token::I64(1),
+            Token::Map { len: None },
+            Token::I32(2),
+            Token::Seq { len: None },
+            Token::I64(3),
+            Token::SeqEnd,
+            Token::MapEnd,
+            Token::I32(11),
+            Token::I32(111),
+            Token::MapEnd,
+        ],
+    );
+}
+
+#[test]
+fn serde_to_sval_to_serde_numbers() {
+    use self::SerdeToken as Token;
+
+    fn assert_roundtrip<T: serde::Serialize + Value>(v: T, token: Token) {
+        assert_ser_tokens(&v, &[token]);
+        assert_ser_tokens(&sval::serde::to_serialize(&v), &[token]);
+        assert_ser_tokens(
+            &sval::serde::to_serialize(sval::serde::to_value(&v)),
+            &[token],
+        );
+        assert_ser_tokens(
+            &sval::serde::to_serialize(value::OwnedValue::from_value(&v)),
+            &[token],
+        );
+    }
+
+    assert_roundtrip(1u8, Token::U8(1));
+    assert_roundtrip(1u16, Token::U16(1));
+    assert_roundtrip(1u32, Token::U32(1));
+    assert_roundtrip(1u64, Token::U64(1));
+    assert_roundtrip(1i8, Token::I8(1));
+    assert_roundtrip(1i16, Token::I16(1));
+    assert_roundtrip(1i32, Token::I32(1));
+    assert_roundtrip(1i64, Token::I64(1));
+    assert_roundtrip(1.5f32, Token::F32(1.5));
+    assert_roundtrip(1.5f64, Token::F64(1.5));
+}
+
+#[test]
+fn serde_to_sval_bytes() {
+    use self::SvalToken as Token;
+
+    let v = sval::test::tokens(sval::serde::to_value(SerdeBytes(&[1, 2, 3])));
+    assert_eq!(vec![Token::Bytes(vec![1, 2, 3])], v);
+}
+
+#[test]
+fn sval_to_serde_bytes() {
+    use self::SerdeToken as Token;
+
+    assert_ser_tokens(
+        &sval::serde::to_serialize(value::Bytes(&[1u8, 2, 3][..])),
+        &[Token::Bytes(&[1, 2, 3])],
+    );
+
+    assert_ser_tokens(
+        &sval::serde::to_serialize(vec![value::Bytes(vec![1u8, 2, 3])]),
+        &[
+            Token::Seq { len: Some(1) },
+            Token::Bytes(&[1, 2, 3]),
+            Token::SeqEnd,
+        ],
+    );
+}
+
+/**
+A stream that keeps the strings borrowed from the value being streamed.
+*/
+#[derive(Default)]
+struct BorrowedStrs<'v> {
+    borrowed: Vec<&'v str>,
+    owned: Vec<String>,
+    fields: Vec<&'static str>,
+}
+
+impl<'v> sval::Stream for BorrowedStrs<'v> {
+    fn fmt(&mut self, args: sval::stream::Arguments) -> Result<(), sval::stream::Error> {
+        self.owned.push(args.to_string());
+
+        Ok(())
+    }
+
+    fn str(&mut self, v: &str) -> Result<(), sval::stream::Error> {
+        self.owned.push(v.to_owned());
+
+        Ok(())
+    }
+
+    fn i64(&mut self, _: i64) -> Result<(), sval::stream::Error> {
+        Ok(())
+    }
+
+    fn map_begin(&mut self, _: Option<usize>) -> Result<(), sval::stream::Error> {
+        Ok(())
+    }
+
+    fn map_key(&mut self) -> Result<(), sval::stream::Error> {
+        Ok(())
+    }
+
+    fn map_value(&mut self) -> Result<(), sval::stream::Error> {
+        Ok(())
+    }
+
+    fn map_end(&mut self) -> Result<(), sval::stream::Error> {
+        Ok(())
+    }
+
+    fn seq_begin(&mut self, _: Option<usize>) -> Result<(), sval::stream::Error> {
+        Ok(())
+    }
+
+    fn seq_elem(&mut self) -> Result<(), sval::stream::Error> {
+        Ok(())
+    }
+
+    fn seq_end(&mut self) -> Result<(), sval::stream::Error> {
+        Ok(())
+    }
+
+    fn struct_begin(&mut self, _: &'static str, _: usize) -> Result<(), sval::stream::Error> {
+        Ok(())
+    }
+
+    fn struct_field(&mut self, _: u32, field: &'static str) -> Result<(), sval::stream::Error> {
+        self.fields.push(field);
+
+        Ok(())
+    }
+
+    fn struct_end(&mut self) -> Result<(), sval::stream::Error> {
+        Ok(())
+    }
+}
+
+impl<'v> sval::stream::BorrowedStream<'v> for BorrowedStrs<'v> {
+    fn str_borrowed(&mut self, v: &'v str) -> Result<(), sval::stream::Error> {
+        self.borrowed.push(v);
+
+        Ok(())
+    }
+}
+
+#[test]
+fn stream_borrowed() {
+    #[derive(Value)]
+    struct Borrowed {
+        id: String,
+        tags: Vec<String>,
+        #[sval(with = "stream_as_str")]
+        count: i32,
+        nested: Option<Box<BorrowedNewType>>,
+    }
+
+    #[derive(Value)]
+    struct BorrowedNewType(String);
+
+    let v = Borrowed {
+        id: "a".to_owned(),
+        tags: vec!["b".to_owned(), "c".to_owned()],
+        count: 1,
+        nested: Some(Box::new(BorrowedNewType("d".to_owned()))),
+    };
+
+    let mut stream = BorrowedStrs::default();
+    sval::stream_borrowed(&v, &mut stream).unwrap();
+
+    assert_eq!(vec!["a", "b", "c", "d"], stream.borrowed);
+    assert_eq!(vec!["1"], stream.owned);
+    assert_eq!(vec!["id", "tags", "count", "nested"], stream.fields);
+
+    // Struct variant fields are lent too
+    #[derive(Value)]
+    enum BorrowedEnum {
+        Struct {
+            id: String,
+            #[sval(with = "stream_as_str")]
+            count: i32,
+        },
+    }
+
+    let e = BorrowedEnum::Struct {
+        id: "e".to_owned(),
+        count: 2,
+    };
+
+    let mut stream = BorrowedStrs::default();
+    sval::stream_borrowed(&e, &mut stream).unwrap();
+
+    assert_eq!(vec!["e"], stream.borrowed);
+
+    // Values that are streamed by value can't lend their strings
+    let mut stream = BorrowedStrs::default();
+    sval::stream_borrowed(&Anonymous, &mut stream).unwrap();
+
+    assert!(stream.borrowed.is_empty());
+
+    // Regular streams receive borrowed strings as regular strings
+    let mut stream = BorrowedStrs::default();
+    sval::stream(&v, &mut stream).unwrap();
+
+    assert!(stream.borrowed.is_empty());
+    assert_eq!(vec!["a", "b", "c", "1", "d"], stream.owned);
+}
+
+#[test]
+fn stream_locals() {
+    struct Locals {
+        id: u32,
+    }
+
+    impl sval::Value for Locals {
+        fn stream(&self, stream: &mut sval::value::Stream) -> Result<(), sval::value::Error> {
+            let id = self.id.to_string();
+
+            stream.seq_begin(Some(2))?;
+            stream.seq_elem(&id)?;
+            stream.seq_elem(format!("#{}", self.id))?;
+            stream.seq_end()
+        }
+    }
+
+    #[derive(Value)]
+    struct Outer {
+        id: String,
+        locals: Locals,
+    }
+
+    let v = Outer {
+        id: "a".to_owned(),
+        locals: Locals { id: 1 },
+    };
+
+    // Values that don't lend their strings are still streamed
+    let mut stream = BorrowedStrs::default();
+    sval::stream_borrowed(&v, &mut stream).unwrap();
+
+    assert_eq!(vec!["a"], stream.borrowed);
+    assert_eq!(vec!["1", "#1"], stream.owned);
+}
+
+#[test]
+fn stream_tuple_variant_elem_index() {
+    // A stream that keeps the index of each tuple variant element
+    #[derive(Default)]
+    struct Indexes(Vec<u32>);
+
+    impl sval::Stream for Indexes {
+        fn fmt(&mut self, _: sval::stream::Arguments) -> Result<(), sval::stream::Error> {
+            Ok(())
+        }
+
+        fn tuple_variant_elem(&mut self, index: u32) -> Result<(), sval::stream::Error> {
+            self.0.push(index);
+
+            Ok(())
+        }
+    }
+
+    #[derive(Value, Serialize)]
+    enum Variant {
+        Tuple(i32, i32, i32),
+    }
+
+    let v = Variant::Tuple(1, 2, 3);
+
+    let mut stream = Indexes::default();
+    sval::stream(&v, &mut stream).unwrap();
+    assert_eq!(vec![0, 1, 2], stream.0);
+
+    // The index is kept when the value is buffered
+    let mut stream = Indexes::default();
+    sval::stream(value::OwnedValue::from_value(&v), &mut stream).unwrap();
+    assert_eq!(vec![0, 1, 2], stream.0);
+
+    // The index is produced for values converted from serde
+    let mut stream = Indexes::default();
+    sval::stream(sval::serde::to_value(&v), &mut stream).unwrap();
+    assert_eq!(vec![0, 1, 2], stream.0);
+}
+
+#[test]
+fn sval_derive_errors() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("ui/*.rs");
+}
@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate sval;
+
+#[derive(Value)]
+#[sval(content = "c")]
+enum Data {
+    A(i32),
+}
+
+fn main() {}
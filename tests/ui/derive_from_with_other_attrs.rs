@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate sval;
+
+#[derive(Value)]
+#[sval(derive_from = "serde", rename_all = "camelCase")]
+struct Data {
+    a: i32,
+}
+
+fn main() {}
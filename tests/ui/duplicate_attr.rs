@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate sval;
+
+#[derive(Value)]
+struct Data {
+    #[sval(rename = "b", rename = "c")]
+    a: i32,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+#[macro_use]
+extern crate sval;
+
+#[derive(Value)]
+struct Inner {
+    a: i32,
+}
+
+#[derive(Value)]
+struct Data {
+    #[sval(flatten, rename = "b")]
+    inner: Inner,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+#[macro_use]
+extern crate sval;
+
+#[derive(Value)]
+struct Inner {
+    a: i32,
+}
+
+#[derive(Value)]
+enum Data {
+    A {
+        #[sval(flatten)]
+        inner: Inner,
+    },
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate sval;
+
+#[derive(Value)]
+struct Data {
+    #[sval(skip_if = "not a path")]
+    a: Option<i32>,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate sval;
+
+#[derive(Value)]
+#[sval(tag = "t")]
+struct Data {
+    a: i32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+#[macro_use]
+extern crate sval;
+
+#[derive(Value)]
+union Data {
+    a: i32,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate sval;
+
+#[derive(Value)]
+struct Data(#[sval(skip)] i32, i32);
+
+fn main() {}
@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate sval;
+
+#[derive(Value)]
+struct Data {
+    #[sval(unknown)]
+    a: i32,
+}
+
+fn main() {}
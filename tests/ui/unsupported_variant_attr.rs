@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate sval;
+
+#[derive(Value)]
+enum Data {
+    #[sval(skip)]
+    A,
+}
+
+fn main() {}